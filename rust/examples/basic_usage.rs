@@ -5,7 +5,7 @@ use serde_json::json;
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // Create client
-    let mut client = WinCCUnifiedClient::new(
+    let client = WinCCUnifiedClient::new(
         "http://DESKTOP-KHLB071:4000/graphql"
     );
     
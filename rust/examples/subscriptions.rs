@@ -8,7 +8,6 @@
 use std::env;
 use std::time::Duration;
 use std::process::Command;
-use tokio;
 use winccua_graphql_client::{GraphQLWSClient, SubscriptionCallbacks, subscriptions};
 use serde_json::Value;
 
@@ -23,7 +22,7 @@ async fn get_token_from_login(http_url: &str, username: &str, password: &str) ->
         .arg("-H")
         .arg("Content-Type: application/json")
         .arg("-d")
-        .arg(&format!(r#"{{"query":"mutation Login($username: String!, $password: String!) {{ login(username: $username, password: $password) {{ token error {{ code description }} }} }}","variables":{{"username":"{}","password":"{}"}}}}"#, username, password))
+        .arg(format!(r#"{{"query":"mutation Login($username: String!, $password: String!) {{ login(username: $username, password: $password) {{ token error {{ code description }} }} }}","variables":{{"username":"{}","password":"{}"}}}}"#, username, password))
         .arg(http_url)
         .output()?;
 
@@ -164,7 +163,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
             let priority = alarm_data.get("priority").and_then(|p| p.as_i64()).unwrap_or(0);
             let event_text = alarm_data.get("eventText")
                 .and_then(|t| t.as_array())
-                .and_then(|arr| arr.get(0))
+                .and_then(|arr| arr.first())
                 .and_then(|t| t.as_str())
                 .unwrap_or("No event text");
             
@@ -258,7 +257,7 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
         .arg("-H")
         .arg("Content-Type: application/json")
         .arg("-H")
-        .arg(&format!("Authorization: Bearer {}", token))
+        .arg(format!("Authorization: Bearer {}", token))
         .arg("-d")
         .arg(r#"{"query":"mutation Logout($allSessions: Boolean) { logout(allSessions: $allSessions) }","variables":{"allSessions":false}}"#)
         .arg(&http_url)
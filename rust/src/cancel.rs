@@ -0,0 +1,68 @@
+//! Giving up on blocking requests
+
+use std::sync::{Arc, Mutex, Weak};
+
+/// Token to stop waiting for requests started with `WinCCUnifiedClient::run_cancellable`
+///
+/// Cancelling releases the waiting thread but does not abort the HTTP request, which completes
+/// in the background. Clones share their state, so a clone can be kept by the UI and cancelled
+/// from there while another thread waits for the request. A cancelled token stays cancelled; use a
+/// new token for the next request.
+///
+/// ```
+/// use winccua_graphql_client::CancelToken;
+///
+/// let token = CancelToken::new();
+/// let ui_token = token.clone();
+/// ui_token.cancel();
+/// assert!(token.is_cancelled());
+/// ```
+#[derive(Clone, Default)]
+pub struct CancelToken {
+    state: Arc<Mutex<CancelState>>,
+}
+
+#[derive(Default)]
+struct CancelState {
+    cancelled: bool,
+    waiters: Vec<Weak<dyn Fn() + Send + Sync>>,
+}
+
+impl CancelToken {
+    /// Create a token that is not cancelled
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Cancel the requests waiting on this token
+    pub fn cancel(&self) {
+        let waiters = {
+            let mut state = self.state.lock().unwrap();
+            state.cancelled = true;
+            std::mem::take(&mut state.waiters)
+        };
+        for waiter in waiters.iter().filter_map(Weak::upgrade) {
+            (waiter)();
+        }
+    }
+
+    /// Whether `cancel` was called
+    pub fn is_cancelled(&self) -> bool {
+        self.state.lock().unwrap().cancelled
+    }
+
+    /// Call `waiter` on cancellation, as long as the caller keeps it alive
+    pub(crate) fn on_cancel(&self, waiter: &Arc<dyn Fn() + Send + Sync>) {
+        let mut state = self.state.lock().unwrap();
+        if !state.cancelled {
+            state.waiters.retain(|waiter| waiter.strong_count() > 0);
+            state.waiters.push(Arc::downgrade(waiter));
+        }
+    }
+}
+
+impl std::fmt::Debug for CancelToken {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("CancelToken").field("cancelled", &self.is_cancelled()).finish()
+    }
+}
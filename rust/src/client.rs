@@ -1,24 +1,1542 @@
 //! Main WinCC Unified GraphQL client implementation
 
-use crate::error::{WinCCError, WinCCResult};
-use crate::graphql::{mutations, queries, subscriptions};
-use crate::graphql_ws::{GraphQLWSClient, SubscriptionCallbacks, Subscription};
+use crate::cancel::CancelToken;
+use crate::error::{GraphQLError, WinCCError, WinCCResult};
+use crate::filter::{cql_string, AlarmFilterBuilder};
+use crate::graphql::{self, mutations, queries, subscriptions};
+use crate::metrics::Metrics;
+use crate::retry::{self, RetryPolicy};
+use crate::transport::Transport;
+use crate::graphql_ws::{BlockingSubscription, CloseInfo, ConnectionState, GraphQLWSClient, OverflowPolicy, SubscriptionCallbacks, TypedSubscriptionCallbacks, Subscription, SubscriptionStream, TokenUpdater, WsProtocol, DEFAULT_CONNECT_TIMEOUT, DEFAULT_DISPATCH_BUFFER, DEFAULT_PING_INTERVAL, DEFAULT_USER_AGENT, validate_ws_url};
 use crate::types::*;
-use reqwest::blocking::Client;
-use reqwest::header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
+use reqwest::blocking::{Client, ClientBuilder};
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue, AUTHORIZATION, CONTENT_TYPE};
 use serde_json::{json, Value};
-use std::collections::HashMap;
+use std::collections::{HashMap, HashSet, VecDeque};
+use std::sync::{Arc, Condvar, Mutex, RwLock};
+use std::thread::JoinHandle;
+use std::time::{Duration, Instant};
+use chrono::{DateTime, Utc};
+use tokio::sync::watch;
+use url::Url;
+
+/// Fraction of the remaining session lifetime after which the session is extended
+const AUTO_EXTEND_LIFETIME_FRACTION: f64 = 0.8;
+
+/// Delay before retrying a failed automatic session extension
+const AUTO_EXTEND_RETRY_DELAY: Duration = Duration::from_secs(30);
+
+/// Language used by the convenience methods until another one is set
+const DEFAULT_LANGUAGE: &str = "en-US";
+
+/// Default margin before the session expiry at which `is_token_expired` reports the token as expired
+const DEFAULT_TOKEN_EXPIRY_SKEW: Duration = Duration::from_secs(30);
+
+/// Root certificate to trust in addition to the system trust store
+#[derive(Debug, Clone)]
+enum RootCertificate {
+    Pem(Vec<u8>),
+    Der(Vec<u8>),
+}
+
+impl RootCertificate {
+    fn to_reqwest(&self) -> WinCCResult<reqwest::Certificate> {
+        Ok(match self {
+            RootCertificate::Pem(pem) => reqwest::Certificate::from_pem(pem)?,
+            RootCertificate::Der(der) => reqwest::Certificate::from_der(der)?,
+        })
+    }
+
+    fn to_native_tls(&self) -> WinCCResult<native_tls::Certificate> {
+        match self {
+            RootCertificate::Pem(pem) => native_tls::Certificate::from_pem(pem),
+            RootCertificate::Der(der) => native_tls::Certificate::from_der(der),
+        }
+        .map_err(|e| WinCCError::InvalidParameter(format!("Invalid root certificate: {}", e)))
+    }
+}
+
+/// Client certificate and private key for mutual TLS
+#[derive(Clone)]
+enum TlsIdentity {
+    Pkcs12 { der: Vec<u8>, password: String },
+    Pkcs8 { cert_pem: Vec<u8>, key_pem: Vec<u8> },
+}
+
+impl std::fmt::Debug for TlsIdentity {
+    // Keeps the password and key material out of debug output
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            TlsIdentity::Pkcs12 { .. } => f.write_str("TlsIdentity::Pkcs12"),
+            TlsIdentity::Pkcs8 { .. } => f.write_str("TlsIdentity::Pkcs8"),
+        }
+    }
+}
+
+impl TlsIdentity {
+    fn to_reqwest(&self) -> WinCCResult<reqwest::Identity> {
+        Ok(match self {
+            TlsIdentity::Pkcs12 { der, password } => reqwest::Identity::from_pkcs12_der(der, password)?,
+            TlsIdentity::Pkcs8 { cert_pem, key_pem } => reqwest::Identity::from_pkcs8_pem(cert_pem, key_pem)?,
+        })
+    }
+
+    fn to_native_tls(&self) -> WinCCResult<native_tls::Identity> {
+        match self {
+            TlsIdentity::Pkcs12 { der, password } => native_tls::Identity::from_pkcs12(der, password),
+            TlsIdentity::Pkcs8 { cert_pem, key_pem } => native_tls::Identity::from_pkcs8(cert_pem, key_pem),
+        }
+        .map_err(|e| WinCCError::InvalidParameter(format!("Invalid client identity: {}", e)))
+    }
+}
+
+/// Builder for [`WinCCUnifiedClient`]
+/// 
+/// Configures the inner HTTP client (timeouts, certificate validation) and the
+/// optional WebSocket URL and token before the client is created. TLS settings
+/// apply to both `https://` requests and `wss://` subscriptions.
+#[derive(Debug, Clone, Default)]
+pub struct WinCCUnifiedClientBuilder {
+    http_url: Option<String>,
+    ws_url: Option<String>,
+    connect_timeout: Option<Duration>,
+    request_timeout: Option<Duration>,
+    token: Option<String>,
+    danger_accept_invalid_certs: bool,
+    root_certificates: Vec<RootCertificate>,
+    identity: Option<TlsIdentity>,
+    proxies: Vec<reqwest::Proxy>,
+    pool_max_idle_per_host: Option<usize>,
+    disable_compression: bool,
+    user_agent: Option<String>,
+    http_client: Option<Client>,
+    ws_proxy: Option<String>,
+    ws_protocol: WsProtocol,
+    ws_connect_timeout: Option<Duration>,
+    ws_ping_interval: Option<Duration>,
+    ws_idle_timeout: Option<Duration>,
+    ws_max_message_size: Option<usize>,
+    ws_max_frame_size: Option<usize>,
+    ws_dispatch_buffer: Option<usize>,
+    ws_overflow_policy: OverflowPolicy,
+    token_expiry_skew: Option<Duration>,
+    prevent_auto_logoff: bool,
+    max_direct_read_batch: Option<usize>,
+    direct_read_chunk_size: Option<usize>,
+    retry_policy: Option<RetryPolicy>,
+    transport: Option<CustomTransport>,
+    metrics: Option<MetricsHook>,
+}
+
+/// Custom transport set with `WinCCUnifiedClientBuilder::transport`
+#[derive(Clone)]
+struct CustomTransport(Arc<dyn Transport>);
+
+impl std::fmt::Debug for CustomTransport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("CustomTransport")
+    }
+}
+
+/// Metrics hooks set with `WinCCUnifiedClientBuilder::metrics`
+#[derive(Clone)]
+struct MetricsHook(Arc<dyn Metrics>);
+
+impl std::fmt::Debug for MetricsHook {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str("MetricsHook")
+    }
+}
+
+impl WinCCUnifiedClientBuilder {
+    /// Create a new builder with default settings (reqwest default timeouts, certificate validation enabled)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Set the HTTP URL for GraphQL queries and mutations (required)
+    pub fn http_url(mut self, http_url: &str) -> Self {
+        self.http_url = Some(http_url.to_string());
+        self
+    }
+
+    /// Set the WebSocket URL for GraphQL subscriptions
+    pub fn ws_url(mut self, ws_url: &str) -> Self {
+        self.ws_url = Some(ws_url.to_string());
+        self
+    }
+
+    /// Set the timeout for establishing the HTTP connection
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the total timeout for each HTTP request
+    pub fn request_timeout(mut self, timeout: Duration) -> Self {
+        self.request_timeout = Some(timeout);
+        self
+    }
+
+    /// Set an existing bearer token to use for authentication
+    pub fn token(mut self, token: &str) -> Self {
+        self.token = Some(token.to_string());
+        self
+    }
+
+    /// Accept invalid (e.g. self-signed) TLS certificates on the HTTP and WebSocket connections.
+    /// 
+    /// Only use this for development or in trusted networks. Prefer
+    /// `add_root_certificate_pem` to trust an internal CA.
+    pub fn danger_accept_invalid_certs(mut self, accept: bool) -> Self {
+        self.danger_accept_invalid_certs = accept;
+        self
+    }
+
+    /// Trust an additional root certificate (PEM encoded), e.g. an internal CA
+    /// 
+    /// The certificate is given as raw bytes rather than a `reqwest::Certificate` because
+    /// it is loaded into both the HTTP client and the WebSocket TLS connector.
+    pub fn add_root_certificate_pem(mut self, pem: &[u8]) -> Self {
+        self.root_certificates.push(RootCertificate::Pem(pem.to_vec()));
+        self
+    }
+
+    /// Trust an additional root certificate (DER encoded)
+    pub fn add_root_certificate_der(mut self, der: &[u8]) -> Self {
+        self.root_certificates.push(RootCertificate::Der(der.to_vec()));
+        self
+    }
+
+    /// Present a client certificate for mutual TLS from a DER encoded PKCS#12 archive
+    pub fn identity_pkcs12(mut self, der: &[u8], password: &str) -> Self {
+        self.identity = Some(TlsIdentity::Pkcs12 { der: der.to_vec(), password: password.to_string() });
+        self
+    }
+
+    /// Present a client certificate for mutual TLS from a PEM certificate chain and a PEM PKCS#8 private key
+    pub fn identity_pem(mut self, cert_pem: &[u8], key_pem: &[u8]) -> Self {
+        self.identity = Some(TlsIdentity::Pkcs8 { cert_pem: cert_pem.to_vec(), key_pem: key_pem.to_vec() });
+        self
+    }
+
+    /// Route HTTP requests through a proxy
+    /// 
+    /// Without an explicit proxy the `HTTP_PROXY`, `HTTPS_PROXY`, `ALL_PROXY` and
+    /// `NO_PROXY` environment variables are used. Use `ws_proxy` for subscriptions.
+    pub fn proxy(mut self, proxy: reqwest::Proxy) -> Self {
+        self.proxies.push(proxy);
+        self
+    }
+
+    /// Keep at most `max` idle HTTP connections per host in the connection pool (reqwest default: unlimited)
+    pub fn pool_max_idle_per_host(mut self, max: usize) -> Self {
+        self.pool_max_idle_per_host = Some(max);
+        self
+    }
+
+    /// Request gzip/brotli compressed responses and decompress them transparently (default true)
+    /// 
+    /// Sends `Accept-Encoding: gzip, br`; large results such as alarm lists usually shrink to
+    /// a fraction of their size. Disable it for servers or gateways that mishandle the header.
+    pub fn compression(mut self, enable: bool) -> Self {
+        self.disable_compression = !enable;
+        self
+    }
+
+    /// Set the `User-Agent` header of HTTP requests and the WebSocket upgrade request
+    /// (default `winccua-graphql-client/<version>`, see `DEFAULT_USER_AGENT`)
+    /// 
+    /// Use it to identify the application towards gateways that log or rate-limit per
+    /// User-Agent, e.g. `my-dashboard/1.2.0`.
+    pub fn user_agent(mut self, user_agent: &str) -> Self {
+        self.user_agent = Some(user_agent.to_string());
+        self
+    }
+
+    /// Send queries and mutations with an existing HTTP client instead of creating one
+    /// 
+    /// Clients built from clones of the same `reqwest::blocking::Client` share its connection
+    /// pool, e.g. one pool for many per-tenant clients. Tokens, custom headers and default
+    /// languages remain per `WinCCUnifiedClient`; the token is sent with each request, never
+    /// stored in the shared HTTP client. The HTTP options of this builder (timeouts, proxy,
+    /// pool size, compression, User-Agent, certificates and identity) are not applied to the given
+    /// client; User-Agent, certificates and identity still apply to the WebSocket connection.
+    pub fn http_client(mut self, client: Client) -> Self {
+        self.http_client = Some(client);
+        self
+    }
+
+    /// Tunnel the WebSocket connection through an HTTP proxy (`http://[user:password@]host:port`)
+    /// 
+    /// Without it the proxy is taken from `HTTPS_PROXY`/`HTTP_PROXY`/`ALL_PROXY`,
+    /// honoring `NO_PROXY`.
+    pub fn ws_proxy(mut self, proxy_url: &str) -> Self {
+        self.ws_proxy = Some(proxy_url.to_string());
+        self
+    }
+
+    /// Retry requests that fail with a connection error, timeout or HTTP 5xx status
+    /// 
+    /// By default requests are not retried.
+    pub fn retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+        self
+    }
+
+    /// Select the WebSocket subprotocol for subscriptions (default `graphql-transport-ws`)
+    pub fn ws_protocol(mut self, protocol: WsProtocol) -> Self {
+        self.ws_protocol = protocol;
+        self
+    }
+
+    /// Set the timeout for establishing the WebSocket connection (default 10s)
+    pub fn ws_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.ws_connect_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the WebSocket keepalive ping interval (default 30s)
+    pub fn ws_ping_interval(mut self, interval: Duration) -> Self {
+        self.ws_ping_interval = Some(interval);
+        self
+    }
+
+    /// Close the WebSocket connection if nothing is received for `timeout` (disabled by default),
+    /// see `GraphQLWSClient::with_idle_timeout`
+    pub fn ws_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.ws_idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the largest WebSocket message accepted from the server in bytes (default 64 MiB),
+    /// see `GraphQLWSClient::with_max_message_size`
+    pub fn ws_max_message_size(mut self, size: usize) -> Self {
+        self.ws_max_message_size = Some(size);
+        self
+    }
+
+    /// Set the largest WebSocket frame accepted from the server in bytes (default 16 MiB),
+    /// see `GraphQLWSClient::with_max_frame_size`
+    pub fn ws_max_frame_size(mut self, size: usize) -> Self {
+        self.ws_max_frame_size = Some(size);
+        self
+    }
+
+    /// Set how many notifications are buffered per subscription for its callbacks (default 100),
+    /// see `GraphQLWSClient::with_dispatch_buffer`
+    pub fn ws_dispatch_buffer(mut self, capacity: usize) -> Self {
+        self.ws_dispatch_buffer = Some(capacity);
+        self
+    }
+
+    /// Set what happens when a subscription's buffer is full (default `OverflowPolicy::Backpressure`)
+    pub fn ws_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.ws_overflow_policy = policy;
+        self
+    }
+
+    /// Reject direct reads (`direct_read: true`) of more than `max` tags with `InvalidParameter`
+    /// 
+    /// Direct reads go to the PLC and get slow for large batches; this guards against
+    /// accidentally direct-reading hundreds of tags at once. Cached reads are not limited.
+    pub fn max_direct_read_batch(mut self, max: usize) -> Self {
+        self.max_direct_read_batch = Some(max);
+        self
+    }
+
+    /// Split direct reads into requests of at most `size` tags, sent one after another
+    /// 
+    /// The results are returned in the order of the names, as for a single request.
+    /// Cached reads are always sent in one request.
+    pub fn direct_read_chunk_size(mut self, size: usize) -> Self {
+        self.direct_read_chunk_size = Some(size.max(1));
+        self
+    }
+
+    /// Report the token as expired this long before the session expiry (default 30s),
+    /// see `WinCCUnifiedClient::is_token_expired`
+    pub fn token_expiry_skew(mut self, skew: Duration) -> Self {
+        self.token_expiry_skew = Some(skew);
+        self
+    }
+
+    /// Let the auto-extend thread (`WinCCUnifiedClient::enable_auto_extend`) also extend the
+    /// session before the user's auto-logoff period elapses without activity (default false)
+    /// 
+    /// This keeps an unattended client logged in, which defeats the purpose of the
+    /// auto-logoff configured for the user; only enable it where that is intended.
+    pub fn prevent_auto_logoff(mut self, prevent: bool) -> Self {
+        self.prevent_auto_logoff = prevent;
+        self
+    }
+
+    /// Execute queries and mutations with a custom transport instead of HTTP, e.g. a
+    /// `MockTransport` for tests. The HTTP URL is optional when a transport is set, and no
+    /// HTTP client is created; see `Transport` for what the transport is responsible for.
+    pub fn transport(mut self, transport: impl Transport + 'static) -> Self {
+        self.transport = Some(CustomTransport(Arc::new(transport)));
+        self
+    }
+
+    /// Report every query and mutation (count, latency, errors) to `metrics`, see `Metrics`
+    pub fn metrics(mut self, metrics: impl Metrics + 'static) -> Self {
+        self.metrics = Some(MetricsHook(Arc::new(metrics)));
+        self
+    }
+
+    /// Build the client
+    /// 
+    /// Errors:
+    /// - InvalidParameter - No HTTP URL (or transport) configured, a URL with the wrong scheme,
+    ///   or an invalid certificate or identity
+    /// - HttpError - The HTTP client could not be created
+    pub fn build(self) -> WinCCResult<WinCCUnifiedClient> {
+        let http_url = match (self.http_url, &self.transport) {
+            (Some(http_url), _) => http_url,
+            (None, Some(_)) => String::new(),
+            (None, None) => return Err(WinCCError::InvalidParameter("HTTP URL not configured".to_string())),
+        };
+        if !http_url.is_empty() {
+            validate_http_url(&http_url)?;
+        }
+        if let Some(ws_url) = &self.ws_url {
+            validate_ws_url(ws_url)?;
+        }
+
+        let user_agent = self.user_agent.unwrap_or_else(|| DEFAULT_USER_AGENT.to_string());
+        let mut http_builder = ClientBuilder::new()
+            .user_agent(user_agent.clone())
+            .danger_accept_invalid_certs(self.danger_accept_invalid_certs)
+            .gzip(!self.disable_compression)
+            .brotli(!self.disable_compression);
+        if let Some(timeout) = self.connect_timeout {
+            http_builder = http_builder.connect_timeout(timeout);
+        }
+        if let Some(timeout) = self.request_timeout {
+            http_builder = http_builder.timeout(timeout);
+        }
+
+        if let Some(max) = self.pool_max_idle_per_host {
+            http_builder = http_builder.pool_max_idle_per_host(max);
+        }
+        for proxy in self.proxies {
+            http_builder = http_builder.proxy(proxy);
+        }
+
+        let mut ws_tls_connector = None;
+        if self.danger_accept_invalid_certs || !self.root_certificates.is_empty() || self.identity.is_some() {
+            let mut tls_builder = native_tls::TlsConnector::builder();
+            tls_builder.danger_accept_invalid_certs(self.danger_accept_invalid_certs);
+            for certificate in &self.root_certificates {
+                http_builder = http_builder.add_root_certificate(certificate.to_reqwest()?);
+                tls_builder.add_root_certificate(certificate.to_native_tls()?);
+            }
+            if let Some(identity) = &self.identity {
+                http_builder = http_builder.identity(identity.to_reqwest()?);
+                tls_builder.identity(identity.to_native_tls()?);
+            }
+            ws_tls_connector = Some(tls_builder.build()
+                .map_err(|e| WinCCError::InvalidParameter(format!("Invalid TLS configuration: {}", e)))?);
+        }
+
+        let requests_time_out = self.request_timeout.is_some() || self.http_client.is_some() || self.transport.is_some();
+        Ok(WinCCUnifiedClient {
+            transport: HttpTransport {
+                backend: match (self.transport, self.http_client) {
+                    (Some(CustomTransport(transport)), _) => Backend::Custom(transport),
+                    (None, Some(client)) => Backend::Http(client),
+                    (None, None) => Backend::Http(http_builder.build()?),
+                },
+                http_url,
+                session: Arc::new(RwLock::new(SessionState {
+                    auth_method: self.token.as_ref().map(|_| AuthMethod::Preset),
+                    token: self.token,
+                    ..Default::default()
+                })),
+                default_headers: Arc::new(Mutex::new(HeaderMap::new())),
+                retry_policy: self.retry_policy,
+                metrics: self.metrics,
+                requests_time_out,
+            },
+            ws_url: self.ws_url,
+            ws_ping_interval: self.ws_ping_interval.unwrap_or(DEFAULT_PING_INTERVAL),
+            ws_tls_connector,
+            ws_proxy: self.ws_proxy,
+            ws_protocol: self.ws_protocol,
+            ws_connect_timeout: self.ws_connect_timeout.unwrap_or(DEFAULT_CONNECT_TIMEOUT),
+            ws_idle_timeout: self.ws_idle_timeout,
+            ws_max_message_size: self.ws_max_message_size,
+            ws_max_frame_size: self.ws_max_frame_size,
+            ws_dispatch_buffer: self.ws_dispatch_buffer.unwrap_or(DEFAULT_DISPATCH_BUFFER),
+            ws_overflow_policy: self.ws_overflow_policy,
+            ws_init_params: Arc::new(Mutex::new(HashMap::new())),
+            user_agent,
+            token_expiry_skew: self.token_expiry_skew.unwrap_or(DEFAULT_TOKEN_EXPIRY_SKEW),
+            prevent_auto_logoff: self.prevent_auto_logoff,
+            max_direct_read_batch: self.max_direct_read_batch,
+            direct_read_chunk_size: self.direct_read_chunk_size,
+            languages: Arc::new(RwLock::new(LanguageSettings::default())),
+            ws_client: None,
+            ws_channels: HashMap::new(),
+            auto_extend: Mutex::new(None),
+        })
+    }
+}
 
 /// Main WinCC Unified GraphQL client
 /// 
-/// This client provides synchronous access to the WinCC Unified GraphQL API,
-/// supporting queries and mutations.
-pub struct WinCCUnifiedClient {
-    http_client: Client,
-    http_url: String,
-    ws_url: Option<String>,
-    token: Option<String>,
-    ws_client: Option<GraphQLWSClient>,
+/// This client provides synchronous access to the WinCC Unified GraphQL API,
+/// supporting queries and mutations.
+/// 
+/// The client is `Send + Sync`, so one instance can be shared between threads (e.g. in an
+/// `Arc`) while the token is refreshed with `login`, `extend_session` or `set_token`
+/// concurrently with in-flight queries. `clone()` is cheap: clones share the HTTP
+/// connection pool, the session token and the custom headers. WebSocket connections
+/// (including channels) and the auto-extend thread belong to the instance that started
+/// them; a clone starts without either.
+pub struct WinCCUnifiedClient {
+    transport: HttpTransport,
+    ws_url: Option<String>,
+    ws_ping_interval: Duration,
+    ws_tls_connector: Option<native_tls::TlsConnector>,
+    ws_proxy: Option<String>,
+    ws_protocol: WsProtocol,
+    ws_connect_timeout: Duration,
+    ws_idle_timeout: Option<Duration>,
+    ws_max_message_size: Option<usize>,
+    ws_max_frame_size: Option<usize>,
+    ws_dispatch_buffer: usize,
+    ws_overflow_policy: OverflowPolicy,
+    ws_init_params: Arc<Mutex<HashMap<String, String>>>,
+    user_agent: String,
+    token_expiry_skew: Duration,
+    prevent_auto_logoff: bool,
+    max_direct_read_batch: Option<usize>,
+    direct_read_chunk_size: Option<usize>,
+    languages: Arc<RwLock<LanguageSettings>>,
+    ws_client: Option<GraphQLWSClient>,
+    ws_channels: HashMap<String, GraphQLWSClient>,
+    auto_extend: Mutex<Option<AutoExtendHandle>>,
+}
+
+impl Clone for WinCCUnifiedClient {
+    fn clone(&self) -> Self {
+        Self {
+            transport: self.transport.clone(),
+            ws_url: self.ws_url.clone(),
+            ws_ping_interval: self.ws_ping_interval,
+            ws_tls_connector: self.ws_tls_connector.clone(),
+            ws_proxy: self.ws_proxy.clone(),
+            ws_protocol: self.ws_protocol,
+            ws_connect_timeout: self.ws_connect_timeout,
+            ws_idle_timeout: self.ws_idle_timeout,
+            ws_max_message_size: self.ws_max_message_size,
+            ws_max_frame_size: self.ws_max_frame_size,
+            ws_dispatch_buffer: self.ws_dispatch_buffer,
+            ws_overflow_policy: self.ws_overflow_policy,
+            ws_init_params: self.ws_init_params.clone(),
+            user_agent: self.user_agent.clone(),
+            token_expiry_skew: self.token_expiry_skew,
+            prevent_auto_logoff: self.prevent_auto_logoff,
+            max_direct_read_batch: self.max_direct_read_batch,
+            direct_read_chunk_size: self.direct_read_chunk_size,
+            languages: self.languages.clone(),
+            ws_client: None,
+            ws_channels: HashMap::new(),
+            auto_extend: Mutex::new(None),
+        }
+    }
+}
+
+/// Collects several GraphQL operations and posts them as one array-batched request
+/// 
+/// Created with `WinCCUnifiedClient::batch`.
+pub struct BatchRequest<'a> {
+    transport: &'a HttpTransport,
+    operations: Vec<(String, Value)>,
+}
+
+impl BatchRequest<'_> {
+    /// Add a query or mutation to the batch
+    pub fn add(mut self, query: &str, variables: Option<Value>) -> Self {
+        self.operations.push((query.to_string(), variables.unwrap_or(json!({}))));
+        self
+    }
+    
+    /// Number of operations in the batch
+    pub fn len(&self) -> usize {
+        self.operations.len()
+    }
+    
+    /// Returns true if no operations were added
+    pub fn is_empty(&self) -> bool {
+        self.operations.is_empty()
+    }
+    
+    /// Send all operations in one HTTP request.
+    /// 
+    /// Returns: The `data` object (or the GraphQL errors) of each operation, in the order they were added
+    /// 
+    /// Errors:
+    /// - HttpError - The request could not be sent
+    /// - ServerError - The server responded with an HTTP error status
+    /// - OperationFailed - The server does not support array-batched requests
+    pub fn execute(self) -> WinCCResult<Vec<WinCCResult<Value>>> {
+        if self.operations.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.transport.batch_request(&self.operations)
+    }
+}
+
+/// Logged-in session that is logged out when the guard is dropped
+/// 
+/// Created with `WinCCUnifiedClient::login_scoped`. The client stays usable while the guard
+/// exists. Dropping the guard calls `logout(false)` and ignores its result; call `close` (or
+/// `close_async` in async code) to see whether the logout succeeded. If the client was
+/// already logged out, e.g. with `logout` or `clear_token`, nothing is sent.
+/// 
+/// When dropped inside a tokio runtime, the blocking logout runs on a detached task of the
+/// blocking thread pool instead of stalling the runtime, so it may not complete if the
+/// runtime shuts down right afterwards. Prefer `close_async().await` there.
+pub struct SessionGuard<'a> {
+    client: &'a WinCCUnifiedClient,
+    session: Session,
+    closed: bool,
+}
+
+impl SessionGuard<'_> {
+    /// Session returned by the login
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+    
+    /// Log out now
+    /// 
+    /// Returns: The result of `logout`, Ok(false) if the client was already logged out
+    pub fn close(mut self) -> WinCCResult<bool> {
+        self.closed = true;
+        logout_scoped(self.client)
+    }
+    
+    /// Async variant of `close` that runs the blocking logout on tokio's blocking thread pool
+    pub async fn close_async(mut self) -> WinCCResult<bool> {
+        self.closed = true;
+        self.client.disable_auto_extend();
+        let client = self.client.clone();
+        tokio::task::spawn_blocking(move || logout_scoped(&client))
+            .await
+            .map_err(|e| WinCCError::OperationFailed(format!("Logout task failed: {}", e)))?
+    }
+}
+
+impl Drop for SessionGuard<'_> {
+    fn drop(&mut self) {
+        if self.closed {
+            return;
+        }
+        let report = |result: WinCCResult<bool>| {
+            if let Err(e) = result {
+                log::warn!("Logout of scoped session failed: {}", e);
+            }
+        };
+        match tokio::runtime::Handle::try_current() {
+            Ok(runtime) => {
+                // Blocking requests must not run on a runtime thread
+                self.client.disable_auto_extend();
+                let client = self.client.clone();
+                runtime.spawn_blocking(move || report(logout_scoped(&client)));
+            }
+            Err(_) => report(logout_scoped(self.client)),
+        }
+    }
+}
+
+/// Log out the session of a `SessionGuard` unless the client is already logged out
+fn logout_scoped(client: &WinCCUnifiedClient) -> WinCCResult<bool> {
+    if client.transport.session.read().unwrap().token.is_none() {
+        return Ok(false);
+    }
+    client.logout(false)
+}
+
+/// Iterator over logged tag values in pages of at most `page_size` values per tag
+/// 
+/// Created with `WinCCUnifiedClient::logged_tag_values_paged`. Tags are read one after
+/// another in ascending time order. Each item is one page for one tag; after the last
+/// page of a tag the next tag is read. The iterator ends after the first request error.
+pub struct LoggedTagValuesPages<'a> {
+    client: &'a WinCCUnifiedClient,
+    names: VecDeque<String>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    page_size: i32,
+    cursor: Option<PageCursor>,
+    done: bool,
+}
+
+/// Position of the pager within the values of one tag
+struct PageCursor {
+    name: String,
+    start_time: Option<String>,
+    /// Timestamp of the last returned value and how many values with exactly that
+    /// timestamp were already returned
+    last: Option<(DateTime<Utc>, usize)>,
+}
+
+impl LoggedTagValuesPages<'_> {
+    /// Timestamp of a logged value, None if it has none
+    fn timestamp(value: &LoggedValue) -> WinCCResult<Option<DateTime<Utc>>> {
+        match &value.value {
+            Some(tag_value) => tag_value.timestamp_utc(),
+            None => Ok(None),
+        }
+    }
+    
+    fn next_page(&mut self) -> WinCCResult<Option<LoggedTagValuesResult>> {
+        loop {
+            if self.cursor.is_none() {
+                match self.names.pop_front() {
+                    Some(name) => self.cursor = Some(PageCursor { name, start_time: self.start_time.clone(), last: None }),
+                    None => return Ok(None),
+                }
+            }
+            let cursor = self.cursor.as_mut().expect("cursor is set above");
+            
+            let mut results = self.client.get_logged_tag_values(
+                std::slice::from_ref(&cursor.name),
+                cursor.start_time.as_deref(),
+                self.end_time.as_deref(),
+                self.page_size,
+                SortingMode::TimeAsc,
+                BoundingMode::None,
+            )?;
+            let Some(mut page) = results.pop() else {
+                self.cursor = None;
+                continue;
+            };
+            if page.error.as_ref().is_some_and(ErrorInfo::is_error) {
+                self.cursor = None;
+                return Ok(Some(page));
+            }
+            
+            let values = page.values.take().unwrap_or_default();
+            let fetched = values.len();
+            
+            // startTime is exclusive, so the next page starts 1ms before the last timestamp; values
+            // before it and values at exactly that timestamp which were already returned are skipped
+            let mut skip = 0;
+            if let Some((last_timestamp, count)) = cursor.last {
+                let mut at_last = 0;
+                for value in &values {
+                    match Self::timestamp(value)? {
+                        Some(timestamp) if timestamp < last_timestamp => {}
+                        Some(timestamp) if timestamp == last_timestamp && at_last < count => at_last += 1,
+                        _ => break,
+                    }
+                    skip += 1;
+                }
+            }
+            let values: Vec<LoggedValue> = values.into_iter().skip(skip).collect();
+            
+            if fetched < self.page_size as usize {
+                self.cursor = None;
+            } else if let Some(last_value) = values.last() {
+                let last_timestamp = Self::timestamp(last_value)?;
+                let mut count = 0;
+                for value in values.iter().rev() {
+                    if Self::timestamp(value)? != last_timestamp {
+                        break;
+                    }
+                    count += 1;
+                }
+                if let Some(last_timestamp) = last_timestamp {
+                    if let Some((previous, previous_count)) = cursor.last {
+                        if previous == last_timestamp && count == values.len() {
+                            count += previous_count;
+                        }
+                    }
+                    let start = last_timestamp - chrono::Duration::milliseconds(1);
+                    cursor.start_time = Some(start.to_rfc3339_opts(chrono::SecondsFormat::Millis, true));
+                    cursor.last = Some((last_timestamp, count));
+                } else {
+                    // Without timestamps there is no way to continue
+                    self.cursor = None;
+                }
+            } else if let Some((last_timestamp, _)) = cursor.last {
+                // A full page of values that all share the last timestamp: the values at this
+                // timestamp beyond the page cannot be requested
+                return Err(WinCCError::TagError(format!(
+                    "More than {} values of {} at {}, use a larger page size",
+                    self.page_size, cursor.name, last_timestamp
+                )));
+            } else {
+                self.cursor = None;
+            }
+            
+            if values.is_empty() {
+                continue;
+            }
+            page.values = Some(values);
+            return Ok(Some(page));
+        }
+    }
+}
+
+impl Iterator for LoggedTagValuesPages<'_> {
+    type Item = WinCCResult<LoggedTagValuesResult>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.page_size <= 0 {
+            self.done = true;
+            return Some(Err(WinCCError::InvalidParameter("Page size must be greater than 0".to_string())));
+        }
+        
+        match self.next_page() {
+            Ok(Some(page)) => Some(Ok(page)),
+            Ok(None) => {
+                self.done = true;
+                None
+            }
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Iterator over logged alarms in pages of at most `page_size` alarms
+/// 
+/// Created with `WinCCUnifiedClient::logged_alarms_paged`. The cursor is the
+/// `modificationTime` of the alarm entries, which is the field the server filters the
+/// time window on. The iterator ends after the first request error.
+pub struct LoggedAlarmsPages<'a> {
+    client: &'a WinCCUnifiedClient,
+    system_names: Vec<String>,
+    filter_string: String,
+    filter_language: String,
+    languages: Vec<String>,
+    start_time: Option<String>,
+    end_time: Option<String>,
+    page_size: i32,
+    cursor: Option<AlarmCursor>,
+    done: bool,
+}
+
+/// Latest modification time returned so far and the alarms (name, instance ID) returned with it
+struct AlarmCursor {
+    modification_time: DateTime<Utc>,
+    returned: HashSet<(Option<String>, Option<i32>)>,
+}
+
+impl LoggedAlarmsPages<'_> {
+    fn next_page(&mut self) -> WinCCResult<Option<Vec<LoggedAlarm>>> {
+        loop {
+            // startTime is exclusive, so the next page starts 1ms before the cursor to include
+            // further entries with the same modification time
+            let start_time = match &self.cursor {
+                Some(cursor) => Some((cursor.modification_time - chrono::Duration::milliseconds(1))
+                    .to_rfc3339_opts(chrono::SecondsFormat::Millis, true)),
+                None => self.start_time.clone(),
+            };
+            
+            let alarms = self.client.get_logged_alarms(
+                &self.system_names,
+                &self.filter_string,
+                &self.filter_language,
+                &self.languages,
+                start_time.as_deref(),
+                self.end_time.as_deref(),
+                self.page_size,
+            )?;
+            let fetched = alarms.len();
+            
+            let mut page = Vec::with_capacity(fetched);
+            for alarm in alarms {
+                let modification_time = alarm.modification_time_utc()?;
+                if let (Some(cursor), Some(modification_time)) = (&self.cursor, modification_time) {
+                    let key = (alarm.name.clone(), alarm.instance_id);
+                    if modification_time < cursor.modification_time
+                        || (modification_time == cursor.modification_time && cursor.returned.contains(&key))
+                    {
+                        continue;
+                    }
+                }
+                page.push(alarm);
+            }
+            
+            if fetched < self.page_size as usize {
+                self.done = true;
+            } else {
+                let mut latest: Option<DateTime<Utc>> = None;
+                for alarm in &page {
+                    if let Some(modification_time) = alarm.modification_time_utc()? {
+                        latest = latest.max(Some(modification_time));
+                    }
+                }
+                match latest {
+                    Some(latest) => {
+                        let cursor = self.cursor.get_or_insert_with(|| AlarmCursor {
+                            modification_time: latest,
+                            returned: HashSet::new(),
+                        });
+                        if cursor.modification_time != latest {
+                            cursor.modification_time = latest;
+                            cursor.returned.clear();
+                        }
+                        for alarm in &page {
+                            if alarm.modification_time_utc()? == Some(latest) {
+                                cursor.returned.insert((alarm.name.clone(), alarm.instance_id));
+                            }
+                        }
+                    }
+                    None if page.is_empty() && self.cursor.is_some() => {
+                        // A full page of alarms that were all returned already: the alarms at this
+                        // modification time beyond the page cannot be requested
+                        let cursor = self.cursor.as_ref().expect("cursor is checked above");
+                        return Err(WinCCError::AlarmError(format!(
+                            "More than {} alarms at {}, use a larger page size",
+                            self.page_size, cursor.modification_time
+                        )));
+                    }
+                    None => {
+                        // Without modification times there is no way to continue
+                        self.done = true;
+                    }
+                }
+            }
+            
+            if !page.is_empty() {
+                return Ok(Some(page));
+            }
+            if self.done {
+                return Ok(None);
+            }
+        }
+    }
+}
+
+impl Iterator for LoggedAlarmsPages<'_> {
+    type Item = WinCCResult<Vec<LoggedAlarm>>;
+    
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None;
+        }
+        if self.page_size <= 0 {
+            self.done = true;
+            return Some(Err(WinCCError::InvalidParameter("Page size must be greater than 0".to_string())));
+        }
+        
+        match self.next_page() {
+            Ok(page) => page.map(Ok),
+            Err(e) => {
+                self.done = true;
+                Some(Err(e))
+            }
+        }
+    }
+}
+
+/// Session state shared between the client and the auto-extend thread
+#[derive(Default)]
+struct SessionState {
+    token: Option<String>,
+    expires: Option<DateTime<Utc>>,
+    ws_token: Option<TokenUpdater>,
+    /// Token updaters of the named WebSocket channels (`connect_ws_channel`)
+    ws_channel_tokens: HashMap<String, TokenUpdater>,
+    /// Inactivity period after which the server logs the user off (`autoLogoffSec`)
+    auto_logoff: Option<Duration>,
+    /// Time of the last request sent with the token while `auto_logoff` is known
+    last_activity: Option<DateTime<Utc>>,
+    /// How the current token was obtained; kept across session extensions
+    auth_method: Option<AuthMethod>,
+}
+
+impl SessionState {
+    /// Store token, expiry and auto-logoff period of a login or session extension
+    fn store_session(&mut self, session: &Session, token: &str) {
+        self.set_token(token);
+        self.expires = session.expires_utc().ok().flatten();
+        if let Some(user) = &session.user {
+            self.auto_logoff = user
+                .auto_logoff_sec
+                .filter(|seconds| *seconds > 0)
+                .map(|seconds| Duration::from_secs(seconds.unsigned_abs().into()));
+        }
+        self.last_activity = self.auto_logoff.map(|_| Utc::now());
+    }
+    
+    /// Clear token, expiry and auto-logoff tracking
+    fn clear(&mut self) {
+        self.token = None;
+        self.expires = None;
+        self.auto_logoff = None;
+        self.last_activity = None;
+        self.auth_method = None;
+    }
+    
+    /// Note a request sent with the token, which restarts the auto-logoff period
+    fn record_activity(&mut self) {
+        if self.auto_logoff.is_some() && self.token.is_some() {
+            self.last_activity = Some(Utc::now());
+        }
+    }
+    
+    /// Time at which the server logs the user off if no further request is sent
+    fn auto_logoff_at(&self) -> Option<DateTime<Utc>> {
+        let auto_logoff = chrono::Duration::from_std(self.auto_logoff?).ok()?;
+        Some(self.last_activity? + auto_logoff)
+    }
+    
+    fn set_token(&mut self, token: &str) {
+        let changed = self.token.as_deref() != Some(token);
+        self.token = Some(token.to_string());
+        
+        // Update WebSocket client token if it is connected and the token changed
+        if changed {
+            if let Some(ws_token) = &self.ws_token {
+                ws_token.update(token.to_string());
+            }
+            for ws_token in self.ws_channel_tokens.values() {
+                ws_token.update(token.to_string());
+            }
+        }
+    }
+}
+
+/// Languages of the convenience methods, shared between clones of the client
+struct LanguageSettings {
+    /// Alarm text languages; the first one is also the filter language
+    alarm: Vec<String>,
+    /// Display name language of browse results
+    browse: String,
+}
+
+impl Default for LanguageSettings {
+    fn default() -> Self {
+        Self {
+            alarm: vec![DEFAULT_LANGUAGE.to_string()],
+            browse: DEFAULT_LANGUAGE.to_string(),
+        }
+    }
+}
+
+/// Where `HttpTransport` sends GraphQL operations
+#[derive(Clone)]
+enum Backend {
+    /// POST to `http_url` with the session token, custom headers and retry policy
+    Http(Client),
+    /// Custom transport set with `WinCCUnifiedClientBuilder::transport`
+    Custom(Arc<dyn Transport>),
+}
+
+/// HTTP transport for GraphQL queries and mutations
+#[derive(Clone)]
+struct HttpTransport {
+    backend: Backend,
+    http_url: String,
+    session: Arc<RwLock<SessionState>>,
+    default_headers: Arc<Mutex<HeaderMap>>,
+    retry_policy: Option<RetryPolicy>,
+    metrics: Option<MetricsHook>,
+    /// Whether requests end on their own: a `request_timeout` is set, or the HTTP client or
+    /// transport was supplied by the caller and has its own timeouts
+    requests_time_out: bool,
+}
+
+impl HttpTransport {
+    /// Make a GraphQL HTTP request
+    fn request(&self, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        Self::into_data(self.execute(query, variables)?)
+    }
+    
+    /// Make a GraphQL HTTP request and return the response field `field` together with the
+    /// GraphQL errors, as long as the server returned data for the field
+    fn request_partial(&self, query: &str, variables: Option<Value>, field: &str) -> WinCCResult<(Value, Vec<GraphQLError>)> {
+        let mut result = self.execute(query, variables)?;
+        let errors = match result.get("errors").and_then(Value::as_array) {
+            Some(errors) if !errors.is_empty() => errors.clone(),
+            _ => return Ok((Self::into_data(result)?.get(field).cloned().unwrap_or_default(), Vec::new())),
+        };
+        
+        match result.get_mut("data").and_then(|data| data.get_mut(field)) {
+            Some(value) if !value.is_null() => Ok((value.take(), GraphQLError::parse_all(&errors))),
+            _ => Err(WinCCError::from_graphql_response(&errors, result.get("data").cloned())),
+        }
+    }
+    
+    /// Send a GraphQL operation and return the complete GraphQL response
+    fn execute(&self, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        self.execute_named(None, query, variables)
+    }
+    
+    /// Send a GraphQL operation with the given operation name, or the one defined in the query
+    fn execute_named(&self, operation_name: Option<&str>, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        let Some(MetricsHook(metrics)) = &self.metrics else {
+            return self.send(operation_name, query, variables).map(|(_, response)| response);
+        };
+        
+        let operation = operation_name.or_else(|| graphql::operation_name(query)).unwrap_or("anonymous");
+        metrics.on_request(operation);
+        let started = Instant::now();
+        let result = self.send(operation_name, query, variables);
+        match &result {
+            Ok((status, response)) => match graphql_error(response) {
+                Some(error) => metrics.on_error(operation, &error),
+                None => metrics.on_response(operation, started.elapsed(), *status),
+            },
+            Err(e) => metrics.on_error(operation, e),
+        }
+        result.map(|(_, response)| response)
+    }
+    
+    /// Send a GraphQL operation and return the HTTP status and the complete GraphQL response
+    fn send(&self, operation_name: Option<&str>, query: &str, variables: Option<Value>) -> WinCCResult<(u16, Value)> {
+        let variables = variables.unwrap_or(json!({}));
+        let result = match &self.backend {
+            Backend::Custom(transport) => {
+                let operation_name = operation_name.or_else(|| graphql::operation_name(query));
+                transport.execute(operation_name, query, &variables).map(|response| (200, response))
+            }
+            Backend::Http(http_client) => {
+                let headers = self.headers();
+                let payload = Self::payload(operation_name, query, variables);
+                self.post(http_client, headers, &payload, retry::is_mutation(query))
+            }
+        };
+        
+        if result.is_ok() {
+            self.session.write().unwrap().record_activity();
+        }
+        result
+    }
+    
+    /// JSON body of one GraphQL operation, `operationName` is taken from the query if not given
+    fn payload(operation_name: Option<&str>, query: &str, variables: Value) -> Value {
+        let mut payload = json!({
+            "query": query,
+            "variables": variables
+        });
+        if let Some(operation_name) = operation_name.or_else(|| graphql::operation_name(query)) {
+            payload["operationName"] = json!(operation_name);
+        }
+        payload
+    }
+    
+    /// Make an array-batched GraphQL HTTP request, results are returned in request order
+    /// 
+    /// With a custom transport the operations are executed one after another.
+    fn batch_request(&self, operations: &[(String, Value)]) -> WinCCResult<Vec<WinCCResult<Value>>> {
+        let http_client = match &self.backend {
+            Backend::Http(http_client) => http_client,
+            Backend::Custom(transport) => {
+                let results: Vec<_> = operations
+                    .iter()
+                    .map(|(query, variables)| {
+                        transport.execute(graphql::operation_name(query), query, variables).and_then(Self::into_data)
+                    })
+                    .collect();
+                self.session.write().unwrap().record_activity();
+                return Ok(results);
+            }
+        };
+        
+        let headers = self.headers();
+        let payload: Vec<Value> = operations
+            .iter()
+            .map(|(query, variables)| Self::payload(None, query, variables.clone()))
+            .collect();
+        
+        let has_mutation = operations.iter().any(|(query, _)| retry::is_mutation(query));
+        let started = Instant::now();
+        if let Some(MetricsHook(metrics)) = &self.metrics {
+            metrics.on_request("batch");
+        }
+        let result = self.post(http_client, headers, &json!(payload), has_mutation);
+        if let Some(MetricsHook(metrics)) = &self.metrics {
+            match &result {
+                Ok((status, _)) => metrics.on_response("batch", started.elapsed(), *status),
+                Err(e) => metrics.on_error("batch", e),
+            }
+        }
+        let (_, response) = result?;
+        self.session.write().unwrap().record_activity();
+        match response {
+            Value::Array(results) if results.len() == operations.len() => {
+                Ok(results.into_iter().map(Self::into_data).collect())
+            }
+            Value::Array(results) => Err(WinCCError::OperationFailed(format!(
+                "Batch returned {} results for {} operations", results.len(), operations.len()
+            ))),
+            _ => Err(WinCCError::OperationFailed("Server does not support batched requests".to_string())),
+        }
+    }
+    
+    /// Headers for a GraphQL request: content type, bearer token and custom headers
+    fn headers(&self) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
+        
+        if let Some(token) = &self.session.read().unwrap().token {
+            let auth_header = format!("Bearer {}", token);
+            headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_header).unwrap());
+        }
+        
+        // Custom headers are applied last so they can override the defaults
+        for (name, value) in self.default_headers.lock().unwrap().iter() {
+            headers.insert(name.clone(), value.clone());
+        }
+        headers
+    }
+    
+    /// POST a JSON payload and return the HTTP status and the parsed JSON response, retrying according to the retry policy
+    fn post(&self, http_client: &Client, headers: HeaderMap, payload: &Value, is_mutation: bool) -> WinCCResult<(u16, Value)> {
+        let Some(policy) = &self.retry_policy else {
+            return self.post_once(http_client, headers, payload);
+        };
+        let attempts = policy.attempts_for(is_mutation);
+        let mut attempt = 1;
+        loop {
+            match self.post_once(http_client, headers.clone(), payload) {
+                Err(e) if attempt < attempts && RetryPolicy::is_retryable(&e) => {
+                    let Some(delay) = policy.retry_delay(attempt, &e) else {
+                        return Err(e);
+                    };
+                    log::debug!("Request attempt {} failed ({}), retrying in {:?}", attempt, e, delay);
+                    std::thread::sleep(delay);
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
+    }
+    
+    /// POST a JSON payload once and return the HTTP status and the parsed JSON response
+    fn post_once(&self, http_client: &Client, headers: HeaderMap, payload: &Value) -> WinCCResult<(u16, Value)> {
+        let response = http_client
+            .post(&self.http_url)
+            .headers(headers)
+            .json(payload)
+            .send()?;
+        
+        if response.status() == reqwest::StatusCode::TOO_MANY_REQUESTS {
+            let retry_after = response.headers()
+                .get(reqwest::header::RETRY_AFTER)
+                .and_then(|value| value.to_str().ok())
+                .and_then(parse_retry_after);
+            return Err(WinCCError::RateLimited { retry_after });
+        }
+        if !response.status().is_success() {
+            let status = response.status().as_u16();
+            let request_id = REQUEST_ID_HEADERS
+                .iter()
+                .find_map(|name| Some(response.headers().get(*name)?.to_str().ok()?.to_string()));
+            let body = truncate_error_body(response.text().unwrap_or_default());
+            return Err(WinCCError::ServerError { status, request_id, body });
+        }
+        
+        let status = response.status().as_u16();
+        Ok((status, response.json()?))
+    }
+    
+    /// Extract `data` from a GraphQL response, or the errors if there are any
+    fn into_data(result: Value) -> WinCCResult<Value> {
+        if let Some(error) = graphql_error(&result) {
+            return Err(error);
+        }
+        
+        Ok(result.get("data").unwrap_or(&json!({})).clone())
+    }
+    
+    /// Run the extendSession mutation and store the returned token and expiry
+    fn extend_session(&self) -> WinCCResult<Session> {
+        let result = self.request(mutations::EXTEND_SESSION, None)?;
+        let extend_result: Session = serde_json::from_value(result["extendSession"].clone())?;
+        
+        let token = session_token(&extend_result).map_err(|(code, description)| {
+            WinCCError::SessionError(match code {
+                0 => format!("Session extension failed: {}", description),
+                code => format!("Session extension failed: {} - {}", code, description),
+            })
+        })?;
+        self.session.write().unwrap().store_session(&extend_result, token);
+        Ok(extend_result)
+    }
+}
+
+/// Background thread that extends the session before it expires
+struct AutoExtendHandle {
+    shutdown: Arc<(Mutex<bool>, Condvar)>,
+    thread: Option<JoinHandle<()>>,
+}
+
+impl AutoExtendHandle {
+    fn spawn(transport: HttpTransport, prevent_auto_logoff: bool) -> Self {
+        let shutdown = Arc::new((Mutex::new(false), Condvar::new()));
+        let thread_shutdown = shutdown.clone();
+        let thread = std::thread::spawn(move || Self::run(transport, prevent_auto_logoff, thread_shutdown));
+        
+        Self {
+            shutdown,
+            thread: Some(thread),
+        }
+    }
+    
+    fn run(transport: HttpTransport, prevent_auto_logoff: bool, shutdown: Arc<(Mutex<bool>, Condvar)>) {
+        let (lock, cvar) = &*shutdown;
+        let mut last_failed = false;
+        
+        loop {
+            let delay = if last_failed {
+                AUTO_EXTEND_RETRY_DELAY
+            } else {
+                let session = transport.session.read().unwrap();
+                let delay = session.expires.map(auto_extend_delay).unwrap_or(AUTO_EXTEND_RETRY_DELAY);
+                match session.auto_logoff_at() {
+                    Some(auto_logoff_at) if prevent_auto_logoff => delay.min(auto_extend_delay(auto_logoff_at)),
+                    _ => delay,
+                }
+            };
+            log::debug!("Next session extension in {:?}", delay);
+            
+            let stopped = lock.lock().unwrap();
+            let (stopped, _) = cvar.wait_timeout_while(stopped, delay, |stopped| !*stopped).unwrap();
+            if *stopped {
+                break;
+            }
+            drop(stopped);
+            
+            match transport.extend_session() {
+                Ok(session) => {
+                    log::debug!("Session extended, expires: {:?}", session.expires);
+                    last_failed = false;
+                }
+                Err(e) => {
+                    log::error!("Automatic session extension failed: {}", e);
+                    last_failed = true;
+                }
+            }
+        }
+        log::debug!("Auto-extend thread stopped");
+    }
+    
+    /// Signal the thread to stop and wait for it to finish
+    fn stop(&mut self) {
+        let (lock, cvar) = &*self.shutdown;
+        *lock.lock().unwrap() = true;
+        cvar.notify_all();
+        
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for AutoExtendHandle {
+    fn drop(&mut self) {
+        self.stop();
+    }
+}
+
+/// Check that the GraphQL endpoint is a valid `http://` or `https://` URL
+fn validate_http_url(url: &str) -> WinCCResult<()> {
+    match Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(()),
+        Ok(_) => Err(WinCCError::InvalidParameter(format!("http_url must use http:// or https://, got {}", url))),
+        Err(e) => Err(WinCCError::InvalidParameter(format!("Invalid http_url {}: {}", url, e))),
+    }
+}
+
+/// The error for a GraphQL response with a non-empty `errors` array
+fn graphql_error(response: &Value) -> Option<WinCCError> {
+    let errors = response.get("errors")?.as_array().filter(|errors| !errors.is_empty())?;
+    Some(WinCCError::from_graphql_response(errors, response.get("data").cloned()))
+}
+
+/// Encode a duration as GraphQL `Timespan`
+/// 
+/// The schema declares `shelveTimeout: Timespan = 0`, an integer literal, so a Timespan is
+/// sent as JSON number. The unit is not documented in the schema; milliseconds are assumed.
+fn timespan(duration: Duration) -> u64 {
+    u64::try_from(duration.as_millis()).unwrap_or(u64::MAX)
+}
+
+/// Response headers that carry the request id of a failed request, in order of preference
+const REQUEST_ID_HEADERS: [&str; 3] = ["x-request-id", "x-correlation-id", "request-id"];
+
+/// Maximum length of the response body kept in `ServerError`
+const MAX_ERROR_BODY_LEN: usize = 4096;
+
+/// Shorten an error response body to `MAX_ERROR_BODY_LEN` bytes (at a character boundary)
+fn truncate_error_body(mut body: String) -> String {
+    if body.len() > MAX_ERROR_BODY_LEN {
+        let mut end = MAX_ERROR_BODY_LEN;
+        while !body.is_char_boundary(end) {
+            end -= 1;
+        }
+        body.truncate(end);
+        body.push_str("... (truncated)");
+    }
+    body
+}
+
+/// Parse a `Retry-After` header value, given either as seconds or as HTTP date
+fn parse_retry_after(value: &str) -> Option<Duration> {
+    if let Ok(seconds) = value.trim().parse::<u64>() {
+        return Some(Duration::from_secs(seconds));
+    }
+    let date = DateTime::parse_from_rfc2822(value.trim()).ok()?;
+    // A date in the past means the request can be retried immediately
+    Some((date.with_timezone(&Utc) - Utc::now()).to_std().unwrap_or(Duration::ZERO))
+}
+
+/// Collect string slices or owned strings into owned strings
+fn into_strings(values: impl IntoIterator<Item = impl AsRef<str>>) -> Vec<String> {
+    values.into_iter().map(|value| value.as_ref().to_string()).collect()
+}
+
+/// ActiveAlarms query document for a field set
+fn active_alarms_query(fields: AlarmFieldSet) -> &'static str {
+    match fields {
+        AlarmFieldSet::Minimal => queries::ACTIVE_ALARMS_MINIMAL,
+        AlarmFieldSet::Standard => queries::ACTIVE_ALARMS_STANDARD,
+        AlarmFieldSet::Full => queries::ACTIVE_ALARMS,
+    }
+}
+
+/// Check the time window rules of `loggedTagValues`: a window open on one side searches from
+/// the given time in the matching sort order and needs a value limit
+fn check_logged_tag_values_window(
+    start_time: Option<&str>,
+    end_time: Option<&str>,
+    max_number_of_values: i32,
+    sorting_mode: SortingMode,
+) -> WinCCResult<()> {
+    let required_sorting = match (start_time, end_time) {
+        (Some(_), None) => SortingMode::TimeAsc,
+        (None, Some(_)) => SortingMode::TimeDesc,
+        _ => return Ok(()),
+    };
+    if sorting_mode != required_sorting {
+        return Err(WinCCError::InvalidParameter(format!(
+            "Sorting mode must be {} if only {} is given",
+            required_sorting.as_str(),
+            if start_time.is_some() { "startTime" } else { "endTime" }
+        )));
+    }
+    if max_number_of_values <= 0 {
+        return Err(WinCCError::InvalidParameter(
+            "maxNumberOfValues must be greater than 0 if only startTime or endTime is given".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+/// Token of a login or extendSession result.
+/// 
+/// A non-zero `error.code` is a failure even if a token is returned; a missing or "0"
+/// code is a success if the token is not empty. Returns the error code (0 if there is no
+/// numeric code) and description otherwise.
+fn session_token(session: &Session) -> Result<&str, (i32, String)> {
+    let error = session.error.as_ref();
+    let code = error.and_then(|e| e.code.as_deref()).map(str::trim).filter(|code| !code.is_empty());
+    let description = error
+        .and_then(|e| e.description.as_deref())
+        .unwrap_or("Unknown error");
+    
+    match (code, session.token.as_deref()) {
+        (Some(code), _) if code != "0" => Err(match code.parse() {
+            Ok(code) => (code, description.to_string()),
+            Err(_) => (0, format!("{} - {}", code, description)),
+        }),
+        (_, Some(token)) if !token.is_empty() => Ok(token),
+        (Some(_), _) => Err((0, "No token returned".to_string())),
+        (None, _) => Err((0, description.to_string())),
+    }
+}
+
+/// `LoginError` for a failed login result
+fn login_error((code, description): (i32, String)) -> WinCCError {
+    WinCCError::LoginError { code, description }
+}
+
+/// Whether an error means that the server rejected the token (HTTP 401/403 or an authentication GraphQL error)
+fn is_auth_failure(error: &WinCCError) -> bool {
+    match error {
+        WinCCError::HttpError(e) => e.status().is_some_and(|status| {
+            status == reqwest::StatusCode::UNAUTHORIZED || status == reqwest::StatusCode::FORBIDDEN
+        }),
+        WinCCError::ServerError { status, .. } => *status == 401 || *status == 403,
+        WinCCError::AuthenticationError(_) => true,
+        WinCCError::GraphQLError { message, .. } | WinCCError::ApiError { message, .. } => {
+            let message = message.to_lowercase();
+            ["unauthori", "authenticat", "not logged in", "invalid token"]
+                .iter()
+                .any(|pattern| message.contains(pattern))
+        }
+        _ => false,
+    }
+}
+
+/// Tag part of a logging tag name (`System::Tag:LoggingTag`), None if the name has no logging tag part
+fn logging_tag_parent(name: &str) -> Option<&str> {
+    let separator = name.rfind(':')?;
+    if separator == 0 || name[..separator].ends_with(':') {
+        return None;
+    }
+    Some(&name[..separator])
+}
+
+/// Browse name filter for the children of a structure tag (`Struct.*`) or a system (`System1::*`)
+fn children_name_filter(parent: &str) -> String {
+    if parent.ends_with("::") {
+        format!("{}*", parent)
+    } else {
+        format!("{}.*", parent)
+    }
+}
+
+/// Collect the non-object values of a nested JSON object with their dotted field paths
+fn flatten_structure_value(prefix: &str, value: &Value, fields: &mut Vec<(String, Value)>) {
+    match value.as_object() {
+        Some(object) => {
+            for (key, member) in object {
+                let path = if prefix.is_empty() { key.clone() } else { format!("{}.{}", prefix, key) };
+                flatten_structure_value(&path, member, fields);
+            }
+        }
+        None => fields.push((prefix.to_string(), value.clone())),
+    }
+}
+
+/// Part of `name` below `parent`, accepting a system prefix on `name` that `parent` omits
+fn path_below<'a>(name: &'a str, parent: &str) -> Option<&'a str> {
+    let below = |name: &'a str| {
+        if parent.ends_with("::") {
+            name.strip_prefix(parent)
+        } else {
+            name.strip_prefix(parent)?.strip_prefix('.')
+        }
+    };
+    below(name).or_else(|| {
+        if parent.contains("::") {
+            return None;
+        }
+        below(name.split_once("::")?.1)
+    })
+}
+
+/// Time until the session should be extended: 80% of the remaining lifetime
+fn auto_extend_delay(expires: DateTime<Utc>) -> Duration {
+    let remaining = expires.signed_duration_since(chrono::Utc::now()).to_std().unwrap_or(Duration::ZERO);
+    remaining.mul_f64(AUTO_EXTEND_LIFETIME_FRACTION)
 }
 
 impl WinCCUnifiedClient {
@@ -33,14 +1551,24 @@ impl WinCCUnifiedClient {
     /// 
     /// let client = WinCCUnifiedClient::new("https://your-server/graphql");
     /// ```
+    /// 
+    /// # Panics
+    /// If `http_url` is not an `http://` or `https://` URL; use `try_new` to handle this as an error.
     pub fn new(http_url: &str) -> Self {
-        Self {
-            http_client: Client::new(),
-            http_url: http_url.to_string(),
-            ws_url: None,
-            token: None,
-            ws_client: None,
-        }
+        Self::try_new(http_url).unwrap_or_else(|e| panic!("Cannot create client for {}: {}", http_url, e))
+    }
+
+    /// Create a new WinCC Unified client, returning an error for an invalid URL
+    /// 
+    /// # Arguments
+    /// * `http_url` - The HTTP URL for GraphQL queries and mutations
+    /// 
+    /// Errors:
+    /// - InvalidParameter - `http_url` is not an `http://` or `https://` URL
+    pub fn try_new(http_url: &str) -> WinCCResult<Self> {
+        WinCCUnifiedClientBuilder::new()
+            .http_url(http_url)
+            .build()
     }
 
     /// Create a new WinCC Unified client with WebSocket support
@@ -48,72 +1576,464 @@ impl WinCCUnifiedClient {
     /// # Arguments
     /// * `http_url` - The HTTP URL for GraphQL queries and mutations
     /// * `ws_url` - The WebSocket URL for GraphQL subscriptions
+    /// 
+    /// # Panics
+    /// If `http_url` is not an `http://` or `https://` URL or `ws_url` is not a `ws://` or `wss://`
+    /// URL; use `try_new_with_ws` to handle this as an error.
     pub fn new_with_ws(http_url: &str, ws_url: &str) -> Self {
-        Self {
-            http_client: Client::new(),
-            http_url: http_url.to_string(),
-            ws_url: Some(ws_url.to_string()),
-            token: None,
-            ws_client: None,
-        }
+        Self::try_new_with_ws(http_url, ws_url)
+            .unwrap_or_else(|e| panic!("Cannot create client for {} and {}: {}", http_url, ws_url, e))
+    }
+
+    /// Create a new WinCC Unified client with WebSocket support, returning an error for an invalid URL
+    /// 
+    /// # Arguments
+    /// * `http_url` - The HTTP URL for GraphQL queries and mutations
+    /// * `ws_url` - The WebSocket URL for GraphQL subscriptions
+    /// 
+    /// Errors:
+    /// - InvalidParameter - `http_url` is not an `http://` or `https://` URL or `ws_url` is not
+    ///   a `ws://` or `wss://` URL
+    pub fn try_new_with_ws(http_url: &str, ws_url: &str) -> WinCCResult<Self> {
+        WinCCUnifiedClientBuilder::new()
+            .http_url(http_url)
+            .ws_url(ws_url)
+            .build()
+    }
+    
+    /// Create a client that is already authenticated with a bearer token
+    /// 
+    /// For deployments where the token is obtained elsewhere (e.g. by an auth service)
+    /// and `login` is never called.
+    /// 
+    /// # Arguments
+    /// * `http_url` - The HTTP URL for GraphQL queries and mutations
+    /// * `token` - The bearer token for authentication
+    /// 
+    /// # Example
+    /// ```
+    /// use winccua_graphql_client::WinCCUnifiedClient;
+    /// 
+    /// let client = WinCCUnifiedClient::with_token("https://your-server/graphql", "token-from-auth-service");
+    /// ```
+    /// 
+    /// # Panics
+    /// If `http_url` is not an `http://` or `https://` URL; use `builder().token(...)` to handle this as an error.
+    pub fn with_token(http_url: &str, token: &str) -> Self {
+        WinCCUnifiedClientBuilder::new()
+            .http_url(http_url)
+            .token(token)
+            .build()
+            .unwrap_or_else(|e| panic!("Cannot create client for {}: {}", http_url, e))
+    }
+    
+    /// Create a client with WebSocket support that is already authenticated with a bearer token
+    /// 
+    /// The token is also sent in the WebSocket `connection_init` payload by `connect_ws`.
+    /// 
+    /// # Arguments
+    /// * `http_url` - The HTTP URL for GraphQL queries and mutations
+    /// * `ws_url` - The WebSocket URL for GraphQL subscriptions
+    /// * `token` - The bearer token for authentication
+    /// 
+    /// # Panics
+    /// If `http_url` is not an `http://` or `https://` URL or `ws_url` is not a `ws://` or `wss://`
+    /// URL; use `builder().token(...)` to handle this as an error.
+    pub fn with_token_and_ws(http_url: &str, ws_url: &str, token: &str) -> Self {
+        WinCCUnifiedClientBuilder::new()
+            .http_url(http_url)
+            .ws_url(ws_url)
+            .token(token)
+            .build()
+            .unwrap_or_else(|e| panic!("Cannot create client for {} and {}: {}", http_url, ws_url, e))
+    }
+    
+    /// Create a client that executes all queries and mutations with the given transport
+    /// 
+    /// # Arguments
+    /// * `transport` - Transport replacing the HTTP layer, e.g. a `MockTransport`
+    pub fn with_transport(transport: impl Transport + 'static) -> Self {
+        WinCCUnifiedClientBuilder::new()
+            .transport(transport)
+            .build()
+            .expect("Failed to create HTTP client")
+    }
+
+    /// Create a builder for configuring timeouts, TLS and the initial token
+    /// 
+    /// # Example
+    /// ```
+    /// use std::time::Duration;
+    /// use winccua_graphql_client::WinCCUnifiedClient;
+    /// 
+    /// let client = WinCCUnifiedClient::builder()
+    ///     .http_url("https://your-server/graphql")
+    ///     .connect_timeout(Duration::from_secs(5))
+    ///     .request_timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// ```
+    pub fn builder() -> WinCCUnifiedClientBuilder {
+        WinCCUnifiedClientBuilder::new()
     }
     
     /// Set the authentication token
     /// 
     /// # Arguments
     /// * `token` - The bearer token for authentication
-    pub fn set_token(&mut self, token: &str) {
-        self.token = Some(token.to_string());
-        
-        // Update WebSocket client token if it exists
-        if let Some(ws_client) = &self.ws_client {
-            ws_client.update_token(token.to_string());
-        }
+    /// 
+    /// `auth_method` reports `AuthMethod::Preset` afterwards, also if the token came from a login.
+    pub fn set_token(&self, token: &str) {
+        let mut session = self.transport.session.write().unwrap();
+        session.set_token(token);
+        session.auth_method = Some(AuthMethod::Preset);
     }
     
     /// Clear the authentication token
-    pub fn clear_token(&mut self) {
-        self.token = None;
+    pub fn clear_token(&self) {
+        self.transport.session.write().unwrap().clear();
+    }
+    
+    /// Set a custom header that is sent with every HTTP request and included in the
+    /// WebSocket `connection_init` payload (e.g. `X-Api-Key` for an API gateway).
+    /// 
+    /// Setting the same header again replaces the previous value. Custom headers take
+    /// precedence over the `Content-Type` and `Authorization` headers set by the client.
+    /// WebSocket connections pick up header changes on the next `connect_ws`.
+    /// 
+    /// Errors:
+    /// - InvalidParameter - Invalid header name or value
+    pub fn set_header(&self, name: &str, value: &str) -> WinCCResult<()> {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|e| WinCCError::InvalidParameter(format!("Invalid header name '{}': {}", name, e)))?;
+        let value = HeaderValue::from_str(value)
+            .map_err(|e| WinCCError::InvalidParameter(format!("Invalid value for header '{}': {}", name, e)))?;
+        
+        self.transport.default_headers.lock().unwrap().insert(name, value);
+        Ok(())
+    }
+    
+    /// Replace all custom headers, see `set_header`
+    pub fn set_default_headers(&self, headers: HeaderMap) {
+        *self.transport.default_headers.lock().unwrap() = headers;
+    }
+    
+    /// Remove a custom header set with `set_header` or `set_default_headers`
+    pub fn remove_header(&self, name: &str) {
+        self.transport.default_headers.lock().unwrap().remove(name);
+    }
+    
+    /// Set an extra entry of the WebSocket `connection_init` payload (e.g. a tenant id or
+    /// locale expected by the deployment)
+    /// 
+    /// Unlike `set_header`, the entry is not sent with HTTP requests. Setting the same key
+    /// again replaces the previous value; an entry replaces a custom header or the
+    /// `Authorization` entry with the same key. Like custom headers, the entries are shared
+    /// with clones of the client. WebSocket connections pick up changes on the next `connect_ws`.
+    pub fn set_ws_init_param(&self, key: &str, value: &str) {
+        self.ws_init_params.lock().unwrap().insert(key.to_string(), value.to_string());
+    }
+    
+    /// Remove an entry set with `set_ws_init_param`
+    pub fn remove_ws_init_param(&self, key: &str) {
+        self.ws_init_params.lock().unwrap().remove(key);
+    }
+    
+    /// Set the languages used by the convenience alarm methods (default `["en-US"]`)
+    /// 
+    /// Applies to `get_active_alarms_simple`, `get_active_alarm_by_instance`,
+    /// `get_active_alarms_by_names`, `get_logged_alarms_simple` and
+    /// `subscribe_to_active_alarms_simple`. The first language is also used as filter language.
+    /// Methods that take `languages` as argument are not affected. An empty list restores the default.
+    /// Like custom headers, the setting is shared with clones of the client.
+    /// 
+    /// # Arguments
+    /// * `languages` - Languages of the returned alarm texts, e.g. `["de-DE", "en-US"]`
+    pub fn set_default_languages(&self, languages: &[String]) {
+        self.languages.write().unwrap().alarm = if languages.is_empty() {
+            vec![DEFAULT_LANGUAGE.to_string()]
+        } else {
+            languages.to_vec()
+        };
+    }
+    
+    /// Languages used by the convenience alarm methods, see `set_default_languages`
+    pub fn default_languages(&self) -> Vec<String> {
+        self.languages.read().unwrap().alarm.clone()
+    }
+    
+    /// Set the language of display names returned by `browse_simple`, `browse_children`,
+    /// `read_structure` and `get_logging_tags_for` (default "en-US")
+    /// 
+    /// Like custom headers, the setting is shared with clones of the client.
+    pub fn set_browse_language(&self, language: &str) {
+        self.languages.write().unwrap().browse = language.to_string();
+    }
+    
+    /// Language used by the convenience browse methods, see `set_browse_language`
+    pub fn browse_language(&self) -> String {
+        self.languages.read().unwrap().browse.clone()
     }
     
     /// Make a GraphQL HTTP request
     fn request(&self, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
-        let mut headers = HeaderMap::new();
-        headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/json"));
-        
-        if let Some(token) = &self.token {
-            let auth_header = format!("Bearer {}", token);
-            headers.insert(AUTHORIZATION, HeaderValue::from_str(&auth_header).unwrap());
+        self.transport.request(query, variables)
+    }
+    
+    /// Execute an arbitrary GraphQL query or mutation against the server.
+    /// 
+    /// Uses the same authentication, custom headers and GraphQL error handling as the
+    /// built-in operations. Useful for schema fields not covered by this client, e.g.
+    /// requesting only a subset of ActiveAlarm fields.
+    /// 
+    /// Returns: The `data` object of the GraphQL response
+    /// 
+    /// # Example
+    /// ```no_run
+    /// use winccua_graphql_client::WinCCUnifiedClient;
+    /// use serde_json::json;
+    /// 
+    /// let client = WinCCUnifiedClient::new("https://your-server/graphql");
+    /// let data = client.execute_raw(
+    ///     "query($names: [String!]!) { tagValues(names: $names) { name value { value } } }",
+    ///     Some(json!({ "names": ["HMI_Tag_1"] })),
+    /// ).unwrap();
+    /// println!("{}", data["tagValues"]);
+    /// ```
+    pub fn execute_raw(&self, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        self.request(query, variables)
+    }
+    
+    /// Execute a GraphQL query or mutation with an explicit `operationName`
+    /// 
+    /// Selects the operation to run from a document with several named operations.
+    /// `execute_raw` and the built-in operations send the name of the (first) operation
+    /// defined in the document, which gateways and APM tools can use to tell requests apart.
+    /// 
+    /// Returns: The `data` object of the GraphQL response
+    pub fn execute_named(&self, operation_name: &str, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        HttpTransport::into_data(self.transport.execute_named(Some(operation_name), query, variables)?)
+    }
+    
+    /// Start a batch that sends several queries in a single HTTP round-trip
+    /// 
+    /// # Example
+    /// ```no_run
+    /// use winccua_graphql_client::{WinCCUnifiedClient, graphql::queries};
+    /// use serde_json::json;
+    /// 
+    /// let client = WinCCUnifiedClient::new("https://your-server/graphql");
+    /// let results = client.batch()
+    ///     .add(queries::TAG_VALUES, Some(json!({ "names": ["HMI_Tag_1"] })))
+    ///     .add(queries::ACTIVE_ALARMS, None)
+    ///     .add(queries::BROWSE, None)
+    ///     .execute()
+    ///     .unwrap();
+    /// ```
+    pub fn batch(&self) -> BatchRequest<'_> {
+        BatchRequest {
+            transport: &self.transport,
+            operations: Vec::new(),
+        }
+    }
+    
+    /// Run `operation` on a new background thread and stop waiting for it if `token` is cancelled
+    /// 
+    /// The operation receives a clone of the client (sharing session and connection pool), so the
+    /// caller can give up on any query, e.g. a large `get_logged_tag_values`. Cancelling does not
+    /// abort the HTTP request: the calling thread is released immediately, while the request is
+    /// detached and runs to completion on its thread, and its result is discarded. Such requests
+    /// are only bounded by the request timeout, so the client must be built with `request_timeout`.
+    /// Each call starts one thread.
+    /// 
+    /// # Example
+    /// ```no_run
+    /// use std::time::Duration;
+    /// use winccua_graphql_client::{CancelToken, WinCCUnifiedClient};
+    /// 
+    /// let client = WinCCUnifiedClient::builder()
+    ///     .http_url("https://your-server/graphql")
+    ///     .request_timeout(Duration::from_secs(30))
+    ///     .build()
+    ///     .unwrap();
+    /// let token = CancelToken::new();
+    /// let ui_token = token.clone(); // call ui_token.cancel() when the user navigates away
+    /// let values = client.run_cancellable(&token, |client| {
+    ///     client.get_tag_values_simple(&["HMI_Tag_1".to_string()])
+    /// });
+    /// ```
+    /// 
+    /// Returns: The result of `operation`
+    /// 
+    /// Errors:
+    /// - Cancelled - The token was cancelled before the operation finished
+    /// - InvalidParameter - The client was built without `request_timeout` (not checked for a
+    ///   client built with `http_client` or `transport`, whose timeouts are their own)
+    /// - OperationFailed - The background thread could not be started
+    pub fn run_cancellable<T, F>(&self, token: &CancelToken, operation: F) -> WinCCResult<T>
+    where
+        T: Send + 'static,
+        F: FnOnce(&WinCCUnifiedClient) -> WinCCResult<T> + Send + 'static,
+    {
+        if !self.transport.requests_time_out {
+            return Err(WinCCError::InvalidParameter(
+                "run_cancellable requires a client built with request_timeout".to_string(),
+            ));
+        }
+        if token.is_cancelled() {
+            return Err(WinCCError::Cancelled);
         }
         
-        let payload = json!({
-            "query": query,
-            "variables": variables.unwrap_or(json!({}))
-        });
+        let slot = Arc::new((Mutex::new(None), Condvar::new()));
+        let worker_slot = slot.clone();
+        let client = self.clone();
+        std::thread::Builder::new()
+            .name("winccua-request".to_string())
+            .spawn(move || {
+                let result = operation(&client);
+                let (lock, done) = &*worker_slot;
+                *lock.lock().unwrap() = Some(result);
+                done.notify_all();
+            })
+            .map_err(|e| WinCCError::OperationFailed(format!("Failed to start request thread: {}", e)))?;
         
-        let response = self.http_client
-            .post(&self.http_url)
-            .headers(headers)
-            .json(&payload)
-            .send()?;
+        let wake_slot = slot.clone();
+        let wake: Arc<dyn Fn() + Send + Sync> = Arc::new(move || {
+            let _guard = wake_slot.0.lock().unwrap();
+            wake_slot.1.notify_all();
+        });
+        token.on_cancel(&wake);
         
-        if !response.status().is_success() {
-            return Err(WinCCError::HttpError(reqwest::Error::from(
-                response.error_for_status().unwrap_err()
-            )));
+        let (lock, done) = &*slot;
+        let mut result = lock.lock().unwrap();
+        loop {
+            if let Some(result) = result.take() {
+                return result;
+            }
+            if token.is_cancelled() {
+                return Err(WinCCError::Cancelled);
+            }
+            result = done.wait(result).unwrap();
+        }
+    }
+    
+    /// Async variant of `execute_raw` that runs the blocking request on tokio's blocking thread pool
+    /// 
+    /// Dropping the returned future stops waiting, but the request itself still completes on
+    /// the blocking pool; set a `request_timeout` to bound it.
+    pub async fn execute_raw_async(&self, query: &str, variables: Option<Value>) -> WinCCResult<Value> {
+        let transport = self.transport.clone();
+        let query = query.to_string();
+        tokio::task::spawn_blocking(move || transport.request(&query, variables))
+            .await
+            .map_err(|e| WinCCError::OperationFailed(format!("Request task failed: {}", e)))?
+    }
+    
+    /// Store the token and expiry of a successful login
+    fn store_session(&self, session: &Session, token: &str, method: AuthMethod) {
+        let mut state = self.transport.session.write().unwrap();
+        state.store_session(session, token);
+        state.auth_method = Some(method);
+    }
+    
+    /// How the current token was obtained: `login` (`Password`), `login_swac` (`Swac`) or
+    /// `set_token` / builder `token` (`Preset`)
+    /// 
+    /// Derived only from the method that was called. Session extensions keep the method of the
+    /// original login. Returns None if no token is set, e.g. after `logout` or `clear_token`.
+    pub fn auth_method(&self) -> Option<AuthMethod> {
+        self.transport.session.read().unwrap().auth_method
+    }
+    
+    /// Expiry of the current session as returned by the last login or session extension
+    /// 
+    /// Returns None if no session is active, the token was set without a login (`set_token`,
+    /// builder `token`) or the server returned no valid `expires` timestamp.
+    pub fn expires_at(&self) -> Option<DateTime<Utc>> {
+        self.transport.session.read().unwrap().expires
+    }
+    
+    /// Returns true if no token is set or the session expires within the configured skew
+    /// (`WinCCUnifiedClientBuilder::token_expiry_skew`, default 30s).
+    /// 
+    /// A token with unknown expiry (see `expires_at`) is reported as not expired.
+    pub fn is_token_expired(&self) -> bool {
+        let session = self.transport.session.read().unwrap();
+        if session.token.is_none() {
+            return true;
+        }
+        let skew = chrono::Duration::from_std(self.token_expiry_skew).unwrap_or(chrono::Duration::MAX);
+        session
+            .expires
+            .is_some_and(|expires| expires.signed_duration_since(Utc::now()) <= skew)
+    }
+    
+    /// Time at which the server logs the user off for inactivity (`User::auto_logoff_sec`)
+    /// unless another request is sent
+    /// 
+    /// Computed from the last successful request of this client (and its clones) since the
+    /// last login or session extension, which reported the auto-logoff period. Activity of
+    /// other clients using the same session is not seen, so the actual deadline may be later.
+    /// 
+    /// Returns None if no session is active, the user has no auto-logoff, or the token was
+    /// set without a login (`set_token`, builder `token`).
+    /// 
+    /// # Example
+    /// ```no_run
+    /// # let client = winccua_graphql_client::WinCCUnifiedClient::new("https://your-server/graphql");
+    /// if let Some(remaining) = client.auto_logoff_remaining() {
+    ///     if remaining.as_secs() <= 60 {
+    ///         // warn "you'll be logged off in 60s" and offer to call extend_session()
+    ///     }
+    /// }
+    /// ```
+    pub fn auto_logoff_at(&self) -> Option<DateTime<Utc>> {
+        self.transport.session.read().unwrap().auto_logoff_at()
+    }
+    
+    /// Time left until `auto_logoff_at`, zero if the deadline has passed
+    pub fn auto_logoff_remaining(&self) -> Option<Duration> {
+        let remaining = self.auto_logoff_at()?.signed_duration_since(Utc::now());
+        Some(remaining.to_std().unwrap_or(Duration::ZERO))
+    }
+    
+    /// Start a background thread that extends the session before the token expires.
+    /// 
+    /// The thread parses the `expires` timestamp of the current session and calls
+    /// `extend_session` after 80% of the remaining lifetime has elapsed. The new token
+    /// and expiry are stored in the client and pushed to the WebSocket client if connected.
+    /// Failed extensions are logged and retried after 30 seconds.
+    /// 
+    /// With `WinCCUnifiedClientBuilder::prevent_auto_logoff` the session is also extended
+    /// after 80% of the remaining time until `auto_logoff_at`, so an idle client is not
+    /// logged off for inactivity.
+    /// 
+    /// The thread runs until `disable_auto_extend` or `logout` is called, or the client
+    /// is dropped. Calling this again restarts the thread.
+    /// 
+    /// Errors:
+    /// - SessionError - No session expiry is known (login first)
+    pub fn enable_auto_extend(&self) -> WinCCResult<()> {
+        if self.expires_at().is_none() {
+            return Err(WinCCError::SessionError("No valid session expiry known, login first".to_string()));
         }
         
-        let result: Value = response.json()?;
-        
-        if let Some(errors) = result.get("errors") {
-            if let Some(error_array) = errors.as_array() {
-                if !error_array.is_empty() {
-                    return Err(WinCCError::from_graphql_errors(error_array));
-                }
-            }
+        self.disable_auto_extend();
+        *self.auto_extend.lock().unwrap() = Some(AutoExtendHandle::spawn(self.transport.clone(), self.prevent_auto_logoff));
+        Ok(())
+    }
+    
+    /// Stop the auto-extend thread started by `enable_auto_extend` and wait for it to exit
+    pub fn disable_auto_extend(&self) {
+        let auto_extend = self.auto_extend.lock().unwrap().take();
+        if let Some(mut auto_extend) = auto_extend {
+            auto_extend.stop();
         }
-        
-        Ok(result.get("data").unwrap_or(&json!({})).clone())
+    }
+    
+    /// Returns true if the auto-extend thread is running
+    pub fn is_auto_extend_enabled(&self) -> bool {
+        self.auto_extend.lock().unwrap().is_some()
     }
     
     /// Logs a user in based on their username and password.
@@ -140,10 +2060,13 @@ impl WinCCUnifiedClient {
     /// }
     /// ```
     /// 
+    /// A non-zero `error.code` is returned as `LoginError` with that code and description even
+    /// if a token is present; a "0" or missing code is a success when the token is not empty.
+    /// 
     /// Errors:
     /// - 101 - Incorrect credentials provided
     /// - 102 - UMC error
-    pub fn login(&mut self, username: &str, password: &str) -> WinCCResult<Session> {
+    pub fn login(&self, username: &str, password: &str) -> WinCCResult<Session> {
         let variables = json!({
             "username": username,
             "password": password
@@ -152,19 +2075,9 @@ impl WinCCUnifiedClient {
         let result = self.request(mutations::LOGIN, Some(variables))?;
         let login_result: Session = serde_json::from_value(result["login"].clone())?;
         
-        if let Some(ref token) = login_result.token {
-            self.set_token(token);
-        }
-        
-        if login_result.token.is_some() {
-            Ok(login_result)
-        } else {
-            let error_msg = login_result.error
-                .as_ref()
-                .and_then(|e| e.description.as_ref())
-                .map_or("Unknown error", |v| v);
-            Err(WinCCError::LoginError(error_msg.to_string()))
-        }
+        let token = session_token(&login_result).map_err(login_error)?;
+        self.store_session(&login_result, token, AuthMethod::Password);
+        Ok(login_result)
     }
     
     /// Returns information about the current session. If all_sessions is true, returns all sessions of the current user.
@@ -205,6 +2118,42 @@ impl WinCCUnifiedClient {
         self.get_session(false)
     }
     
+    /// Checks that the server is reachable and the current token is accepted, without side effects.
+    /// 
+    /// Sends a `session` query that requests only the expiry of the current session.
+    /// 
+    /// Returns: true if the session query succeeded, false if the server answered with a
+    /// GraphQL error that is not related to authentication
+    /// 
+    /// Errors:
+    /// - AuthenticationError - The server rejected the token (HTTP 401/403, an authentication
+    ///   GraphQL error or a session error code), or no session is active
+    /// - HttpError - The server could not be reached
+    pub fn ping(&self) -> WinCCResult<bool> {
+        let result = match self.request(queries::PING, None) {
+            Ok(result) => result,
+            Err(e) if is_auth_failure(&e) => return Err(WinCCError::AuthenticationError(e.to_string())),
+            Err(WinCCError::GraphQLError { .. }) | Err(WinCCError::ApiError { .. }) => return Ok(false),
+            Err(e) => return Err(e),
+        };
+        
+        let sessions: Vec<Session> = serde_json::from_value(result["session"].clone()).unwrap_or_default();
+        let session = sessions
+            .first()
+            .ok_or_else(|| WinCCError::AuthenticationError("No active session".to_string()))?;
+        if let Some(error) = &session.error {
+            let code = error.code.as_deref().map(str::trim).unwrap_or("0");
+            if !code.is_empty() && code != "0" {
+                return Err(WinCCError::AuthenticationError(format!(
+                    "{} - {}",
+                    code,
+                    error.description.as_deref().unwrap_or("Unknown error")
+                )));
+            }
+        }
+        Ok(true)
+    }
+    
     /// Queries tag values based on the provided names list. If direct_read is true, values are taken directly from PLC.
     /// 
     /// Returns: Array of TagValueResult objects with tag name, value, and quality information
@@ -233,25 +2182,143 @@ impl WinCCUnifiedClient {
     /// }]
     /// ```
     /// 
+    /// Direct reads are limited by `max_direct_read_batch` and split by `direct_read_chunk_size`
+    /// if configured on the builder.
+    /// 
     /// Errors:
+    /// - InvalidParameter - A direct read of more tags than `max_direct_read_batch`
     /// - 2 - Cannot resolve provided name
     /// - 202 - Only leaf elements of a Structure Tag can be addressed
     pub fn get_tag_values(&self, names: &[String], direct_read: bool) -> WinCCResult<Vec<TagValueResult>> {
-        let variables = json!({
-            "names": names,
-            "directRead": direct_read
-        });
-        
-        let result = self.request(queries::TAG_VALUES, Some(variables))?;
-        let tag_values: Vec<TagValueResult> = serde_json::from_value(result["tagValues"].clone())?;
+        self.get_tag_values_with_query(queries::TAG_VALUES, names, direct_read)
+    }
+    
+    /// Queries tag values like `get_tag_values`, with a custom query instead of `queries::TAG_VALUES`
+    /// 
+    /// The query receives the same `$names` and `$directRead` variables and must select the
+    /// `tagValues` field, e.g. a copy of `queries::TAG_VALUES` without the quality fields.
+    /// Fields left out of the selection are `None` in the result.
+    pub fn get_tag_values_with_query(&self, query: &str, names: &[String], direct_read: bool) -> WinCCResult<Vec<TagValueResult>> {
+        let mut tag_values = Vec::with_capacity(names.len());
+        for chunk in self.tag_value_chunks(names, direct_read)? {
+            let variables = json!({
+                "names": chunk,
+                "directRead": direct_read
+            });
+            
+            let result = self.request(query, Some(variables))?;
+            let chunk_values: Vec<TagValueResult> = serde_json::from_value(result["tagValues"].clone())?;
+            tag_values.extend(chunk_values);
+        }
         Ok(tag_values)
     }
     
+    /// Names per `tagValues` request: one request for cached reads, direct reads checked
+    /// against `max_direct_read_batch` and split by `direct_read_chunk_size`
+    fn tag_value_chunks<'a>(&self, names: &'a [String], direct_read: bool) -> WinCCResult<Vec<&'a [String]>> {
+        if !direct_read {
+            return Ok(vec![names]);
+        }
+        if let Some(max) = self.max_direct_read_batch {
+            if names.len() > max {
+                return Err(WinCCError::InvalidParameter(format!(
+                    "Direct read of {} tags exceeds max_direct_read_batch ({})", names.len(), max
+                )));
+            }
+        }
+        match self.direct_read_chunk_size {
+            Some(size) if names.len() > size => Ok(names.chunks(size).collect()),
+            _ => Ok(vec![names]),
+        }
+    }
+    
+    /// Queries tag values like `get_tag_values`, but keeps the results if the server reports
+    /// GraphQL errors for some of the tags.
+    /// 
+    /// Returns: The TagValueResult objects returned by the server and the GraphQL errors sent
+    /// alongside them (empty if the request fully succeeded)
+    /// 
+    /// Errors:
+    /// - InvalidParameter - A direct read of more tags than `max_direct_read_batch`
+    /// - GraphQLError / ApiError - The server returned errors and no tag values
+    pub fn get_tag_values_partial(&self, names: &[String], direct_read: bool) -> WinCCResult<(Vec<TagValueResult>, Vec<GraphQLError>)> {
+        let mut tag_values = Vec::with_capacity(names.len());
+        let mut errors = Vec::new();
+        for chunk in self.tag_value_chunks(names, direct_read)? {
+            let variables = json!({
+                "names": chunk,
+                "directRead": direct_read
+            });
+            
+            let (chunk_values, chunk_errors) = self.transport.request_partial(queries::TAG_VALUES, Some(variables), "tagValues")?;
+            tag_values.extend(serde_json::from_value::<Vec<TagValueResult>>(chunk_values)?);
+            errors.extend(chunk_errors);
+        }
+        Ok((tag_values, errors))
+    }
+    
+    /// Queries tag values and fails if any tag could not be read
+    /// 
+    /// Returns: (name, value) pairs in the order of the results
+    /// 
+    /// Errors:
+    /// - TagError - A tag returned an error (non-zero code) or no value; the message lists
+    ///   each offending name with its error
+    pub fn get_tag_values_strict(&self, names: &[String], direct_read: bool) -> WinCCResult<Vec<(String, TagValue)>> {
+        let results = self.get_tag_values(names, direct_read)?;
+        let mut values = Vec::with_capacity(results.len());
+        let mut failed = Vec::new();
+        
+        for (index, result) in results.into_iter().enumerate() {
+            let name = result.name.or_else(|| names.get(index).cloned()).unwrap_or_default();
+            match (result.error.filter(ErrorInfo::is_error), result.value) {
+                (Some(error), _) => failed.push(error.message_for(&name)),
+                (None, Some(value)) => values.push((name, value)),
+                (None, None) => failed.push(format!("{}: no value", name)),
+            }
+        }
+        
+        if !failed.is_empty() {
+            return Err(WinCCError::TagError(format!("Failed to read tags: {}", failed.join("; "))));
+        }
+        Ok(values)
+    }
+    
     /// Queries tag values (without direct read)
     pub fn get_tag_values_simple(&self, names: &[String]) -> WinCCResult<Vec<TagValueResult>> {
         self.get_tag_values(names, false)
     }
     
+    /// Queries the value of a single tag (without direct read)
+    /// 
+    /// Returns: The TagValueResult of the tag; a per-tag error is returned in its `error` field
+    /// 
+    /// Errors:
+    /// - TagError - The server did not return exactly one result
+    pub fn get_tag_value(&self, name: &str) -> WinCCResult<TagValueResult> {
+        let mut results = self.get_tag_values_simple(&[name.to_string()])?;
+        if results.len() != 1 {
+            return Err(WinCCError::TagError(format!(
+                "Expected 1 result for tag {}, got {}", name, results.len()
+            )));
+        }
+        Ok(results.remove(0))
+    }
+    
+    /// Reads the value of a single tag as floating point number
+    /// 
+    /// Returns: The value, or None if the tag has no value or it is not a number
+    /// 
+    /// Errors:
+    /// - TagError - The server returned an error for the tag
+    pub fn read_f64(&self, name: &str) -> WinCCResult<Option<f64>> {
+        let result = self.get_tag_value(name)?;
+        if let Some(error) = result.error.as_ref().filter(|e| e.is_error()) {
+            return Err(WinCCError::TagError(error.message_for(name)));
+        }
+        Ok(result.value.as_ref().and_then(TagValue::as_f64))
+    }
+    
     /// Queries logged tag values from the database. Names must be LoggingTag names or Tag names (if only one logging tag exists).
     /// 
     /// Returns: Array of LoggedTagValuesResult objects with logging tag name, error info, and array of logged values
@@ -283,10 +2350,19 @@ impl WinCCUnifiedClient {
     /// }]
     /// ```
     /// 
-    /// Sorting modes: TIME_ASC, TIME_DESC
-    /// Bounding modes: NO_BOUNDING_VALUES, LEFT_BOUNDING_VALUES, RIGHT_BOUNDING_VALUES, LEFTRIGHT_BOUNDING_VALUES
+    /// Sorting modes: SortingMode::TimeAsc, SortingMode::TimeDesc
+    /// Bounding modes: BoundingMode::None, BoundingMode::Left, BoundingMode::Right, BoundingMode::LeftRight
+    /// 
+    /// Use BoundingMode::LeftRight for trend displays that need anchor points just outside the time window.
+    /// Left and right refer to time regardless of the sorting mode, so with SortingMode::TimeDesc the
+    /// left bounding value comes last.
+    /// 
+    /// With only `start_time` given the search runs forward and needs SortingMode::TimeAsc, with only
+    /// `end_time` it runs backward and needs SortingMode::TimeDesc; both need `max_number_of_values` > 0.
+    /// With both times any sorting mode can be used.
     /// 
     /// Errors:
+    /// - InvalidParameter - A one-sided time window with the wrong sorting mode or without `max_number_of_values`
     /// - 1 - Generic error
     /// - 2 - Cannot resolve provided name
     /// - 3 - Argument error
@@ -296,12 +2372,37 @@ impl WinCCUnifiedClient {
         start_time: Option<&str>,
         end_time: Option<&str>,
         max_number_of_values: i32,
-        sorting_mode: &str,
+        sorting_mode: SortingMode,
+        bounding_mode: BoundingMode,
+    ) -> WinCCResult<Vec<LoggedTagValuesResult>> {
+        self.get_logged_tag_values_with_query(
+            queries::LOGGED_TAG_VALUES, names, start_time, end_time, max_number_of_values, sorting_mode, bounding_mode,
+        )
+    }
+    
+    /// Queries logged tag values like `get_logged_tag_values`, with a custom query instead of
+    /// `queries::LOGGED_TAG_VALUES`
+    /// 
+    /// The query receives the same variables and must select the `loggedTagValues` field.
+    /// Fields left out of the selection are `None` in the result.
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_logged_tag_values_with_query(
+        &self,
+        query: &str,
+        names: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        max_number_of_values: i32,
+        sorting_mode: SortingMode,
+        bounding_mode: BoundingMode,
     ) -> WinCCResult<Vec<LoggedTagValuesResult>> {
+        check_logged_tag_values_window(start_time, end_time, max_number_of_values, sorting_mode)?;
+        
         let mut variables = json!({
             "names": names,
             "maxNumberOfValues": max_number_of_values,
-            "sortingMode": sorting_mode
+            "sortingMode": sorting_mode.as_str(),
+            "boundingValuesMode": bounding_mode.as_str()
         });
         
         if let Some(start) = start_time {
@@ -311,12 +2412,108 @@ impl WinCCUnifiedClient {
             variables["endTime"] = json!(end);
         }
         
-        let result = self.request(queries::LOGGED_TAG_VALUES, Some(variables))?;
+        let result = self.request(query, Some(variables))?;
         let logged_values: Vec<LoggedTagValuesResult> = serde_json::from_value(result["loggedTagValues"].clone())?;
         Ok(logged_values)
     }
     
-    /// Queries logged tag values with default sorting (TIME_ASC)
+    /// Queries logged tag values by Tag name, for all logging tags configured for each tag
+    /// 
+    /// Resolves every tag to its logging tags with `get_logging_tags_for` (one browse per tag)
+    /// and queries the values of all of them in one `loggedTagValues` request. A tag with
+    /// several logging tags yields one entry per logging tag.
+    /// 
+    /// Returns: LoggedTagValuesResult objects keyed by logging tag name as returned by browse
+    /// (e.g. `System1::HMI_Tag_1:LoggingTag_1`)
+    /// 
+    /// Errors:
+    /// - TagError - No logging tag is configured for one of the tags
+    /// - 2 - Cannot resolve provided name
+    /// - 3 - Argument error
+    pub fn get_logged_tag_values_by_tag(
+        &self,
+        tag_names: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        max_number_of_values: i32,
+        sorting_mode: SortingMode,
+        bounding_mode: BoundingMode,
+    ) -> WinCCResult<HashMap<String, LoggedTagValuesResult>> {
+        let mut logging_tag_names = Vec::new();
+        for tag_name in tag_names {
+            let logging_tags = self.get_logging_tags_for(tag_name)?;
+            if logging_tags.is_empty() {
+                return Err(WinCCError::TagError(format!("No logging tag configured for {}", tag_name)));
+            }
+            logging_tag_names.extend(logging_tags.into_iter().filter_map(|logging_tag| logging_tag.name));
+        }
+        if logging_tag_names.is_empty() {
+            return Ok(HashMap::new());
+        }
+        
+        let results = self.get_logged_tag_values(
+            &logging_tag_names,
+            start_time,
+            end_time,
+            max_number_of_values,
+            sorting_mode,
+            bounding_mode,
+        )?;
+        Ok(logging_tag_names
+            .into_iter()
+            .zip(results)
+            .map(|(requested, result)| (result.logging_tag_name.clone().unwrap_or(requested), result))
+            .collect())
+    }
+    
+    /// Read the complete history of the given tags in pages of at most `page_size` values.
+    /// 
+    /// Each page continues at the timestamp of the last value of the previous page. Values at
+    /// that timestamp which were already returned are skipped, so no value is returned twice
+    /// or left out at page boundaries.
+    /// 
+    /// `startTime` is treated as exclusive, as documented for `loggedAlarms` in the schema (it
+    /// is not specified for `loggedTagValues`): a value at exactly `start_time` is not returned,
+    /// and each further page is requested from 1ms before the last timestamp, so millisecond
+    /// timestamps are assumed. `logged_alarms_paged` uses the same rule. Values before the
+    /// cursor are dropped, so a server with an inclusive `startTime` returns no duplicates
+    /// either. Without `start_time` the history is read from the start
+    /// of the log (1970-01-01, the schema default), as the server rejects ascending queries
+    /// with only an end time.
+    /// 
+    /// A page cannot continue within one timestamp, so `page_size` must exceed the number of
+    /// values of a tag that share a timestamp. Otherwise the iterator yields a `TagError` and
+    /// stops instead of skipping the remaining values at that timestamp.
+    /// 
+    /// ```no_run
+    /// # use winccua_graphql_client::WinCCUnifiedClient;
+    /// # let client = WinCCUnifiedClient::new("http://localhost:4000/graphql");
+    /// let names = vec!["HMI_Tag_1:LoggingTag_1".to_string()];
+    /// for page in client.logged_tag_values_paged(&names, Some("2024-01-01T00:00:00Z"), Some("2024-01-01T06:00:00Z"), 1000) {
+    ///     let page = page?;
+    ///     println!("{:?}: {} values", page.logging_tag_name, page.values.map_or(0, |v| v.len()));
+    /// }
+    /// # Ok::<(), winccua_graphql_client::WinCCError>(())
+    /// ```
+    pub fn logged_tag_values_paged(
+        &self,
+        names: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        page_size: i32,
+    ) -> LoggedTagValuesPages<'_> {
+        LoggedTagValuesPages {
+            client: self,
+            names: names.iter().cloned().collect(),
+            start_time: Some(start_time.unwrap_or("1970-01-01T00:00:00.000Z").to_string()),
+            end_time: end_time.map(str::to_string),
+            page_size,
+            cursor: None,
+            done: false,
+        }
+    }
+    
+    /// Queries logged tag values with default sorting (TIME_ASC) and no bounding values (NO_BOUNDING_VALUES)
     pub fn get_logged_tag_values_simple(
         &self,
         names: &[String],
@@ -324,7 +2521,25 @@ impl WinCCUnifiedClient {
         end_time: Option<&str>,
         max_number_of_values: i32,
     ) -> WinCCResult<Vec<LoggedTagValuesResult>> {
-        self.get_logged_tag_values(names, start_time, end_time, max_number_of_values, "TIME_ASC")
+        self.get_logged_tag_values(names, start_time, end_time, max_number_of_values, SortingMode::TimeAsc, BoundingMode::None)
+    }
+    
+    /// Queries logged tag values with sorting and bounding modes given as GraphQL enum strings
+    #[deprecated(note = "use get_logged_tag_values with SortingMode and BoundingMode")]
+    pub fn get_logged_tag_values_str(
+        &self,
+        names: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        max_number_of_values: i32,
+        sorting_mode: &str,
+        bounding_mode: &str,
+    ) -> WinCCResult<Vec<LoggedTagValuesResult>> {
+        let sorting_mode: SortingMode = serde_json::from_value(json!(sorting_mode))
+            .map_err(|_| WinCCError::InvalidParameter(format!("Invalid sorting mode: {}", sorting_mode)))?;
+        let bounding_mode: BoundingMode = serde_json::from_value(json!(bounding_mode))
+            .map_err(|_| WinCCError::InvalidParameter(format!("Invalid bounding mode: {}", bounding_mode)))?;
+        self.get_logged_tag_values(names, start_time, end_time, max_number_of_values, sorting_mode, bounding_mode)
     }
     
     /// Returns a nonce that can be used with e.g. the UMC SWAC login method.
@@ -346,17 +2561,67 @@ impl WinCCUnifiedClient {
     
     /// Returns the URL of the identity provider for UMC SWAC authentication.
     /// 
-    /// Returns: String URL where user should be redirected for SWAC login
+    /// Returns: Absolute http(s) URL where user should be redirected for SWAC login; query
+    /// parameters (e.g. the nonce) can be added with `query_pairs_mut`
     /// 
     /// JSON Structure: 
     /// ```json
     /// "https://identity-provider.example.com/auth"
     /// ```
-    pub fn get_identity_provider_url(&self) -> WinCCResult<String> {
+    /// 
+    /// Errors:
+    /// - OperationFailed - The server returned no URL, a relative URL or one that is not http(s)
+    pub fn get_identity_provider_url(&self) -> WinCCResult<Url> {
         let result = self.request(queries::IDENTITY_PROVIDER_URL, None)?;
         let url = result["identityProviderURL"].as_str()
             .ok_or_else(|| WinCCError::OperationFailed("Invalid identity provider URL".to_string()))?;
-        Ok(url.to_string())
+        match Url::parse(url) {
+            Ok(parsed) if matches!(parsed.scheme(), "http" | "https") && parsed.has_host() => Ok(parsed),
+            Ok(_) => Err(WinCCError::OperationFailed(format!("Identity provider URL is not an http(s) URL: {}", url))),
+            Err(e) => Err(WinCCError::OperationFailed(format!("Invalid identity provider URL {:?}: {}", url, e))),
+        }
+    }
+    
+    /// Fetch the server's GraphQL schema with the standard introspection query.
+    /// 
+    /// Useful for tooling that needs to discover which operations and types the connected
+    /// WinCC Unified version supports. Servers may disable introspection.
+    /// 
+    /// Returns: The `__schema` object (query/mutation/subscription root type names, all types with
+    /// their fields, arguments and enum values, and the directives)
+    /// 
+    /// Errors:
+    /// - GraphQLError - The server rejected the introspection query
+    /// - OperationFailed - The response has no `__schema` object
+    pub fn introspect(&self) -> WinCCResult<Value> {
+        let mut result = self.request(queries::INTROSPECTION, None)?;
+        match result.get_mut("__schema").map(Value::take) {
+            Some(schema) if schema.is_object() => Ok(schema),
+            _ => Err(WinCCError::OperationFailed("Introspection response contains no schema".to_string())),
+        }
+    }
+    
+    /// Names of the fields of the query root type, e.g. `tagValues`, `browse`, `activeAlarms`
+    /// 
+    /// Runs `introspect` and returns the names in schema order.
+    /// 
+    /// Errors:
+    /// - GraphQLError - The server rejected the introspection query
+    /// - OperationFailed - The schema has no query type
+    pub fn list_query_fields(&self) -> WinCCResult<Vec<String>> {
+        let schema = self.introspect()?;
+        let query_type = schema["queryType"]["name"]
+            .as_str()
+            .ok_or_else(|| WinCCError::OperationFailed("Schema has no query type".to_string()))?;
+        let fields = schema["types"]
+            .as_array()
+            .and_then(|types| types.iter().find(|t| t["name"] == query_type))
+            .and_then(|t| t["fields"].as_array())
+            .ok_or_else(|| WinCCError::OperationFailed(format!("Query type {} not found in schema", query_type)))?;
+        Ok(fields
+            .iter()
+            .filter_map(|field| field["name"].as_str().map(str::to_string))
+            .collect())
     }
     
     /// Queries tags, elements, types, alarms, logging tags based on filter criteria. 
@@ -401,39 +2666,272 @@ impl WinCCUnifiedClient {
     
     /// Browse with default parameters
     pub fn browse_simple(&self) -> WinCCResult<Vec<BrowseTagsResult>> {
-        self.browse(&[], &[], &[], "en-US")
+        self.browse(&[], &[], &[], &self.browse_language())
+    }
+    
+    /// List the alarm classes configured on the server, e.g. for a legend or a class filter
+    /// 
+    /// Browses for `ALARMCLASS` objects with display names in the browse language
+    /// (`set_browse_language`). The schema has no query for class details such as priority
+    /// ranges, colors or state machine; these are reported per alarm (see `AlarmClass`).
+    /// 
+    /// Returns: Array of AlarmClass objects, results without name are skipped
+    /// 
+    /// Errors:
+    /// - 1 - Generic error
+    pub fn get_alarm_classes(&self) -> WinCCResult<Vec<AlarmClass>> {
+        let results = self.browse(&[], &["ALARMCLASS".to_string()], &[], &self.browse_language())?;
+        Ok(results
+            .into_iter()
+            .filter_map(|result| {
+                Some(AlarmClass {
+                    name: result.name?,
+                    display_name: result.display_name,
+                })
+            })
+            .collect())
+    }
+    
+    /// List the logging tags configured for a tag, e.g. to find the name to pass to
+    /// `get_logged_tag_values`.
+    /// 
+    /// Browses for `LOGGINGTAG` objects below `tag_name` (logging tags are addressed as
+    /// `Tag:LoggingTag`) and keeps those whose base tag is `tag_name`, with or without
+    /// system prefix.
+    /// 
+    /// Returns: Array of BrowseTagsResult objects of the logging tags, empty if none are configured
+    /// 
+    /// Errors:
+    /// - 2 - Cannot resolve provided name
+    pub fn get_logging_tags_for(&self, tag_name: &str) -> WinCCResult<Vec<BrowseTagsResult>> {
+        let results = self.browse(
+            &[format!("{}:*", tag_name)],
+            &["LOGGINGTAG".to_string()],
+            &[],
+            &self.browse_language(),
+        )?;
+        
+        Ok(results
+            .into_iter()
+            .filter(|result| {
+                result.name.as_deref().and_then(logging_tag_parent).is_some_and(|parent| {
+                    parent == tag_name || parent.strip_suffix(tag_name).is_some_and(|prefix| prefix.ends_with("::"))
+                })
+            })
+            .collect())
+    }
+    
+    /// List the direct children of a structure tag (e.g. `HMI_Struct`) or of a system
+    /// (given with separator, e.g. `System1::`).
+    /// 
+    /// Browses with the name filter `parent.*` (`System1::*` for a system) and drops
+    /// results further down the hierarchy, so members of nested structures are not included.
+    /// 
+    /// Returns: Array of BrowseTagsResult objects of the children, empty if there are none
+    /// 
+    /// Errors:
+    /// - InvalidParameter - `parent` is empty
+    /// - 2 - Cannot resolve provided name
+    pub fn browse_children(&self, parent: &str) -> WinCCResult<Vec<BrowseTagsResult>> {
+        if parent.is_empty() {
+            return Err(WinCCError::InvalidParameter("Parent name must not be empty".to_string()));
+        }
+        
+        let results = self.browse(&[children_name_filter(parent)], &[], &[], &self.browse_language())?;
+        Ok(results
+            .into_iter()
+            .filter(|result| {
+                result
+                    .name
+                    .as_deref()
+                    .and_then(|name| path_below(name, parent))
+                    .is_some_and(|path| !path.is_empty() && !path.contains('.'))
+            })
+            .collect())
+    }
+    
+    /// Read all leaf elements of a structure tag (or all tags of a system, given as `System1::`)
+    /// 
+    /// Browses the children with `browse_children`, descends into nested `STRUCTURETAG`
+    /// children and reads the `TAG`/`SIMPLETAG` leaves with one `get_tag_values` request
+    /// (without direct read). Other objects (alarms, logging tags, ...) are skipped.
+    /// 
+    /// Returns: The TagValueResult objects of the leaves in browse order, empty if the
+    /// structure has no leaves; per-tag errors are returned in their `error` field
+    /// 
+    /// Errors:
+    /// - InvalidParameter - `parent` is empty
+    /// - 2 - Cannot resolve provided name
+    pub fn read_structure(&self, parent: &str) -> WinCCResult<Vec<TagValueResult>> {
+        let mut leaves = Vec::new();
+        self.collect_structure_leaves(parent, &mut leaves)?;
+        if leaves.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.get_tag_values(&leaves, false)
+    }
+    
+    fn collect_structure_leaves(&self, parent: &str, leaves: &mut Vec<String>) -> WinCCResult<()> {
+        for child in self.browse_children(parent)? {
+            let Some(name) = child.name else { continue };
+            match child.object_type {
+                Some(ObjectType::StructureTag) => self.collect_structure_leaves(&name, leaves)?,
+                Some(ObjectType::Tag) | Some(ObjectType::SimpleTag) | None => leaves.push(name),
+                Some(_) => {}
+            }
+        }
+        Ok(())
+    }
+    
+    /// Write the members of a structure tag from a nested JSON object
+    /// 
+    /// Structure tags cannot be written as a whole (202). This browses the leaves of `parent`
+    /// like `read_structure`, maps each field path of `value` to its leaf (e.g.
+    /// `{"Motor": {"Speed": 5}}` to `HMI_Struct.Motor.Speed`) and writes all given leaves with
+    /// one `write_tag_values` request, in browse order. Leaves missing in `value` are not written.
+    /// 
+    /// ```no_run
+    /// # let client = winccua_graphql_client::WinCCUnifiedClient::new("https://your-server/graphql");
+    /// use serde_json::json;
+    /// 
+    /// let results = client.write_structure("HMI_Struct", &json!({ "Setpoint": 5, "Mode": 2 })).unwrap();
+    /// ```
+    /// 
+    /// Returns: Array of WriteTagValuesResult objects, one per written leaf; empty (without a
+    /// request) if `value` has no fields
+    /// 
+    /// Errors:
+    /// - InvalidParameter - `parent` is empty, `value` is not an object, or a field does not
+    ///   match a leaf of the structure (nothing is written then)
+    /// - 2 - Cannot resolve provided name
+    /// - 201 - Cannot convert provided value to data type
+    pub fn write_structure(&self, parent: &str, value: &Value) -> WinCCResult<Vec<WriteTagValuesResult>> {
+        if !value.is_object() {
+            return Err(WinCCError::InvalidParameter("Structure value must be a JSON object".to_string()));
+        }
+        let mut fields = Vec::new();
+        flatten_structure_value("", value, &mut fields);
+        if fields.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let mut leaves = Vec::new();
+        self.collect_structure_leaves(parent, &mut leaves)?;
+        let mut inputs = Vec::new();
+        for leaf in &leaves {
+            let path = path_below(leaf, parent);
+            if let Some(index) = fields.iter().position(|(field, _)| Some(field.as_str()) == path) {
+                inputs.push(TagValueInput::new(leaf, fields.remove(index).1));
+            }
+        }
+        if !fields.is_empty() {
+            let unknown: Vec<_> = fields.into_iter().map(|(field, _)| field).collect();
+            return Err(WinCCError::InvalidParameter(format!(
+                "No leaf of {} for field(s): {}", parent, unknown.join(", ")
+            )));
+        }
+        
+        self.write_tag_values(&inputs, None, None)
     }
     
     /// Query active alarms from the provided systems using ChromQueryLanguage filter.
     /// 
     /// Returns: Array of ActiveAlarm objects with comprehensive alarm information
     /// 
-    /// JSON Structure: (truncated for brevity, see ActiveAlarm struct for full definition)
-    /// ```json
-    /// [{
-    ///   "name": "string",
-    ///   "instanceID": 123,
-    ///   "alarmGroupID": 456,
-    ///   "raiseTime": "2023-12-31T23:59:59.999Z",
-    ///   "acknowledgmentTime": "2023-12-31T23:59:59.999Z",
-    ///   "state": "RAISED",
-    ///   "priority": 10,
-    ///   "eventText": ["Alarm message"],
-    ///   "...": "... (see ActiveAlarm struct for complete structure)"
-    /// }]
-    /// ```
+    /// JSON Structure: (truncated for brevity, see ActiveAlarm struct for full definition)
+    /// ```json
+    /// [{
+    ///   "name": "string",
+    ///   "instanceID": 123,
+    ///   "alarmGroupID": 456,
+    ///   "raiseTime": "2023-12-31T23:59:59.999Z",
+    ///   "acknowledgmentTime": "2023-12-31T23:59:59.999Z",
+    ///   "state": "RAISED",
+    ///   "priority": 10,
+    ///   "eventText": ["Alarm message"],
+    ///   "...": "... (see ActiveAlarm struct for complete structure)"
+    /// }]
+    /// ```
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    /// - 302 - Invalid language
+    /// - 303 - Invalid filter language
+    pub fn get_active_alarms(
+        &self,
+        system_names: &[String],
+        filter_string: &str,
+        filter_language: &str,
+        languages: &[String],
+    ) -> WinCCResult<Vec<ActiveAlarm>> {
+        self.get_active_alarms_with_fields(system_names, filter_string, filter_language, languages, AlarmFieldSet::Full)
+    }
+    
+    /// Get active alarms with default parameters
+    pub fn get_active_alarms_simple(&self) -> WinCCResult<Vec<ActiveAlarm>> {
+        let languages = self.default_languages();
+        self.get_active_alarms(&[], "", &languages[0], &languages)
+    }
+    
+    /// Query active alarms requesting only the fields of the given field set.
+    /// 
+    /// Same parameters as `get_active_alarms`; fields outside the field set are `None`.
+    /// `AlarmFieldSet::Full` is equivalent to `get_active_alarms`.
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn get_active_alarms_with_fields(
+        &self,
+        system_names: &[String],
+        filter_string: &str,
+        filter_language: &str,
+        languages: &[String],
+        fields: AlarmFieldSet,
+    ) -> WinCCResult<Vec<ActiveAlarm>> {
+        self.get_active_alarms_with_query(active_alarms_query(fields), system_names, filter_string, filter_language, languages)
+    }
+    
+    /// Query active alarms like `get_active_alarms`, with a custom query instead of `queries::ACTIVE_ALARMS`
+    /// 
+    /// The query receives the same `$systemNames`, `$filterString`, `$filterLanguage` and
+    /// `$languages` variables and must select the `activeAlarms` field, e.g. a trimmed copy of
+    /// `queries::ACTIVE_ALARMS`. Fields left out of the selection are `None` in the result.
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn get_active_alarms_with_query(
+        &self,
+        query: &str,
+        system_names: &[String],
+        filter_string: &str,
+        filter_language: &str,
+        languages: &[String],
+    ) -> WinCCResult<Vec<ActiveAlarm>> {
+        let variables = json!({
+            "systemNames": system_names,
+            "filterString": filter_string,
+            "filterLanguage": filter_language,
+            "languages": languages
+        });
+        
+        let result = self.request(query, Some(variables))?;
+        let active_alarms: Vec<ActiveAlarm> = serde_json::from_value(result["activeAlarms"].clone())?;
+        Ok(active_alarms)
+    }
+    
+    /// Query active alarms with the `AlarmFieldSet::Minimal` fields for large list views.
+    /// 
+    /// Returns: Array of ActiveAlarmSummary objects (name, instance ID, state, priority, raise time, event text)
     /// 
     /// Errors:
     /// - 301 - Syntax error in query string
-    /// - 302 - Invalid language
-    /// - 303 - Invalid filter language
-    pub fn get_active_alarms(
+    pub fn get_active_alarms_minimal(
         &self,
         system_names: &[String],
         filter_string: &str,
         filter_language: &str,
         languages: &[String],
-    ) -> WinCCResult<Vec<ActiveAlarm>> {
+    ) -> WinCCResult<Vec<ActiveAlarmSummary>> {
         let variables = json!({
             "systemNames": system_names,
             "filterString": filter_string,
@@ -441,14 +2939,52 @@ impl WinCCUnifiedClient {
             "languages": languages
         });
         
-        let result = self.request(queries::ACTIVE_ALARMS, Some(variables))?;
-        let active_alarms: Vec<ActiveAlarm> = serde_json::from_value(result["activeAlarms"].clone())?;
+        let result = self.request(queries::ACTIVE_ALARMS_MINIMAL, Some(variables))?;
+        let active_alarms: Vec<ActiveAlarmSummary> = serde_json::from_value(result["activeAlarms"].clone())?;
         Ok(active_alarms)
     }
     
-    /// Get active alarms with default parameters
-    pub fn get_active_alarms_simple(&self) -> WinCCResult<Vec<ActiveAlarm>> {
-        self.get_active_alarms(&[], "", "en-US", &["en-US".to_string()])
+    /// Get a single active alarm by name and instance ID, e.g. after receiving an alarm notification.
+    /// 
+    /// Returns: The matching ActiveAlarm, or None if the alarm is not active
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn get_active_alarm_by_instance(&self, name: &str, instance_id: i32) -> WinCCResult<Option<ActiveAlarm>> {
+        let filter = AlarmFilterBuilder::new().name(name).instance_id(instance_id).build();
+        let languages = self.default_languages();
+        let alarms = self.get_active_alarms(&[], &filter, &languages[0], &languages)?;
+        Ok(alarms.into_iter().next())
+    }
+    
+    /// Get the active alarms matching a filter built with `AlarmFilterBuilder`, with the
+    /// default languages (see `set_default_languages`)
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn get_active_alarms_filtered(&self, filter: &AlarmFilterBuilder) -> WinCCResult<Vec<ActiveAlarm>> {
+        let languages = self.default_languages();
+        self.get_active_alarms(&[], &filter.build(), &languages[0], &languages)
+    }
+    
+    /// Get all active alarm instances of the given alarm names
+    /// 
+    /// Returns: Array of ActiveAlarm objects, empty if no names are given
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn get_active_alarms_by_names(&self, names: &[String]) -> WinCCResult<Vec<ActiveAlarm>> {
+        if names.is_empty() {
+            return Ok(Vec::new());
+        }
+        
+        let filter = names
+            .iter()
+            .map(|name| format!("name = {}", cql_string(name)))
+            .collect::<Vec<_>>()
+            .join(" OR ");
+        let languages = self.default_languages();
+        self.get_active_alarms(&[], &filter, &languages[0], &languages)
     }
     
     /// Query logged alarms from the storage system using ChromQueryLanguage filter and time boundaries.
@@ -475,6 +3011,7 @@ impl WinCCUnifiedClient {
     /// - 301 - Syntax error in query string
     /// - 302 - Invalid language (or not logged)
     /// - 303 - Invalid filter language (or not logged)
+    #[allow(clippy::too_many_arguments)]
     pub fn get_logged_alarms(
         &self,
         system_names: &[String],
@@ -484,6 +3021,30 @@ impl WinCCUnifiedClient {
         start_time: Option<&str>,
         end_time: Option<&str>,
         max_number_of_results: i32,
+    ) -> WinCCResult<Vec<LoggedAlarm>> {
+        self.get_logged_alarms_with_query(
+            queries::LOGGED_ALARMS, system_names, filter_string, filter_language, languages, start_time, end_time, max_number_of_results,
+        )
+    }
+    
+    /// Query logged alarms like `get_logged_alarms`, with a custom query instead of `queries::LOGGED_ALARMS`
+    /// 
+    /// The query receives the same variables and must select the `loggedAlarms` field.
+    /// Fields left out of the selection are `None` in the result.
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    #[allow(clippy::too_many_arguments)]
+    pub fn get_logged_alarms_with_query(
+        &self,
+        query: &str,
+        system_names: &[String],
+        filter_string: &str,
+        filter_language: &str,
+        languages: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        max_number_of_results: i32,
     ) -> WinCCResult<Vec<LoggedAlarm>> {
         let mut variables = json!({
             "systemNames": system_names,
@@ -500,14 +3061,69 @@ impl WinCCUnifiedClient {
             variables["endTime"] = json!(end);
         }
         
-        let result = self.request(queries::LOGGED_ALARMS, Some(variables))?;
+        let result = self.request(query, Some(variables))?;
         let logged_alarms: Vec<LoggedAlarm> = serde_json::from_value(result["loggedAlarms"].clone())?;
         Ok(logged_alarms)
     }
     
     /// Get logged alarms with default parameters
     pub fn get_logged_alarms_simple(&self) -> WinCCResult<Vec<LoggedAlarm>> {
-        self.get_logged_alarms(&[], "", "en-US", &["en-US".to_string()], None, None, 0)
+        let languages = self.default_languages();
+        self.get_logged_alarms(&[], "", &languages[0], &languages, None, None, 0)
+    }
+    
+    /// Read all logged alarms in a time window in pages of at most `page_size` alarms.
+    /// 
+    /// `get_logged_alarms` truncates the result at `max_number_of_results`. This iterator
+    /// requests further pages until the window is exhausted, using the `modificationTime`
+    /// of the last page as cursor (the server filters `startTime`/`endTime` on it, and unlike
+    /// `raiseTime` it differs for each state change of an alarm). Alarms with the same
+    /// modificationTime on both sides of a page boundary are de-duplicated by name and
+    /// instanceID. Pages are expected in ascending modificationTime order.
+    /// 
+    /// The schema documents `startTime` as exclusive (only alarms modified after it are
+    /// returned), so an alarm modified at exactly `start_time` is not returned, and each
+    /// further page is requested from 1ms before the last modificationTime.
+    /// `logged_tag_values_paged` uses the same rule.
+    /// 
+    /// A page cannot continue within one modificationTime, so `page_size` must exceed the
+    /// number of alarms that share a modificationTime. Otherwise the iterator yields an
+    /// `AlarmError` and stops instead of skipping the remaining alarms at that time.
+    /// 
+    /// ```no_run
+    /// # use winccua_graphql_client::WinCCUnifiedClient;
+    /// # let client = WinCCUnifiedClient::new("http://localhost:4000/graphql");
+    /// let languages = vec!["en-US".to_string()];
+    /// let alarms = client
+    ///     .logged_alarms_paged(&[], "", "en-US", &languages, Some("2024-01-01T00:00:00.000Z"), Some("2024-01-02T00:00:00.000Z"), 500)
+    ///     .collect::<Result<Vec<_>, _>>()?
+    ///     .concat();
+    /// println!("{} alarm entries", alarms.len());
+    /// # Ok::<(), winccua_graphql_client::WinCCError>(())
+    /// ```
+    #[allow(clippy::too_many_arguments)]
+    pub fn logged_alarms_paged(
+        &self,
+        system_names: &[String],
+        filter_string: &str,
+        filter_language: &str,
+        languages: &[String],
+        start_time: Option<&str>,
+        end_time: Option<&str>,
+        page_size: i32,
+    ) -> LoggedAlarmsPages<'_> {
+        LoggedAlarmsPages {
+            client: self,
+            system_names: system_names.to_vec(),
+            filter_string: filter_string.to_string(),
+            filter_language: filter_language.to_string(),
+            languages: languages.to_vec(),
+            start_time: start_time.map(str::to_string),
+            end_time: end_time.map(str::to_string),
+            page_size,
+            cursor: None,
+            done: false,
+        }
     }
     
     /// Logs a user in based on the claim and signed claim from UMC SWAC authentication.
@@ -516,10 +3132,13 @@ impl WinCCUnifiedClient {
     /// 
     /// JSON Structure: Same as login() method
     /// 
+    /// Failures are returned as `LoginError` with the code, like for `login`; on 103 a new
+    /// nonce must be requested before retrying.
+    /// 
     /// Errors:
     /// - 101 - Incorrect credentials provided
     /// - 103 - Nonce expired
-    pub fn login_swac(&mut self, claim: &str, signed_claim: &str) -> WinCCResult<Session> {
+    pub fn login_swac(&self, claim: &str, signed_claim: &str) -> WinCCResult<Session> {
         let variables = json!({
             "claim": claim,
             "signedClaim": signed_claim
@@ -528,18 +3147,48 @@ impl WinCCUnifiedClient {
         let result = self.request(mutations::LOGIN_SWAC, Some(variables))?;
         let login_result: Session = serde_json::from_value(result["loginSWAC"].clone())?;
         
-        if let Some(ref token) = login_result.token {
-            self.set_token(token);
-        }
-        
-        if login_result.token.is_some() {
-            Ok(login_result)
-        } else {
-            let error_msg = login_result.error
-                .as_ref()
-                .and_then(|e| e.description.as_ref())
-                .map_or("Unknown error", |v| v);
-            Err(WinCCError::LoginError(format!("SWAC login failed: {}", error_msg)))
+        let token = session_token(&login_result).map_err(login_error)?;
+        self.store_session(&login_result, token, AuthMethod::Swac);
+        Ok(login_result)
+    }
+    
+    /// Complete UMC SWAC login: fetch nonce and identity provider URL, authenticate, and log in.
+    /// 
+    /// `authenticate` receives the identity provider URL and nonce, performs the redirect to the
+    /// identity provider (e.g. in a browser) and returns the claim and signed claim. If the
+    /// login fails because the nonce expired (103) in the meantime, a fresh nonce is requested
+    /// and `authenticate` is called once more.
+    /// 
+    /// Returns: Session object containing user info, token, and expiry timestamp
+    /// 
+    /// Errors:
+    /// - OperationFailed - The server returned no nonce or an invalid identity provider URL
+    /// - 101 - Incorrect credentials provided
+    /// - 103 - Nonce expired (also after the retry)
+    /// - Any error returned by `authenticate`
+    pub fn swac_login_flow(
+        &self,
+        mut authenticate: impl FnMut(&SwacChallenge) -> WinCCResult<SwacClaim>,
+    ) -> WinCCResult<Session> {
+        let identity_provider_url = self.get_identity_provider_url()?;
+        let mut retried = false;
+        loop {
+            let nonce = self.get_nonce()?;
+            let challenge = SwacChallenge {
+                identity_provider_url: identity_provider_url.clone(),
+                nonce: nonce.value
+                    .filter(|value| !value.is_empty())
+                    .ok_or_else(|| WinCCError::OperationFailed("Server returned no nonce".to_string()))?,
+                valid_for: nonce.valid_for,
+            };
+            let claim = authenticate(&challenge)?;
+            match self.login_swac(&claim.claim, &claim.signed_claim) {
+                Err(e) if e.code() == Some(103) && !retried => {
+                    log::debug!("SWAC nonce expired, retrying with a new nonce");
+                    retried = true;
+                }
+                result => return result,
+            }
         }
     }
     
@@ -548,23 +3197,30 @@ impl WinCCUnifiedClient {
     /// Returns: Session object with updated expiry timestamp
     /// 
     /// JSON Structure: Same as login() method
-    pub fn extend_session(&mut self) -> WinCCResult<Session> {
-        let result = self.request(mutations::EXTEND_SESSION, None)?;
-        let extend_result: Session = serde_json::from_value(result["extendSession"].clone())?;
-        
-        if let Some(ref token) = extend_result.token {
-            self.set_token(token);
-        }
-        
-        if extend_result.token.is_some() {
-            Ok(extend_result)
-        } else {
-            let error_msg = extend_result.error
-                .as_ref()
-                .and_then(|e| e.description.as_ref())
-                .map_or("Unknown error", |v| v);
-            Err(WinCCError::SessionError(format!("Session extension failed: {}", error_msg)))
-        }
+    pub fn extend_session(&self) -> WinCCResult<Session> {
+        self.transport.extend_session()
+    }
+    
+    /// Log in like `login` and return a guard that logs out when it is dropped
+    /// 
+    /// For short-lived tools that must not leak sessions on the server, also on early
+    /// returns and errors. See `SessionGuard`.
+    /// 
+    /// # Example
+    /// ```no_run
+    /// # use winccua_graphql_client::WinCCUnifiedClient;
+    /// let client = WinCCUnifiedClient::new("https://your-server/graphql");
+    /// let session = client.login_scoped("username", "password").unwrap();
+    /// let _values = client.get_tag_values_simple(&["HMI_Tag_1".to_string()]);
+    /// drop(session); // logs out
+    /// ```
+    /// 
+    /// Errors:
+    /// - 101 - Incorrect credentials provided
+    /// - 102 - UMC error
+    pub fn login_scoped(&self, username: &str, password: &str) -> WinCCResult<SessionGuard<'_>> {
+        let session = self.login(username, password)?;
+        Ok(SessionGuard { client: self, session, closed: false })
     }
     
     /// Logs out the current user. If all_sessions is true, all sessions of the current user will be terminated.
@@ -575,13 +3231,18 @@ impl WinCCUnifiedClient {
     /// ```json
     /// true
     /// ```
-    pub fn logout(&mut self, all_sessions: bool) -> WinCCResult<bool> {
+    /// 
+    /// Auto-extend is stopped only after the logout request succeeded; if it fails, the
+    /// session stays valid and keeps being extended.
+    pub fn logout(&self, all_sessions: bool) -> WinCCResult<bool> {
         let variables = json!({
             "allSessions": all_sessions
         });
         
         let result = self.request(mutations::LOGOUT, Some(variables))?;
         
+        // Stopped before clearing the token, so an extension that ran meanwhile cannot store a new one
+        self.disable_auto_extend();
         self.clear_token();
         
         let logout_result = result["logout"].as_bool().unwrap_or(false);
@@ -589,12 +3250,15 @@ impl WinCCUnifiedClient {
     }
     
     /// Logout current session only
-    pub fn logout_simple(&mut self) -> WinCCResult<bool> {
+    pub fn logout_simple(&self) -> WinCCResult<bool> {
         self.logout(false)
     }
     
     /// Updates tags based on the provided TagValueInput list. Uses fallback timestamp and quality if not specified per tag.
     /// 
+    /// A timestamp or quality set on a `TagValueInput` always takes precedence; the `timestamp`
+    /// and `quality` arguments only apply to inputs that leave them unset.
+    /// 
     /// Returns: Array of WriteTagValuesResult objects with tag name and error information
     /// 
     /// JSON Structure: 
@@ -634,11 +3298,76 @@ impl WinCCUnifiedClient {
         Ok(write_results)
     }
     
+    /// Write tag values like `write_tag_values`, but fail unless every tag was written
+    /// 
+    /// For all-or-nothing error handling; use `write_tag_values` to handle the result of each
+    /// tag. The server writes the tags independently, so the tags without error have been
+    /// written even if this returns an error.
+    /// 
+    /// Errors:
+    /// - TagError - The server returned an error (code other than "0") for at least one tag,
+    ///   listing each failed tag with description and code, e.g. `1 of 2 tag writes failed:
+    ///   HMI_Tag_2: Cannot resolve provided name (code 2)`, or not one result per input
+    pub fn write_tag_values_checked(
+        &self,
+        input: &[TagValueInput],
+        timestamp: Option<&str>,
+        quality: Option<&QualityInput>,
+    ) -> WinCCResult<()> {
+        let results = self.write_tag_values(input, timestamp, quality)?;
+        if results.len() != input.len() {
+            return Err(WinCCError::TagError(format!(
+                "Expected {} write results, got {}", input.len(), results.len()
+            )));
+        }
+        
+        let failures: Vec<String> = input
+            .iter()
+            .zip(&results)
+            .filter_map(|(input, result)| {
+                let error = result.error.as_ref().filter(|e| e.is_error())?;
+                Some(error.message_for(result.name.as_deref().unwrap_or(&input.name)))
+            })
+            .collect();
+        if failures.is_empty() {
+            return Ok(());
+        }
+        Err(WinCCError::TagError(format!(
+            "{} of {} tag writes failed: {}", failures.len(), input.len(), failures.join("; ")
+        )))
+    }
+    
     /// Write tag values without timestamp and quality
     pub fn write_tag_values_simple(&self, input: &[TagValueInput]) -> WinCCResult<Vec<WriteTagValuesResult>> {
         self.write_tag_values(input, None, None)
     }
     
+    /// Write tag values with the current client time as timestamp for the whole batch.
+    /// 
+    /// All values share one timestamp (`Utc::now()` in RFC3339 with milliseconds, e.g.
+    /// `2023-12-31T23:59:59.999Z`). A timestamp set on a `TagValueInput` overrides it.
+    pub fn write_tag_values_now(&self, input: &[TagValueInput]) -> WinCCResult<Vec<WriteTagValuesResult>> {
+        let now = Utc::now().to_rfc3339_opts(chrono::SecondsFormat::Millis, true);
+        self.write_tag_values(input, Some(&now), None)
+    }
+    
+    /// Write the value of a single tag without timestamp and quality
+    /// 
+    /// Errors:
+    /// - TagError - The server returned an error for the tag, or not exactly one result
+    pub fn write_tag(&self, name: &str, value: impl Into<Value>) -> WinCCResult<()> {
+        let results = self.write_tag_values_simple(&[TagValueInput::new(name, value)])?;
+        let [result] = results.as_slice() else {
+            return Err(WinCCError::TagError(format!(
+                "Expected 1 result for tag {}, got {}", name, results.len()
+            )));
+        };
+        if let Some(error) = result.error.as_ref().filter(|e| e.is_error()) {
+            return Err(WinCCError::TagError(error.message_for(name)));
+        }
+        Ok(())
+    }
+    
     /// Acknowledge one or more alarms. Each alarm identifier must have the alarm name and optionally an instanceID.
     /// 
     /// Returns: Array of ActiveAlarmMutationResult objects with alarm name, instance ID, and error information
@@ -669,6 +3398,35 @@ impl WinCCUnifiedClient {
         Ok(ack_results)
     }
     
+    /// Acknowledge all active alarms matching a ChromQueryLanguage filter, e.g. `area = 'Plant1'`
+    /// 
+    /// Queries the matching alarms (name and instance ID only, with the default languages)
+    /// and acknowledges them with one `acknowledge_alarms` request. An empty filter matches
+    /// all active alarms. Alarms that cannot be acknowledged, e.g. because they are already
+    /// acknowledged, are reported with their error (305) in the returned results.
+    /// 
+    /// Returns: Array of ActiveAlarmMutationResult objects, one per matching alarm; empty (and
+    /// no mutation sent) if no alarm matches
+    /// 
+    /// Errors:
+    /// - 301 - Syntax error in query string
+    pub fn acknowledge_matching(&self, filter: &str) -> WinCCResult<Vec<ActiveAlarmMutationResult>> {
+        let languages = self.default_languages();
+        let alarms = self.get_active_alarms_minimal(&[], filter, &languages[0], &languages)?;
+        let identifiers: Vec<AlarmIdentifierInput> = alarms
+            .into_iter()
+            .filter_map(|alarm| {
+                let name = alarm.name?;
+                Some(AlarmIdentifierInput { name, instance_id: alarm.instance_id })
+            })
+            .collect();
+        
+        if identifiers.is_empty() {
+            return Ok(Vec::new());
+        }
+        self.acknowledge_alarms(&identifiers)
+    }
+    
     /// Reset one or more alarms. Each alarm identifier must have the alarm name and optionally an instanceID.
     /// 
     /// Returns: Array of ActiveAlarmMutationResult objects with alarm name, instance ID, and error information
@@ -737,6 +3495,9 @@ impl WinCCUnifiedClient {
     /// Shelve all active alarm instances of the provided configured alarms. 
     /// Uses runtime's configured shelving timeout if not specified.
     /// 
+    /// `shelve_timeout` is passed to the server unchanged as `Timespan`; the schema does not
+    /// document its format. See `shelve_alarms_for` to pass a `Duration`.
+    /// 
     /// Returns: Array of AlarmMutationResult objects with alarm name and error information
     /// 
     /// JSON Structure: Same as disable_alarms()
@@ -757,6 +3518,27 @@ impl WinCCUnifiedClient {
         Ok(shelve_results)
     }
     
+    /// Shelve alarms for the given duration instead of the runtime's configured shelving timeout
+    /// 
+    /// The duration is sent as JSON number `Timespan` in whole milliseconds; sub-millisecond
+    /// parts are dropped. The schema does not document the unit of `Timespan`, so milliseconds
+    /// are an assumption not yet verified against a server.
+    /// 
+    /// Returns: Array of AlarmMutationResult objects with alarm name and error information
+    /// 
+    /// Errors:
+    /// - 2 - Cannot resolve provided name
+    pub fn shelve_alarms_for(&self, names: &[String], timeout: Duration) -> WinCCResult<Vec<AlarmMutationResult>> {
+        let variables = json!({
+            "names": names,
+            "shelveTimeout": timespan(timeout)
+        });
+        
+        let result = self.request(mutations::SHELVE_ALARMS, Some(variables))?;
+        let shelve_results: Vec<AlarmMutationResult> = serde_json::from_value(result["shelveAlarms"].clone())?;
+        Ok(shelve_results)
+    }
+    
     /// Shelve alarms with default timeout
     pub fn shelve_alarms_simple(&self, names: &[String]) -> WinCCResult<Vec<AlarmMutationResult>> {
         self.shelve_alarms(names, None)
@@ -785,27 +3567,205 @@ impl WinCCUnifiedClient {
 
     /// Initialize WebSocket connection for subscriptions
     /// This must be called before using any subscription methods
+    /// 
+    /// Returns after the WebSocket handshake; `connection_ack` is awaited in the background.
+    /// 
+    /// Errors:
+    /// - InvalidParameter - WebSocket URL not configured
+    /// - WebSocketError - Connection or handshake failed or timed out
     pub async fn connect_ws(&mut self) -> WinCCResult<()> {
-        if let Some(ws_url) = &self.ws_url {
-            let token = self.token.clone().unwrap_or_default();
-            let mut ws_client = GraphQLWSClient::new(ws_url.clone(), token);
-            ws_client.connect().await?;
-            self.ws_client = Some(ws_client);
-            Ok(())
-        } else {
-            Err(WinCCError::InvalidParameter("WebSocket URL not configured".to_string()))
+        let mut ws_client = self.new_ws_client()?;
+        ws_client.connect().await?;
+        self.transport.session.write().unwrap().ws_token = Some(ws_client.token_updater());
+        self.ws_client = Some(ws_client);
+        Ok(())
+    }
+    
+    /// WebSocket client configured with the URL, token, headers and connection settings of this client
+    fn new_ws_client(&self) -> WinCCResult<GraphQLWSClient> {
+        let ws_url = self.ws_url.as_ref()
+            .ok_or_else(|| WinCCError::InvalidParameter("WebSocket URL not configured".to_string()))?;
+        let token = self.transport.session.read().unwrap().token.clone().unwrap_or_default();
+        let headers = self.transport.default_headers.lock().unwrap()
+            .iter()
+            .filter_map(|(name, value)| Some((name.to_string(), value.to_str().ok()?.to_string())))
+            .collect();
+        let mut ws_client = GraphQLWSClient::new(ws_url.clone(), token)
+            .with_ping_interval(self.ws_ping_interval)
+            .with_protocol(self.ws_protocol)
+            .with_connect_timeout(self.ws_connect_timeout)
+            .with_dispatch_buffer(self.ws_dispatch_buffer)
+            .with_overflow_policy(self.ws_overflow_policy)
+            .with_user_agent(self.user_agent.clone())
+            .with_headers(headers)
+            .with_init_params(self.ws_init_params.lock().unwrap().clone());
+        if let Some(connector) = &self.ws_tls_connector {
+            ws_client = ws_client.with_tls_connector(connector.clone());
+        }
+        if let Some(proxy) = &self.ws_proxy {
+            ws_client = ws_client.with_proxy(proxy.clone());
+        }
+        if let Some(timeout) = self.ws_idle_timeout {
+            ws_client = ws_client.with_idle_timeout(timeout);
+        }
+        if let Some(size) = self.ws_max_message_size {
+            ws_client = ws_client.with_max_message_size(size);
+        }
+        if let Some(size) = self.ws_max_frame_size {
+            ws_client = ws_client.with_max_frame_size(size);
+        }
+        Ok(ws_client)
+    }
+
+    /// Initialize WebSocket connection and wait until the server acknowledged it.
+    /// 
+    /// Unlike `connect_ws`, this returns only after `connection_ack` was received, so
+    /// subscriptions made afterwards do not race the handshake.
+    /// 
+    /// Errors:
+    /// - InvalidParameter - WebSocket URL not configured
+    /// - WebSocketError - Connection failed, was rejected, or timed out
+    pub async fn connect_ws_and_wait(&mut self, timeout: Duration) -> WinCCResult<()> {
+        if self.ws_client.is_none() {
+            self.connect_ws().await?;
+        }
+        let result = match self.ws_client.as_mut() {
+            Some(ws_client) => ws_client.connect_and_wait(timeout).await,
+            None => Err(WinCCError::WebSocketError("WebSocket not connected".to_string())),
+        };
+        if result.is_err() {
+            self.disconnect_ws().await;
         }
+        result
+    }
+
+    /// Current WebSocket connection state (Disconnected if `connect_ws` was not called)
+    pub fn ws_connection_state(&self) -> ConnectionState {
+        self.ws_client
+            .as_ref()
+            .map_or(ConnectionState::Disconnected, |ws_client| ws_client.connection_state())
     }
 
+    /// Receiver for WebSocket connection state changes, available after `connect_ws`
+    pub fn ws_state(&self) -> Option<watch::Receiver<ConnectionState>> {
+        self.ws_client.as_ref().map(|ws_client| ws_client.state())
+    }
+    
+    /// Close code and reason if the server closed the WebSocket connection, see `GraphQLWSClient::close_info`
+    /// 
+    /// A code of 4401 or 4403 (`CloseInfo::is_unauthorized`) means the token was rejected
+    /// and a new login or session extension is needed.
+    pub fn ws_close_info(&self) -> Option<CloseInfo> {
+        self.ws_client.as_ref().and_then(|ws_client| ws_client.close_info())
+    }
+
+    /// Close the WebSocket connection and connect again, replaying the active subscriptions
+    /// 
+    /// Returns after the new `connection_ack`. See `GraphQLWSClient::reconnect`.
+    /// 
+    /// Errors:
+    /// - WebSocketError - Not connected, or the new connection failed, was rejected or timed out;
+    ///   the WebSocket is then disconnected
+    pub async fn reconnect_ws(&mut self) -> WinCCResult<()> {
+        let result = match self.ws_client.as_mut() {
+            Some(ws_client) => ws_client.reconnect().await,
+            None => Err(WinCCError::WebSocketError("WebSocket not connected".to_string())),
+        };
+        match (&result, &self.ws_client) {
+            // A new connection task may have been started; token updates must reach it
+            (Ok(()), Some(ws_client)) => self.transport.session.write().unwrap().ws_token = Some(ws_client.token_updater()),
+            _ => self.disconnect_ws().await,
+        }
+        result
+    }
+    
+    /// Stop all subscriptions of the main WebSocket connection, e.g. before `logout` or when
+    /// switching dashboards
+    /// 
+    /// The connection stays open; channels opened with `connect_ws_channel` are not affected.
+    /// See `GraphQLWSClient::unsubscribe_all`.
+    /// 
+    /// Errors:
+    /// - WebSocketError - Not connected, or the unsubscribe was not confirmed in time
+    pub async fn unsubscribe_all(&self) -> WinCCResult<()> {
+        match &self.ws_client {
+            Some(ws_client) => ws_client.unsubscribe_all().await,
+            None => Err(WinCCError::WebSocketError("WebSocket not connected".to_string())),
+        }
+    }
+    
     /// Disconnect WebSocket connection
+    /// 
+    /// Channels opened with `connect_ws_channel` stay connected.
     pub async fn disconnect_ws(&mut self) {
+        self.transport.session.write().unwrap().ws_token = None;
         if let Some(mut ws_client) = self.ws_client.take() {
             ws_client.disconnect().await;
         }
     }
+    
+    /// Open an additional named WebSocket connection ("channel") for subscriptions and wait
+    /// until the server acknowledged it
+    /// 
+    /// Each channel has its own socket, connection task and dispatch queue, so a high-rate
+    /// subscription on one channel does not delay notifications on the main connection
+    /// (`connect_ws`) or on other channels. Channels use the URL, token, headers and WebSocket
+    /// settings of this client, receive token changes like the main connection, and are
+    /// closed with `disconnect_ws_channel` or when the client is dropped. Connecting a name
+    /// that is already connected replaces that channel.
+    /// 
+    /// Subscribe on the returned `GraphQLWSClient` with the queries in `subscriptions`; typed
+    /// callbacks are converted with `TypedSubscriptionCallbacks::into_callbacks`.
+    /// 
+    /// # Example
+    /// ```no_run
+    /// # async fn example(mut client: winccua_graphql_client::WinCCUnifiedClient) -> winccua_graphql_client::WinCCResult<()> {
+    /// use std::collections::HashMap;
+    /// use winccua_graphql_client::{subscriptions, SubscriptionCallbacks};
+    /// 
+    /// let mut variables = HashMap::new();
+    /// variables.insert("names".to_string(), serde_json::json!(["HighRate_Tag_1", "HighRate_Tag_2"]));
+    /// let channel = client.connect_ws_channel("high-rate").await?;
+    /// let subscription = channel
+    ///     .subscribe(subscriptions::TAG_VALUES.to_string(), variables, SubscriptionCallbacks::new(|data| println!("{}", data)))
+    ///     .await?;
+    /// # Ok(())
+    /// # }
+    /// ```
+    /// 
+    /// Errors:
+    /// - InvalidParameter - WebSocket URL not configured
+    /// - WebSocketError - Connection failed, was rejected, or timed out (`ws_connect_timeout`)
+    pub async fn connect_ws_channel(&mut self, name: &str) -> WinCCResult<&GraphQLWSClient> {
+        self.disconnect_ws_channel(name).await;
+        
+        let mut ws_client = self.new_ws_client()?;
+        ws_client.connect_and_wait(self.ws_connect_timeout).await?;
+        self.transport.session.write().unwrap().ws_channel_tokens.insert(name.to_string(), ws_client.token_updater());
+        Ok(self.ws_channels.entry(name.to_string()).or_insert(ws_client))
+    }
+    
+    /// The WebSocket channel opened with `connect_ws_channel` under `name`
+    pub fn ws_channel(&self, name: &str) -> Option<&GraphQLWSClient> {
+        self.ws_channels.get(name)
+    }
+    
+    /// Names of the open WebSocket channels
+    pub fn ws_channel_names(&self) -> Vec<&str> {
+        self.ws_channels.keys().map(String::as_str).collect()
+    }
+    
+    /// Close the WebSocket channel `name`, its subscriptions end
+    pub async fn disconnect_ws_channel(&mut self, name: &str) {
+        self.transport.session.write().unwrap().ws_channel_tokens.remove(name);
+        if let Some(mut ws_client) = self.ws_channels.remove(name) {
+            ws_client.disconnect().await;
+        }
+    }
 
     /// Subscribe to tag values for the tags based on the provided names list.
-    /// Notifications contain reason (Added, Modified, Removed, Removed (Name changed)).
+    /// Notifications contain reason (Added, Modified, Removed, Removed (Name changed)),
+    /// use `SubscriptionCallbacks::with_reason_filter` to only receive some of them.
     /// 
     /// Returns: Subscription object with unsubscribe method
     /// 
@@ -831,12 +3791,12 @@ impl WinCCUnifiedClient {
     /// - 202 - Only leaf elements of a Structure Tag can be addressed
     pub async fn subscribe_to_tag_values(
         &self,
-        names: Vec<String>,
+        names: impl IntoIterator<Item = impl AsRef<str>>,
         callbacks: SubscriptionCallbacks,
     ) -> WinCCResult<Subscription> {
         if let Some(ws_client) = &self.ws_client {
             let mut variables = HashMap::new();
-            variables.insert("names".to_string(), json!(names));
+            variables.insert("names".to_string(), json!(into_strings(names)));
             
             ws_client
                 .subscribe(subscriptions::TAG_VALUES.to_string(), variables, callbacks)
@@ -846,6 +3806,84 @@ impl WinCCUnifiedClient {
         }
     }
 
+    /// Subscribe without an async runtime: opens a dedicated WebSocket connection on a
+    /// background thread and invokes the callbacks there.
+    /// 
+    /// Blocks until the connection is acknowledged and the subscription is sent. The connection
+    /// uses the current token and the WebSocket settings of this client, but is independent of
+    /// `connect_ws`; it is closed when the returned handle is stopped or dropped. See
+    /// `GraphQLWSClient::subscribe_blocking`.
+    /// 
+    /// # Example
+    /// ```no_run
+    /// use std::collections::HashMap;
+    /// use winccua_graphql_client::{subscriptions, SubscriptionCallbacks, WinCCUnifiedClient};
+    /// 
+    /// let client = WinCCUnifiedClient::new_with_ws("https://your-server/graphql", "wss://your-server/graphql");
+    /// client.login("username", "password").unwrap();
+    /// 
+    /// let mut variables = HashMap::new();
+    /// variables.insert("names".to_string(), serde_json::json!(["HMI_Tag_1"]));
+    /// let subscription = client
+    ///     .subscribe_blocking(subscriptions::TAG_VALUES, variables, SubscriptionCallbacks::new(|data| println!("{}", data)))
+    ///     .unwrap();
+    /// std::thread::sleep(std::time::Duration::from_secs(60));
+    /// subscription.stop();
+    /// ```
+    /// 
+    /// Errors:
+    /// - InvalidParameter - No WebSocket URL configured
+    /// - WebSocketError - The connection or handshake failed or timed out
+    pub fn subscribe_blocking(
+        &self,
+        query: &str,
+        variables: HashMap<String, Value>,
+        callbacks: SubscriptionCallbacks,
+    ) -> WinCCResult<BlockingSubscription> {
+        self.new_ws_client()?.subscribe_blocking(query.to_string(), variables, callbacks)
+    }
+    
+    /// Subscribe to an arbitrary GraphQL subscription and wait at most `timeout` for the server's first response
+    /// 
+    /// See `GraphQLWSClient::subscribe_with_timeout`.
+    pub async fn subscribe_with_timeout(
+        &self,
+        query: &str,
+        variables: HashMap<String, Value>,
+        callbacks: SubscriptionCallbacks,
+        timeout: Duration,
+    ) -> WinCCResult<Subscription> {
+        if let Some(ws_client) = &self.ws_client {
+            ws_client.subscribe_with_timeout(query.to_string(), variables, callbacks, timeout).await
+        } else {
+            Err(WinCCError::OperationFailed("WebSocket not connected".to_string()))
+        }
+    }
+    
+    /// Subscribe to an arbitrary GraphQL subscription and receive the notifications as a `Stream`
+    /// 
+    /// See `GraphQLWSClient::subscribe_stream`.
+    pub async fn subscribe_stream(
+        &self,
+        query: &str,
+        variables: HashMap<String, Value>,
+    ) -> WinCCResult<SubscriptionStream> {
+        if let Some(ws_client) = &self.ws_client {
+            ws_client.subscribe_stream(query.to_string(), variables).await
+        } else {
+            Err(WinCCError::OperationFailed("WebSocket not connected".to_string()))
+        }
+    }
+    
+    /// Subscribe to tag values with callbacks that receive parsed TagValueNotification objects
+    pub async fn subscribe_to_tag_values_typed(
+        &self,
+        names: impl IntoIterator<Item = impl AsRef<str>>,
+        callbacks: TypedSubscriptionCallbacks<TagValueNotification>,
+    ) -> WinCCResult<Subscription> {
+        self.subscribe_to_tag_values(names, callbacks.into_callbacks("tagValues")).await
+    }
+
     /// Subscribe for active alarms matching the given filters.
     /// Notifications contain reason (Added, Modified, Removed).
     /// 
@@ -859,18 +3897,18 @@ impl WinCCUnifiedClient {
     /// - 303 - Invalid filter language
     pub async fn subscribe_to_active_alarms(
         &self,
-        system_names: Vec<String>,
-        filter_string: String,
-        filter_language: String,
-        languages: Vec<String>,
+        system_names: impl IntoIterator<Item = impl AsRef<str>>,
+        filter_string: impl Into<String>,
+        filter_language: impl Into<String>,
+        languages: impl IntoIterator<Item = impl AsRef<str>>,
         callbacks: SubscriptionCallbacks,
     ) -> WinCCResult<Subscription> {
         if let Some(ws_client) = &self.ws_client {
             let mut variables = HashMap::new();
-            variables.insert("systemNames".to_string(), json!(system_names));
-            variables.insert("filterString".to_string(), json!(filter_string));
-            variables.insert("filterLanguage".to_string(), json!(filter_language));
-            variables.insert("languages".to_string(), json!(languages));
+            variables.insert("systemNames".to_string(), json!(into_strings(system_names)));
+            variables.insert("filterString".to_string(), json!(filter_string.into()));
+            variables.insert("filterLanguage".to_string(), json!(filter_language.into()));
+            variables.insert("languages".to_string(), json!(into_strings(languages)));
             
             ws_client
                 .subscribe(subscriptions::ACTIVE_ALARMS.to_string(), variables, callbacks)
@@ -885,15 +3923,34 @@ impl WinCCUnifiedClient {
         &self,
         callbacks: SubscriptionCallbacks,
     ) -> WinCCResult<Subscription> {
+        let languages = self.default_languages();
         self.subscribe_to_active_alarms(
-            vec![],
-            String::new(),
-            "en-US".to_string(),
-            vec!["en-US".to_string()],
+            Vec::<String>::new(),
+            "",
+            &languages[0],
+            &languages,
             callbacks,
         ).await
     }
 
+    /// Subscribe for active alarms with callbacks that receive parsed ActiveAlarmNotification objects
+    pub async fn subscribe_to_active_alarms_typed(
+        &self,
+        system_names: impl IntoIterator<Item = impl AsRef<str>>,
+        filter_string: impl Into<String>,
+        filter_language: impl Into<String>,
+        languages: impl IntoIterator<Item = impl AsRef<str>>,
+        callbacks: TypedSubscriptionCallbacks<ActiveAlarmNotification>,
+    ) -> WinCCResult<Subscription> {
+        self.subscribe_to_active_alarms(
+            system_names,
+            filter_string,
+            filter_language,
+            languages,
+            callbacks.into_callbacks("activeAlarms"),
+        ).await
+    }
+
     /// Subscribe to redundancy state notifications.
     /// Notifications contain information about the active/passive state of the system on state changes.
     /// 
@@ -923,4 +3980,51 @@ impl WinCCUnifiedClient {
             Err(WinCCError::OperationFailed("WebSocket not connected".to_string()))
         }
     }
+
+    /// Subscribe to redundancy state with callbacks that receive parsed ReduStateNotification objects
+    pub async fn subscribe_to_redu_state_typed(
+        &self,
+        callbacks: TypedSubscriptionCallbacks<ReduStateNotification>,
+    ) -> WinCCResult<Subscription> {
+        self.subscribe_to_redu_state(callbacks.into_callbacks("reduState")).await
+    }
+    
+    /// Read the current redundancy state (ACTIVE / PASSIVE) of the connected server
+    /// 
+    /// The schema has no query for the redundancy state, so this subscribes to `reduState`,
+    /// waits for the first notification and unsubscribes again. It relies on the server sending
+    /// the current state when the subscription starts; if it only reports state changes, this
+    /// fails after `timeout`.
+    /// 
+    /// Returns: The ReduStateValue of the first notification
+    /// 
+    /// Errors:
+    /// - OperationFailed - WebSocket not connected, no notification arrived within `timeout`,
+    ///   or the notification has no value
+    /// - WebSocketError - The subscription failed or the connection closed
+    /// - JsonError - The notification could not be parsed
+    pub async fn get_redu_state(&self, timeout: Duration) -> WinCCResult<ReduStateValue> {
+        use futures_util::StreamExt;
+        
+        let mut stream = self.subscribe_stream(subscriptions::REDU_STATE, HashMap::new()).await?;
+        let first = tokio::time::timeout(timeout, stream.next()).await;
+        if let Err(e) = stream.unsubscribe().await {
+            log::debug!("Unsubscribe from redu state failed: {}", e);
+        }
+        
+        let payload = match first {
+            Ok(Some(payload)) => payload?,
+            Ok(None) => return Err(WinCCError::WebSocketError("Redu state subscription ended without notification".to_string())),
+            Err(_) => {
+                return Err(WinCCError::OperationFailed(format!(
+                    "No redundancy state received within {:?}",
+                    timeout
+                )))
+            }
+        };
+        let notification: ReduStateNotification = serde_json::from_value(payload["data"]["reduState"].clone())?;
+        notification
+            .value
+            .ok_or_else(|| WinCCError::OperationFailed("Redu state notification has no value".to_string()))
+    }
 }
\ No newline at end of file
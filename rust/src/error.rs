@@ -1,27 +1,98 @@
 //! Error types for WinCC Unified GraphQL client
 
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::time::Duration;
 use thiserror::Error;
 
 /// Result type for WinCC operations
 pub type WinCCResult<T> = Result<T, WinCCError>;
 
+/// Single entry of a GraphQL `errors` array as returned by the server
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct GraphQLError {
+    #[serde(default)]
+    pub message: String,
+    pub locations: Option<Vec<Value>>,
+    pub path: Option<Vec<Value>>,
+    pub extensions: Option<Value>,
+    /// Error code some servers return next to `message` instead of in `extensions`
+    pub code: Option<Value>,
+}
+
+impl GraphQLError {
+    /// Numeric WinCC error code from `extensions.code` or `code`, if present
+    pub fn code(&self) -> Option<i32> {
+        error_code(self.extensions.as_ref(), self.code.as_ref())
+    }
+    
+    /// Parse the entries of a GraphQL `errors` array, keeping at least the message of malformed entries
+    pub(crate) fn parse_all(errors: &[Value]) -> Vec<GraphQLError> {
+        errors
+            .iter()
+            .map(|e| serde_json::from_value(e.clone()).unwrap_or_else(|_| GraphQLError {
+                message: e["message"].as_str().unwrap_or("Unknown error").to_string(),
+                extensions: e.get("extensions").cloned(),
+                code: e.get("code").cloned(),
+                ..Default::default()
+            }))
+            .collect()
+    }
+}
+
 /// Error types for WinCC Unified GraphQL operations
 #[derive(Error, Debug)]
 pub enum WinCCError {
     #[error("HTTP request failed: {0}")]
     HttpError(#[from] reqwest::Error),
     
+    #[error("Rate limited by the server (HTTP 429){}", retry_after.map(|d| format!(", retry after {:?}", d)).unwrap_or_default())]
+    RateLimited {
+        /// Delay requested by the server in the `Retry-After` header
+        retry_after: Option<Duration>,
+    },
+    
+    #[error(
+        "Server returned HTTP {status}{}: {body}",
+        request_id.as_ref().map(|id| format!(" (request id {})", id)).unwrap_or_default()
+    )]
+    ServerError {
+        /// HTTP status code of the response
+        status: u16,
+        /// Value of the `x-request-id`, `x-correlation-id` or `request-id` response header
+        request_id: Option<String>,
+        /// Response body, truncated to 4 KiB
+        body: String,
+    },
+    
     #[error("JSON parsing error: {0}")]
     JsonError(#[from] serde_json::Error),
     
-    #[error("GraphQL error: {0}")]
-    GraphQLError(String),
+    #[error("GraphQL error: {message}")]
+    GraphQLError {
+        message: String,
+        errors: Vec<GraphQLError>,
+        data: Option<Value>,
+    },
+    
+    #[error("API error {code}: {message}")]
+    ApiError {
+        code: i32,
+        message: String,
+        errors: Vec<GraphQLError>,
+        data: Option<Value>,
+    },
     
     #[error("Authentication error: {0}")]
     AuthenticationError(String),
     
-    #[error("Login failed: {0}")]
-    LoginError(String),
+    #[error("Login failed: {code} - {description}")]
+    LoginError {
+        /// WinCC error code of the login result, e.g. 101 (incorrect credentials), 102 (UMC
+        /// error) or 103 (nonce expired); 0 if the server returned no token without a code
+        code: i32,
+        description: String,
+    },
     
     #[error("Session error: {0}")]
     SessionError(String),
@@ -32,19 +103,104 @@ pub enum WinCCError {
     #[error("Alarm operation error: {0}")]
     AlarmError(String),
     
+    #[error("WebSocket error: {0}")]
+    WebSocketError(String),
+    
     #[error("Invalid parameter: {0}")]
     InvalidParameter(String),
     
+    #[error("Invalid timestamp: {0}")]
+    InvalidTimestamp(String),
+    
     #[error("Operation failed: {0}")]
     OperationFailed(String),
+    
+    #[error("Request cancelled")]
+    Cancelled,
 }
 
 impl WinCCError {
-    pub fn from_graphql_errors(errors: &[serde_json::Value]) -> Self {
+    /// Build an error from a GraphQL `errors` array.
+    /// 
+    /// If an error carries a numeric WinCC code (in `extensions.code` or `code`),
+    /// an `ApiError` with the first code found is returned, otherwise a `GraphQLError`.
+    pub fn from_graphql_errors(errors: &[Value]) -> Self {
+        Self::from_graphql_response(errors, None)
+    }
+    
+    /// Build an error from a GraphQL `errors` array and the (partial) `data` returned alongside it
+    pub fn from_graphql_response(errors: &[Value], data: Option<Value>) -> Self {
         let error_messages: Vec<String> = errors
             .iter()
             .map(|e| e["message"].as_str().unwrap_or("Unknown error").to_string())
             .collect();
-        WinCCError::GraphQLError(error_messages.join(", "))
+        let message = error_messages.join(", ");
+        
+        let code = errors.iter().find_map(|e| error_code(e.get("extensions"), e.get("code")));
+        
+        let errors = GraphQLError::parse_all(errors);
+        let data = data.filter(|d| !d.is_null());
+        
+        match code {
+            Some(code) => WinCCError::ApiError { code, message, errors, data },
+            None => WinCCError::GraphQLError { message, errors, data },
+        }
     }
-}
\ No newline at end of file
+    
+    /// Returns the numeric WinCC error code, if the server provided one
+    pub fn code(&self) -> Option<i32> {
+        match self {
+            WinCCError::ApiError { code, .. } => Some(*code),
+            WinCCError::LoginError { code, .. } if *code != 0 => Some(*code),
+            _ => None,
+        }
+    }
+    
+    /// Returns the HTTP status code of a failed request, if the server responded
+    pub fn status(&self) -> Option<u16> {
+        match self {
+            WinCCError::ServerError { status, .. } => Some(*status),
+            WinCCError::RateLimited { .. } => Some(429),
+            WinCCError::HttpError(e) => e.status().map(|status| status.as_u16()),
+            _ => None,
+        }
+    }
+    
+    /// Returns the delay requested by the server when the request was rate limited
+    pub fn retry_after(&self) -> Option<Duration> {
+        match self {
+            WinCCError::RateLimited { retry_after } => *retry_after,
+            _ => None,
+        }
+    }
+    
+    /// Returns the individual GraphQL errors (message, locations, path, extensions)
+    pub fn graphql_errors(&self) -> &[GraphQLError] {
+        match self {
+            WinCCError::GraphQLError { errors, .. } | WinCCError::ApiError { errors, .. } => errors,
+            _ => &[],
+        }
+    }
+    
+    /// Returns the partial `data` the server returned together with the errors, if any
+    pub fn partial_data(&self) -> Option<&Value> {
+        match self {
+            WinCCError::GraphQLError { data, .. } | WinCCError::ApiError { data, .. } => data.as_ref(),
+            _ => None,
+        }
+    }
+}
+
+/// WinCC error code of a GraphQL error, from `extensions.code` or else a top-level `code`
+fn error_code(extensions: Option<&Value>, code: Option<&Value>) -> Option<i32> {
+    let code = extensions.and_then(|ext| ext.get("code")).or(code)?;
+    parse_code(code)
+}
+
+fn parse_code(code: &Value) -> Option<i32> {
+    match code {
+        Value::Number(n) => n.as_i64().and_then(|n| i32::try_from(n).ok()),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
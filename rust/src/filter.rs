@@ -0,0 +1,112 @@
+//! Builder for ChromQueryLanguage alarm filters
+
+/// Builds a ChromQueryLanguage `filterString` for alarm queries and subscriptions
+///
+/// All conditions are combined with AND. String values are quoted with embedded single
+/// quotes doubled, so names and areas containing quotes cannot break the query. Pass the
+/// result of `build` as `filter_string`, or use `WinCCUnifiedClient::get_active_alarms_filtered`.
+///
+/// ```
+/// use winccua_graphql_client::AlarmFilterBuilder;
+///
+/// let filter = AlarmFilterBuilder::new()
+///     .priority_gte(10)
+///     .state("RAISED")
+///     .area("Plant'1")
+///     .name_like("Motor*")
+///     .build();
+/// assert_eq!(filter, "priority >= 10 AND state = 'RAISED' AND area = 'Plant''1' AND name LIKE 'Motor*'");
+/// ```
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct AlarmFilterBuilder {
+    conditions: Vec<String>,
+}
+
+impl AlarmFilterBuilder {
+    /// Create a builder without conditions (matches all alarms)
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Alarm name equals `name`
+    pub fn name(self, name: &str) -> Self {
+        self.compare("name", "=", &cql_string(name))
+    }
+
+    /// Alarm name matches the LIKE `pattern`
+    pub fn name_like(self, pattern: &str) -> Self {
+        self.compare("name", "LIKE", &cql_string(pattern))
+    }
+
+    /// Alarm state equals `state`, e.g. "RAISED" or "RAISED_ACKNOWLEDGED"
+    pub fn state(self, state: &str) -> Self {
+        self.compare("state", "=", &cql_string(state))
+    }
+
+    /// Alarm area equals `area`
+    pub fn area(self, area: &str) -> Self {
+        self.compare("area", "=", &cql_string(area))
+    }
+
+    /// Alarm class name equals `class_name`
+    pub fn alarm_class(self, class_name: &str) -> Self {
+        self.compare("alarmClassName", "=", &cql_string(class_name))
+    }
+
+    /// Alarm origin equals `origin`
+    pub fn origin(self, origin: &str) -> Self {
+        self.compare("origin", "=", &cql_string(origin))
+    }
+
+    /// Priority equals `priority`
+    pub fn priority(self, priority: i32) -> Self {
+        self.compare("priority", "=", &priority.to_string())
+    }
+
+    /// Priority is at least `priority`
+    pub fn priority_gte(self, priority: i32) -> Self {
+        self.compare("priority", ">=", &priority.to_string())
+    }
+
+    /// Priority is at most `priority`
+    pub fn priority_lte(self, priority: i32) -> Self {
+        self.compare("priority", "<=", &priority.to_string())
+    }
+
+    /// Instance ID equals `instance_id`
+    pub fn instance_id(self, instance_id: i32) -> Self {
+        self.compare("instanceID", "=", &instance_id.to_string())
+    }
+
+    /// Add a condition written by hand; it is wrapped in parentheses and not escaped
+    pub fn raw(mut self, condition: &str) -> Self {
+        self.conditions.push(format!("({})", condition));
+        self
+    }
+
+    /// True if no condition was added
+    pub fn is_empty(&self) -> bool {
+        self.conditions.is_empty()
+    }
+
+    /// ChromQueryLanguage filter string, empty if no condition was added
+    pub fn build(&self) -> String {
+        self.conditions.join(" AND ")
+    }
+
+    fn compare(mut self, field: &str, operator: &str, value: &str) -> Self {
+        self.conditions.push(format!("{} {} {}", field, operator, value));
+        self
+    }
+}
+
+impl std::fmt::Display for AlarmFilterBuilder {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.write_str(&self.build())
+    }
+}
+
+/// Quote a string literal for a ChromQueryLanguage filter, doubling embedded single quotes
+pub(crate) fn cql_string(value: &str) -> String {
+    format!("'{}'", value.replace('\'', "''"))
+}
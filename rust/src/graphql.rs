@@ -26,6 +26,18 @@ pub mod queries {
         }
     "#;
 
+    pub const PING: &str = r#"
+        query Ping {
+            session(allSessions: false) {
+                expires
+                error {
+                    code
+                    description
+                }
+            }
+        }
+    "#;
+
     pub const NONCE: &str = r#"
         query Nonce {
             nonce {
@@ -41,6 +53,103 @@ pub mod queries {
         }
     "#;
 
+    /// Standard GraphQL introspection query
+    pub const INTROSPECTION: &str = r#"
+        query IntrospectionQuery {
+            __schema {
+                queryType { name }
+                mutationType { name }
+                subscriptionType { name }
+                types {
+                    ...FullType
+                }
+                directives {
+                    name
+                    description
+                    locations
+                    args {
+                        ...InputValue
+                    }
+                }
+            }
+        }
+
+        fragment FullType on __Type {
+            kind
+            name
+            description
+            fields(includeDeprecated: true) {
+                name
+                description
+                args {
+                    ...InputValue
+                }
+                type {
+                    ...TypeRef
+                }
+                isDeprecated
+                deprecationReason
+            }
+            inputFields {
+                ...InputValue
+            }
+            interfaces {
+                ...TypeRef
+            }
+            enumValues(includeDeprecated: true) {
+                name
+                description
+                isDeprecated
+                deprecationReason
+            }
+            possibleTypes {
+                ...TypeRef
+            }
+        }
+
+        fragment InputValue on __InputValue {
+            name
+            description
+            type {
+                ...TypeRef
+            }
+            defaultValue
+        }
+
+        fragment TypeRef on __Type {
+            kind
+            name
+            ofType {
+                kind
+                name
+                ofType {
+                    kind
+                    name
+                    ofType {
+                        kind
+                        name
+                        ofType {
+                            kind
+                            name
+                            ofType {
+                                kind
+                                name
+                                ofType {
+                                    kind
+                                    name
+                                    ofType {
+                                        kind
+                                        name
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    "#;
+
     pub const TAG_VALUES: &str = r#"
         query TagValues($names: [String!]!, $directRead: Boolean = false) {
             tagValues(names: $names, directRead: $directRead) {
@@ -188,6 +297,50 @@ pub mod queries {
         }
     "#;
 
+    /// ActiveAlarms query with `AlarmFieldSet::Minimal` fields
+    pub const ACTIVE_ALARMS_MINIMAL: &str = r#"
+        query ActiveAlarms($systemNames: [String] = [], $filterString: String = "", $filterLanguage: String = "en-US", $languages: [String] = ["en-US"]) {
+            activeAlarms(systemNames: $systemNames, filterString: $filterString, filterLanguage: $filterLanguage, languages: $languages) {
+                name
+                instanceID
+                state
+                priority
+                raiseTime
+                eventText
+            }
+        }
+    "#;
+
+    /// ActiveAlarms query with `AlarmFieldSet::Standard` fields
+    pub const ACTIVE_ALARMS_STANDARD: &str = r#"
+        query ActiveAlarms($systemNames: [String] = [], $filterString: String = "", $filterLanguage: String = "en-US", $languages: [String] = ["en-US"]) {
+            activeAlarms(systemNames: $systemNames, filterString: $filterString, filterLanguage: $filterLanguage, languages: $languages) {
+                name
+                instanceID
+                alarmGroupID
+                raiseTime
+                acknowledgmentTime
+                clearTime
+                resetTime
+                modificationTime
+                state
+                textColor
+                backColor
+                flashing
+                languages
+                alarmClassName
+                alarmClassSymbol
+                priority
+                eventText
+                infoText
+                stateText
+                origin
+                area
+                value
+            }
+        }
+    "#;
+
     pub const LOGGED_ALARMS: &str = r#"
         query LoggedAlarms($systemNames: [String] = [], $filterString: String = "", $filterLanguage: String = "en-US", $languages: [String] = ["en-US"], $startTime: Timestamp, $endTime: Timestamp, $maxNumberOfResults: Int = 0) {
             loggedAlarms(systemNames: $systemNames, filterString: $filterString, filterLanguage: $filterLanguage, languages: $languages, startTime: $startTime, endTime: $endTime, maxNumberOfResults: $maxNumberOfResults) {
@@ -433,6 +586,10 @@ pub mod mutations {
 }
 
 /// GraphQL subscriptions
+/// 
+/// The WinCC Unified schema only provides the `tagValues`, `activeAlarms` and `reduState`
+/// subscriptions. Logged (historical) tag values are not available as a subscription and
+/// must be read with the `loggedTagValues` query instead.
 pub mod subscriptions {
     pub const TAG_VALUES: &str = r#"
         subscription TagValues($names: [String!]!) {
@@ -555,4 +712,18 @@ pub mod subscriptions {
             }
         }
     "#;
-}
\ No newline at end of file
+}
+
+/// Name of the operation defined by a GraphQL document, e.g. `Login` for
+/// `mutation Login($username: String!, ...)`. None for anonymous operations.
+pub(crate) fn operation_name(document: &str) -> Option<&str> {
+    let document = document.trim_start();
+    let rest = ["query", "mutation", "subscription"]
+        .iter()
+        .find_map(|keyword| document.strip_prefix(keyword))?
+        .trim_start();
+    let end = rest
+        .find(|c: char| !(c.is_ascii_alphanumeric() || c == '_'))
+        .unwrap_or(rest.len());
+    Some(&rest[..end]).filter(|name| !name.is_empty())
+}
@@ -1,18 +1,30 @@
 use crate::error::WinCCError;
-use futures_util::{SinkExt, StreamExt};
+use crate::graphql;
+use crate::types::NotificationReason;
+use futures_util::{SinkExt, Stream, StreamExt};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
-use std::collections::HashMap;
+use std::collections::{HashMap, VecDeque};
+use std::pin::Pin;
 use std::sync::atomic::{AtomicU32, Ordering};
-use std::sync::{Arc, Mutex};
-use tokio::sync::mpsc;
-use tokio_tungstenite::{connect_async, tungstenite::protocol::Message};
+use std::sync::{Arc, Condvar, Mutex};
+use std::task::{Context, Poll};
+use std::time::{Duration, Instant};
+use tokio::sync::{mpsc, oneshot, watch, Notify};
+use tokio::io::{AsyncReadExt, AsyncWriteExt};
+use tokio::net::TcpStream;
+use tokio_tungstenite::{client_async_tls_with_config, connect_async_tls_with_config, tungstenite::protocol::Message, Connector};
+use tungstenite::error::CapacityError;
+use tungstenite::protocol::WebSocketConfig;
 
 #[derive(Clone)]
 pub struct SubscriptionCallbacks {
     pub on_data: Arc<dyn Fn(Value) + Send + Sync>,
     pub on_error: Option<Arc<dyn Fn(String) + Send + Sync>>,
     pub on_complete: Option<Arc<dyn Fn() + Send + Sync>>,
+    /// Called with the close code and reason when the server closes the connection, before `on_error`
+    pub on_close: Option<Arc<dyn Fn(CloseInfo) + Send + Sync>>,
 }
 
 impl SubscriptionCallbacks {
@@ -21,9 +33,118 @@ impl SubscriptionCallbacks {
             on_data: Arc::new(on_data),
             on_error: None,
             on_complete: None,
+            on_close: None,
         }
     }
 
+    /// Create callbacks from a data callback that mutates captured state (`FnMut`)
+    ///
+    /// The callback is driven behind a mutex, so it only needs to be `Send`. Notifications
+    /// are delivered one at a time by the subscription's dispatch thread, so the lock is not
+    /// contended.
+    ///
+    /// ```
+    /// use winccua_graphql_client::SubscriptionCallbacks;
+    ///
+    /// let mut count = 0;
+    /// let callbacks = SubscriptionCallbacks::new_mut(move |_| {
+    ///     count += 1;
+    ///     println!("{} notifications", count);
+    /// });
+    /// ```
+    pub fn new_mut(on_data: impl FnMut(Value) + Send + 'static) -> Self {
+        let on_data = Mutex::new(on_data);
+        Self::new(move |payload| (on_data.lock().unwrap_or_else(|e| e.into_inner()))(payload))
+    }
+
+    pub fn with_error(mut self, on_error: impl Fn(String) + Send + Sync + 'static) -> Self {
+        self.on_error = Some(Arc::new(on_error));
+        self
+    }
+
+    pub fn with_complete(mut self, on_complete: impl Fn() + Send + Sync + 'static) -> Self {
+        self.on_complete = Some(Arc::new(on_complete));
+        self
+    }
+
+    pub fn with_close(mut self, on_close: impl Fn(CloseInfo) + Send + Sync + 'static) -> Self {
+        self.on_close = Some(Arc::new(on_close));
+        self
+    }
+
+    /// Only pass notifications with one of the given `notificationReason`s to `on_data`
+    ///
+    /// E.g. `[NotificationReason::Modified]` drops the `Added` notifications sent when the
+    /// subscription starts. Payloads without a notification reason (such as GraphQL errors)
+    /// are passed on unchanged.
+    ///
+    /// ```
+    /// use winccua_graphql_client::{NotificationReason, SubscriptionCallbacks};
+    ///
+    /// let callbacks = SubscriptionCallbacks::new(|data| println!("{}", data))
+    ///     .with_reason_filter([NotificationReason::Modified]);
+    /// ```
+    pub fn with_reason_filter(mut self, reasons: impl IntoIterator<Item = NotificationReason>) -> Self {
+        let reasons: Vec<NotificationReason> = reasons.into_iter().collect();
+        let on_data = self.on_data;
+        self.on_data = Arc::new(move |payload| {
+            match notification_reason(&payload) {
+                Some(reason) if !reasons.contains(&reason) => {}
+                _ => (on_data)(payload),
+            }
+        });
+        self
+    }
+}
+
+/// `notificationReason` of the notification in a subscription payload (`data.<field>.notificationReason`)
+fn notification_reason(payload: &Value) -> Option<NotificationReason> {
+    let notification = payload.get("data")?.as_object()?.values().next()?;
+    serde_json::from_value(notification.get("notificationReason")?.clone()).ok()
+}
+
+/// Subscription callbacks that receive notifications already deserialized into `T`.
+///
+/// The GraphQL envelope (`data.<fieldName>`) is stripped before deserializing.
+/// If the payload does not match `T`, `on_error` is called with the parse error.
+pub struct TypedSubscriptionCallbacks<T> {
+    pub on_data: Arc<dyn Fn(T) + Send + Sync>,
+    pub on_error: Option<Arc<dyn Fn(String) + Send + Sync>>,
+    pub on_complete: Option<Arc<dyn Fn() + Send + Sync>>,
+    pub on_close: Option<Arc<dyn Fn(CloseInfo) + Send + Sync>>,
+    /// Notification reasons passed to `on_data`, all if None (see `with_reason_filter`)
+    pub reason_filter: Option<Vec<NotificationReason>>,
+}
+
+impl<T> Clone for TypedSubscriptionCallbacks<T> {
+    fn clone(&self) -> Self {
+        Self {
+            on_data: self.on_data.clone(),
+            on_error: self.on_error.clone(),
+            on_complete: self.on_complete.clone(),
+            on_close: self.on_close.clone(),
+            reason_filter: self.reason_filter.clone(),
+        }
+    }
+}
+
+impl<T: DeserializeOwned + 'static> TypedSubscriptionCallbacks<T> {
+    pub fn new(on_data: impl Fn(T) + Send + Sync + 'static) -> Self {
+        Self {
+            on_data: Arc::new(on_data),
+            on_error: None,
+            on_complete: None,
+            on_close: None,
+            reason_filter: None,
+        }
+    }
+
+    /// Create callbacks from a data callback that mutates captured state, see `SubscriptionCallbacks::new_mut`
+    pub fn new_mut(on_data: impl FnMut(T) + Send + 'static) -> Self {
+        let on_data = Mutex::new(on_data);
+        Self::new(move |notification| (on_data.lock().unwrap_or_else(|e| e.into_inner()))(notification))
+    }
+
     pub fn with_error(mut self, on_error: impl Fn(String) + Send + Sync + 'static) -> Self {
         self.on_error = Some(Arc::new(on_error));
         self
@@ -33,6 +154,117 @@ impl SubscriptionCallbacks {
         self.on_complete = Some(Arc::new(on_complete));
         self
     }
+
+    pub fn with_close(mut self, on_close: impl Fn(CloseInfo) + Send + Sync + 'static) -> Self {
+        self.on_close = Some(Arc::new(on_close));
+        self
+    }
+
+    /// Only pass notifications with one of the given reasons to `on_data`, see
+    /// `SubscriptionCallbacks::with_reason_filter`
+    pub fn with_reason_filter(mut self, reasons: impl IntoIterator<Item = NotificationReason>) -> Self {
+        self.reason_filter = Some(reasons.into_iter().collect());
+        self
+    }
+
+    /// Convert into untyped callbacks that unwrap `data.<field_name>` and deserialize it into `T`
+    pub fn into_callbacks(self, field_name: &str) -> SubscriptionCallbacks {
+        let field_name = field_name.to_string();
+        let on_data = self.on_data;
+        let on_error = self.on_error.clone();
+
+        let callbacks = SubscriptionCallbacks {
+            on_data: Arc::new(move |payload: Value| {
+                match parse_notification::<T>(&payload, &field_name) {
+                    Ok(notification) => (on_data)(notification),
+                    Err(e) => {
+                        log::error!("Failed to parse {} notification: {}", field_name, e);
+                        if let Some(on_error) = &on_error {
+                            (on_error)(e);
+                        }
+                    }
+                }
+            }),
+            on_error: self.on_error,
+            on_complete: self.on_complete,
+            on_close: self.on_close,
+        };
+        match self.reason_filter {
+            Some(reasons) => callbacks.with_reason_filter(reasons),
+            None => callbacks,
+        }
+    }
+}
+
+/// Strip the GraphQL envelope from a subscription payload and deserialize the named field
+pub fn parse_notification<T: DeserializeOwned>(payload: &Value, field_name: &str) -> Result<T, String> {
+    if let Some(errors) = payload.get("errors").and_then(|e| e.as_array()) {
+        if !errors.is_empty() {
+            return Err(WinCCError::from_graphql_errors(errors).to_string());
+        }
+    }
+
+    let field = payload
+        .get("data")
+        .and_then(|d| d.get(field_name))
+        .ok_or_else(|| format!("Missing data.{} in subscription payload", field_name))?;
+
+    serde_json::from_value(field.clone())
+        .map_err(|e| format!("Failed to deserialize data.{}: {}", field_name, e))
+}
+
+/// WebSocket subprotocol used for subscriptions
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum WsProtocol {
+    /// `graphql-transport-ws` (graphql-ws library), used by current WinCC Unified releases
+    #[default]
+    GraphQLTransportWs,
+    /// Legacy `subscriptions-transport-ws` (`graphql-ws` subprotocol) with `start`/`data`/`stop` messages
+    SubscriptionsTransportWs,
+}
+
+impl WsProtocol {
+    /// Value of the `Sec-WebSocket-Protocol` header
+    pub fn subprotocol(&self) -> &'static str {
+        match self {
+            WsProtocol::GraphQLTransportWs => "graphql-transport-ws",
+            WsProtocol::SubscriptionsTransportWs => "graphql-ws",
+        }
+    }
+
+    /// Legacy name of a client message type
+    fn legacy_type(message_type: &str) -> &str {
+        match message_type {
+            "subscribe" => "start",
+            "complete" => "stop",
+            other => other,
+        }
+    }
+
+    fn encode(&self, msg: &WSMessage) -> serde_json::Result<String> {
+        let mut value = serde_json::to_value(msg)?;
+        if *self == WsProtocol::SubscriptionsTransportWs {
+            if let Some(message_type) = value.get("type").and_then(Value::as_str) {
+                value["type"] = Value::from(Self::legacy_type(message_type));
+            }
+        }
+        serde_json::to_string(&value)
+    }
+
+    fn decode(&self, text: &str) -> Option<WSMessage> {
+        let mut value: Value = serde_json::from_str(text).ok()?;
+        if *self == WsProtocol::SubscriptionsTransportWs {
+            let message_type = match value.get("type").and_then(Value::as_str)? {
+                "data" => "next",
+                // Keepalive from the server, handled like a pong
+                "ka" => "pong",
+                other => other,
+            }
+            .to_string();
+            value["type"] = Value::from(message_type);
+        }
+        serde_json::from_value(value).ok()
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -60,7 +292,14 @@ enum WSMessage {
     Complete {
         id: String,
     },
-    Pong,
+    Ping {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
+    Pong {
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        payload: Option<Value>,
+    },
 }
 
 #[derive(Debug, Serialize, Deserialize)]
@@ -69,26 +308,303 @@ struct SubscribePayload {
     variables: HashMap<String, Value>,
 }
 
+/// Default time to wait for an unsubscribe to be confirmed
+pub const DEFAULT_UNSUBSCRIBE_TIMEOUT: Duration = Duration::from_secs(5);
+
 pub struct Subscription {
     id: String,
-    unsubscribe_tx: mpsc::Sender<String>,
+    operation_name: Option<String>,
+    command_tx: mpsc::Sender<WSCommand>,
+    /// Dangling once the connection task dropped the callbacks of this subscription
+    alive: std::sync::Weak<()>,
+}
+
+impl std::fmt::Debug for Subscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Subscription")
+            .field("id", &self.id)
+            .field("operation_name", &self.operation_name)
+            .field("active", &self.is_active())
+            .finish()
+    }
 }
 
 impl Subscription {
-    pub async fn unsubscribe(self) {
-        let _ = self.unsubscribe_tx.send(self.id).await;
+    /// Protocol id of the subscription (`sub_<n>`), as used in log messages
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    /// Name of the GraphQL operation, e.g. `TagValues`; None for anonymous subscriptions
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
+    }
+
+    /// Returns true until the subscription is completed by the server, unsubscribed or
+    /// its connection is closed
+    pub fn is_active(&self) -> bool {
+        self.alive.strong_count() > 0 && !self.command_tx.is_closed()
+    }
+
+    /// Stop the subscription and wait until it is stopped (at most 5 seconds)
+    ///
+    /// When this returns Ok, the subscription was removed and `Complete` was sent to the
+    /// server; no further callbacks are invoked except `on_complete`, which is called once.
+    ///
+    /// Errors:
+    /// - WebSocketError - The connection is closed or the unsubscribe was not confirmed in time
+    pub async fn unsubscribe(self) -> Result<(), WinCCError> {
+        self.unsubscribe_with_timeout(DEFAULT_UNSUBSCRIBE_TIMEOUT).await
+    }
+
+    /// Stop the subscription and wait at most `timeout` until it is stopped
+    pub async fn unsubscribe_with_timeout(self, timeout: Duration) -> Result<(), WinCCError> {
+        log::debug!("Unsubscribe requested for: {}", self.id);
+        let (done_tx, done_rx) = oneshot::channel();
+        self.command_tx
+            .send(WSCommand::Unsubscribe { id: self.id, done: Some(done_tx) })
+            .await
+            .map_err(|_| WinCCError::WebSocketError("WebSocket not connected".to_string()))?;
+
+        match tokio::time::timeout(timeout, done_rx).await {
+            Ok(Ok(result)) => result.map_err(WinCCError::WebSocketError),
+            Ok(Err(_)) => Err(WinCCError::WebSocketError("WebSocket closed before unsubscribe was confirmed".to_string())),
+            Err(_) => Err(WinCCError::WebSocketError("Timed out waiting for unsubscribe".to_string())),
+        }
+    }
+}
+
+/// Subscription that yields its notifications as a `Stream` instead of calling callbacks
+///
+/// Created with `GraphQLWSClient::subscribe_stream`. Each item is the raw notification
+/// payload (`{"data": {...}}`). The stream ends when the server completes the
+/// subscription or it is unsubscribed, and after yielding the first error (a subscription
+/// error or the connection closing). If the server closed the connection with 4401 or 4403,
+/// the error is an `AuthenticationError`. Notifications are buffered without limit until
+/// they are polled.
+pub struct SubscriptionStream {
+    subscription: Subscription,
+    rx: mpsc::UnboundedReceiver<Result<Value, WinCCError>>,
+    done: bool,
+}
+
+impl SubscriptionStream {
+    /// Stop the subscription, see `Subscription::unsubscribe`
+    pub async fn unsubscribe(self) -> Result<(), WinCCError> {
+        self.subscription.unsubscribe().await
+    }
+}
+
+impl Stream for SubscriptionStream {
+    type Item = Result<Value, WinCCError>;
+
+    fn poll_next(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Option<Self::Item>> {
+        if self.done {
+            return Poll::Ready(None);
+        }
+        match self.rx.poll_recv(cx) {
+            Poll::Ready(Some(Err(e))) => {
+                self.done = true;
+                Poll::Ready(Some(Err(e)))
+            }
+            Poll::Ready(None) => {
+                self.done = true;
+                Poll::Ready(None)
+            }
+            poll => poll,
+        }
+    }
+}
+
+/// Subscription running on its own thread and Tokio runtime, for applications without async code
+///
+/// Created with `GraphQLWSClient::subscribe_blocking` or `WinCCUnifiedClient::subscribe_blocking`.
+/// The callbacks are invoked on the background thread. `stop()` or dropping the handle
+/// unsubscribes, closes the connection and waits for the thread to finish.
+pub struct BlockingSubscription {
+    id: String,
+    operation_name: Option<String>,
+    stop_tx: Option<oneshot::Sender<()>>,
+    thread: Option<std::thread::JoinHandle<()>>,
+}
+
+impl std::fmt::Debug for BlockingSubscription {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("BlockingSubscription")
+            .field("id", &self.id)
+            .field("operation_name", &self.operation_name)
+            .field("active", &self.is_active())
+            .finish()
+    }
+}
+
+impl BlockingSubscription {
+    /// Protocol id of the subscription, see `Subscription::id`
+    pub fn id(&self) -> &str {
+        &self.id
     }
+
+    /// Name of the GraphQL operation, see `Subscription::operation_name`
+    pub fn operation_name(&self) -> Option<&str> {
+        self.operation_name.as_deref()
+    }
+
+    /// Returns true until the subscription is stopped or its connection is closed
+    pub fn is_active(&self) -> bool {
+        self.thread.as_ref().is_some_and(|thread| !thread.is_finished())
+    }
+
+    /// Unsubscribe, close the connection and wait for the background thread to finish
+    pub fn stop(mut self) {
+        self.shutdown();
+    }
+
+    fn shutdown(&mut self) {
+        if let Some(stop_tx) = self.stop_tx.take() {
+            let _ = stop_tx.send(());
+        }
+        if let Some(thread) = self.thread.take() {
+            let _ = thread.join();
+        }
+    }
+}
+
+impl Drop for BlockingSubscription {
+    fn drop(&mut self) {
+        self.shutdown();
+    }
+}
+
+/// Close code and reason sent by the server when it closed the connection
+///
+/// graphql-transport-ws servers use e.g. 4400 (bad request), 4401 (unauthorized),
+/// 4403 (forbidden), 4408 (connection_init timeout) and 4409 (duplicate subscription id).
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CloseInfo {
+    pub code: u16,
+    pub reason: String,
+}
+
+impl CloseInfo {
+    /// True if the server rejected the token (4401 Unauthorized or 4403 Forbidden)
+    pub fn is_unauthorized(&self) -> bool {
+        matches!(self.code, 4401 | 4403)
+    }
+
+    /// Error for an operation that failed because the server closed the connection:
+    /// AuthenticationError if the token was rejected, WebSocketError otherwise
+    pub fn to_error(&self) -> WinCCError {
+        let message = format!("WebSocket connection closed by server ({})", self);
+        if self.is_unauthorized() {
+            WinCCError::AuthenticationError(message)
+        } else {
+            WinCCError::WebSocketError(message)
+        }
+    }
+}
+
+impl std::fmt::Display for CloseInfo {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        if self.reason.is_empty() {
+            write!(f, "{}", self.code)
+        } else {
+            write!(f, "{}: {}", self.code, self.reason)
+        }
+    }
+}
+
+/// State of the WebSocket connection
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ConnectionState {
+    /// Handshake in progress, waiting for `connection_ack`
+    Connecting,
+    /// `connection_ack` received, subscriptions are active
+    Connected,
+    /// Not connected (never connected, closed or failed)
+    Disconnected,
+    /// Connection lost, a new connection is being established
+    Reconnecting,
+}
+
+/// Default timeout for establishing the WebSocket connection (TCP, TLS and upgrade)
+pub const DEFAULT_CONNECT_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// User-Agent sent with the WebSocket upgrade request and HTTP requests unless configured otherwise
+pub const DEFAULT_USER_AGENT: &str = concat!(env!("CARGO_PKG_NAME"), "/", env!("CARGO_PKG_VERSION"));
+
+/// Default interval between keepalive pings
+pub const DEFAULT_PING_INTERVAL: Duration = Duration::from_secs(30);
+
+/// Default number of notifications buffered per subscription for its callbacks
+pub const DEFAULT_DISPATCH_BUFFER: usize = 100;
+
+/// What happens when a subscription's notification buffer is full
+///
+/// Each subscription's callbacks run on their own thread, fed by a bounded buffer (see
+/// `GraphQLWSClient::with_dispatch_buffer`). The policy decides what the connection does
+/// when a callback falls so far behind that the buffer is full.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum OverflowPolicy {
+    /// Stop reading from the socket until the callback has caught up (default)
+    ///
+    /// No notification is lost, but while the buffer is full the whole connection stalls:
+    /// notifications of all other subscriptions, keepalive pings, unsubscribe and reconnect
+    /// wait until the slow callback has taken the next notification.
+    #[default]
+    Backpressure,
+    /// Discard the oldest buffered notification of the subscription to make room
+    ///
+    /// Other subscriptions and the connection are never stalled, but notifications are lost.
+    /// Only for streams where the latest value is enough, such as tag values; not for active
+    /// alarms, whose notifications are changes of the alarm list. Errors and completion are
+    /// never discarded.
+    DropOldest,
 }
 
 pub struct GraphQLWSClient {
     url: String,
-    token: Arc<Mutex<String>>,
-    subscriptions: Arc<Mutex<HashMap<String, SubscriptionCallbacks>>>,
+    /// Current token; the connection task reconnects when it changes
+    token: Arc<watch::Sender<String>>,
+    ping_interval: Duration,
+    idle_timeout: Option<Duration>,
+    connect_timeout: Duration,
+    headers: HashMap<String, String>,
+    init_params: HashMap<String, String>,
+    user_agent: String,
+    max_message_size: Option<usize>,
+    max_frame_size: Option<usize>,
+    tls_connector: Option<native_tls::TlsConnector>,
+    proxy: Option<String>,
+    protocol: WsProtocol,
+    state: Arc<watch::Sender<ConnectionState>>,
+    close_info: Arc<Mutex<Option<CloseInfo>>>,
+    dispatch_buffer: usize,
+    overflow_policy: OverflowPolicy,
+    subscriptions: Arc<Mutex<HashMap<String, Dispatcher>>>,
     subscription_counter: Arc<AtomicU32>,
     command_tx: Option<mpsc::Sender<WSCommand>>,
     handle: Option<tokio::task::JoinHandle<()>>,
 }
 
+/// Cloneable handle for pushing a new token to a WebSocket client
+///
+/// The token is published through a watch channel, so a connected client always sees the
+/// latest token, also if the update is made while it is busy.
+#[derive(Clone)]
+pub(crate) struct TokenUpdater {
+    token: Arc<watch::Sender<String>>,
+}
+
+impl TokenUpdater {
+    pub(crate) fn update(&self, token: String) {
+        self.token.send_if_modified(|current| {
+            let changed = *current != token;
+            *current = token;
+            changed
+        });
+    }
+}
+
 enum WSCommand {
     Subscribe {
         id: String,
@@ -98,19 +614,191 @@ enum WSCommand {
     },
     Unsubscribe {
         id: String,
+        done: Option<oneshot::Sender<Result<(), String>>>,
+    },
+    UnsubscribeAll {
+        done: oneshot::Sender<Result<(), String>>,
     },
-    UpdateToken {
-        #[allow(dead_code)]
-        token: String,
+    Reconnect {
+        ack: oneshot::Sender<Result<(), String>>,
     },
     Disconnect,
 }
 
+enum DispatchEvent {
+    Data(Value),
+    Error(String),
+    Close(CloseInfo),
+    Complete,
+}
+
+struct DispatchState {
+    events: VecDeque<DispatchEvent>,
+    closed: bool,
+}
+
+/// Bounded buffer between the connection task and a subscription's callback thread
+struct DispatchQueue {
+    state: Mutex<DispatchState>,
+    event_added: Condvar,
+    space_freed: Notify,
+    capacity: usize,
+    policy: OverflowPolicy,
+}
+
+impl DispatchQueue {
+    /// Queue a notification, waiting for space or dropping the oldest one when full
+    async fn push_data(&self, payload: Value) {
+        let mut payload = Some(payload);
+        loop {
+            let space_freed = self.space_freed.notified();
+            {
+                let mut state = self.state.lock().unwrap();
+                if state.closed {
+                    return;
+                }
+                let full = state.events.len() >= self.capacity;
+                if full && self.policy == OverflowPolicy::DropOldest {
+                    if let Some(index) = state.events.iter().position(|event| matches!(event, DispatchEvent::Data(_))) {
+                        state.events.remove(index);
+                        log::warn!("Subscription callback is too slow, dropped the oldest notification");
+                    }
+                }
+                if !full || self.policy == OverflowPolicy::DropOldest {
+                    state.events.push_back(DispatchEvent::Data(payload.take().unwrap_or_default()));
+                    self.event_added.notify_one();
+                    return;
+                }
+            }
+            space_freed.await;
+        }
+    }
+
+    /// Queue an error, close or completion event; these are never dropped or delayed
+    fn push_control(&self, event: DispatchEvent) {
+        let mut state = self.state.lock().unwrap();
+        if !state.closed {
+            state.events.push_back(event);
+            self.event_added.notify_one();
+        }
+    }
+
+    /// Discard the notifications not yet delivered
+    fn clear_data(&self) {
+        self.state.lock().unwrap().events.retain(|event| !matches!(event, DispatchEvent::Data(_)));
+        self.space_freed.notify_one();
+    }
+
+    /// Let the callback thread end after delivering the queued events
+    fn close(&self) {
+        self.state.lock().unwrap().closed = true;
+        self.event_added.notify_all();
+        self.space_freed.notify_one();
+    }
+
+    fn pop(&self) -> Option<DispatchEvent> {
+        let mut state = self.state.lock().unwrap();
+        loop {
+            if let Some(event) = state.events.pop_front() {
+                self.space_freed.notify_one();
+                return Some(event);
+            }
+            if state.closed {
+                return None;
+            }
+            state = self.event_added.wait(state).unwrap();
+        }
+    }
+}
+
+/// Invokes the callbacks of one subscription on a dedicated thread, so a slow callback
+/// does not block the connection or other subscriptions
+struct Dispatcher {
+    queue: Arc<DispatchQueue>,
+    finished: oneshot::Receiver<()>,
+}
+
+impl Dispatcher {
+    /// Start the callback thread of a subscription
+    ///
+    /// If the thread cannot be started, the subscription fails: its `on_error` callback is
+    /// called directly and None is returned, so the subscription is not sent to the server.
+    fn spawn(callbacks: SubscriptionCallbacks, capacity: usize, policy: OverflowPolicy) -> Option<Self> {
+        let queue = Arc::new(DispatchQueue {
+            state: Mutex::new(DispatchState { events: VecDeque::new(), closed: false }),
+            event_added: Condvar::new(),
+            space_freed: Notify::new(),
+            capacity,
+            policy,
+        });
+        let (finished_tx, finished) = oneshot::channel();
+        
+        let on_error = callbacks.on_error.clone();
+        let worker_queue = queue.clone();
+        let run = move || {
+            while let Some(event) = worker_queue.pop() {
+                match event {
+                    DispatchEvent::Data(payload) => (callbacks.on_data)(payload),
+                    DispatchEvent::Error(message) => {
+                        if let Some(on_error) = &callbacks.on_error {
+                            (on_error)(message);
+                        }
+                    }
+                    DispatchEvent::Close(close_info) => {
+                        if let Some(on_close) = &callbacks.on_close {
+                            (on_close)(close_info);
+                        }
+                    }
+                    DispatchEvent::Complete => {
+                        if let Some(on_complete) = &callbacks.on_complete {
+                            (on_complete)();
+                        }
+                    }
+                }
+            }
+            // Drop the callbacks before reporting, so their resources are released when waited for
+            drop(callbacks);
+            let _ = finished_tx.send(());
+        };
+        if let Err(e) = std::thread::Builder::new().name("winccua-dispatch".to_string()).spawn(run) {
+            log::error!("Failed to start subscription callback thread: {}", e);
+            if let Some(on_error) = on_error {
+                (on_error)(format!("Failed to start subscription callback thread: {}", e));
+            }
+            return None;
+        }
+        
+        Some(Self { queue, finished })
+    }
+}
+
+impl Drop for Dispatcher {
+    /// Ends the callback thread also if the connection task was aborted
+    fn drop(&mut self) {
+        self.queue.close();
+    }
+}
+
 impl GraphQLWSClient {
     pub fn new(url: String, token: String) -> Self {
         Self {
             url,
-            token: Arc::new(Mutex::new(token)),
+            token: Arc::new(watch::channel(token).0),
+            ping_interval: DEFAULT_PING_INTERVAL,
+            idle_timeout: None,
+            connect_timeout: DEFAULT_CONNECT_TIMEOUT,
+            headers: HashMap::new(),
+            init_params: HashMap::new(),
+            user_agent: DEFAULT_USER_AGENT.to_string(),
+            max_message_size: None,
+            max_frame_size: None,
+            tls_connector: None,
+            proxy: None,
+            protocol: WsProtocol::default(),
+            state: Arc::new(watch::channel(ConnectionState::Disconnected).0),
+            close_info: Arc::new(Mutex::new(None)),
+            dispatch_buffer: DEFAULT_DISPATCH_BUFFER,
+            overflow_policy: OverflowPolicy::default(),
             subscriptions: Arc::new(Mutex::new(HashMap::new())),
             subscription_counter: Arc::new(AtomicU32::new(0)),
             command_tx: None,
@@ -118,209 +806,735 @@ impl GraphQLWSClient {
         }
     }
 
+    /// Set the keepalive ping interval (default 30s).
+    ///
+    /// A ping is sent whenever the interval elapses. If nothing at all was received from
+    /// the server within one interval after a ping, the connection is treated as dead.
+    pub fn with_ping_interval(mut self, interval: Duration) -> Self {
+        self.ping_interval = interval;
+        self
+    }
+
+    /// Treat the connection as dead if no message at all (data, ack, pong, ping) is received
+    /// for `timeout` (disabled by default).
+    ///
+    /// This is a receive-side watchdog independent of the keepalive pings, for networks that
+    /// silently drop idle connections. When it fires the connection is closed and all
+    /// subscriptions receive an error.
+    pub fn with_idle_timeout(mut self, timeout: Duration) -> Self {
+        self.idle_timeout = Some(timeout);
+        self
+    }
+
+    /// Set the timeout for establishing the connection, including proxy tunnel, TLS
+    /// and WebSocket upgrade (default 10s)
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = timeout;
+        self
+    }
+
+    /// Set extra headers that are sent with the WebSocket upgrade request and
+    /// included in the `connection_init` payload
+    pub fn with_headers(mut self, headers: HashMap<String, String>) -> Self {
+        self.headers = headers;
+        self
+    }
+
+    /// Set extra entries of the `connection_init` payload, e.g. a tenant id or locale
+    ///
+    /// Unlike `with_headers` they are not sent with the upgrade request. An entry replaces
+    /// an `Authorization` or header entry with the same key.
+    pub fn with_init_params(mut self, params: HashMap<String, String>) -> Self {
+        self.init_params = params;
+        self
+    }
+
+    /// Set the `User-Agent` header of the WebSocket upgrade request (default
+    /// `winccua-graphql-client/<version>`)
+    ///
+    /// A `User-Agent` given in `with_headers` takes precedence.
+    pub fn with_user_agent(mut self, user_agent: String) -> Self {
+        self.user_agent = user_agent;
+        self
+    }
+
+    /// Set the maximum size of an incoming message in bytes (tungstenite default 64 MiB)
+    ///
+    /// A larger message, e.g. a burst of many active alarms, closes the connection and the
+    /// subscriptions receive an error naming the size and the limit.
+    pub fn with_max_message_size(mut self, size: usize) -> Self {
+        self.max_message_size = Some(size);
+        self
+    }
+
+    /// Set the maximum payload size of a single incoming frame in bytes (tungstenite default 16 MiB)
+    ///
+    /// Servers usually send a message as one frame, so raise this together with
+    /// `with_max_message_size`.
+    pub fn with_max_frame_size(mut self, size: usize) -> Self {
+        self.max_frame_size = Some(size);
+        self
+    }
+
+    /// Set the TLS connector used for `wss://` connections
+    ///
+    /// Use this to trust a custom root certificate or to present a client certificate.
+    /// Without it the system trust store is used.
+    pub fn with_tls_connector(mut self, connector: native_tls::TlsConnector) -> Self {
+        self.tls_connector = Some(connector);
+        self
+    }
+
+    /// Tunnel the connection through an HTTP proxy (`http://[user:password@]host:port`)
+    ///
+    /// Without it the proxy is taken from `HTTPS_PROXY` (for `wss://`) or `HTTP_PROXY`
+    /// (for `ws://`), falling back to `ALL_PROXY`; hosts listed in `NO_PROXY` connect directly.
+    /// The connection is tunneled with HTTP `CONNECT`; SOCKS proxies are not supported.
+    pub fn with_proxy(mut self, proxy_url: String) -> Self {
+        self.proxy = Some(proxy_url);
+        self
+    }
+
+    /// Set how many notifications are buffered per subscription for its callbacks (default 100)
+    ///
+    /// The callbacks of each subscription run on their own thread, so a slow callback only
+    /// delays its own subscription until the buffer is full; then the overflow policy applies,
+    /// and with `OverflowPolicy::Backpressure` the whole connection waits for the callback.
+    pub fn with_dispatch_buffer(mut self, capacity: usize) -> Self {
+        self.dispatch_buffer = capacity.max(1);
+        self
+    }
+
+    /// Set what happens when a subscription's buffer is full (default `OverflowPolicy::Backpressure`)
+    pub fn with_overflow_policy(mut self, policy: OverflowPolicy) -> Self {
+        self.overflow_policy = policy;
+        self
+    }
+
+    /// Select the WebSocket subprotocol (default `graphql-transport-ws`)
+    ///
+    /// Use `WsProtocol::SubscriptionsTransportWs` for servers that only speak the legacy
+    /// subscriptions-transport-ws protocol.
+    pub fn with_protocol(mut self, protocol: WsProtocol) -> Self {
+        self.protocol = protocol;
+        self
+    }
+
+    /// Receiver for connection state changes
+    ///
+    /// Use `borrow()` for the current state or `changed().await` to wait for the next change.
+    pub fn state(&self) -> watch::Receiver<ConnectionState> {
+        self.state.subscribe()
+    }
+
+    /// Current connection state
+    pub fn connection_state(&self) -> ConnectionState {
+        *self.state.borrow()
+    }
+
+    /// Close code and reason of the last connection, if the server closed it with a close frame
+    ///
+    /// Reset when a new connection is started.
+    pub fn close_info(&self) -> Option<CloseInfo> {
+        self.close_info.lock().unwrap().clone()
+    }
+
+    /// Connect and wait until the WebSocket handshake completed.
+    ///
+    /// The `connection_init`/`connection_ack` exchange continues in the background;
+    /// subscriptions made before the acknowledgement are queued. Use `connect_and_wait`
+    /// to also wait for `connection_ack`.
+    ///
+    /// Errors:
+    /// - InvalidParameter - The URL is not a `ws://` or `wss://` URL
+    /// - WebSocketError - The connection or handshake failed or timed out
     pub async fn connect(&mut self) -> Result<(), WinCCError> {
         if self.handle.is_some() {
-            println!("WebSocket already connected");
+            log::debug!("WebSocket already connected");
+            return Ok(());
+        }
+
+        let (handshake_tx, handshake_rx) = oneshot::channel();
+        self.start(None, Some(handshake_tx))?;
+
+        let result = match handshake_rx.await {
+            Ok(Ok(())) => Ok(()),
+            Ok(Err(e)) => Err(WinCCError::WebSocketError(e)),
+            Err(_) => Err(WinCCError::WebSocketError("Connection task ended during handshake".to_string())),
+        };
+
+        if result.is_err() {
+            self.disconnect().await;
+        }
+        result
+    }
+
+    /// Connect and wait until the server acknowledged the connection (`connection_ack`).
+    ///
+    /// Errors:
+    /// - WebSocketError - The connection failed, the server sent `connection_error`,
+    ///   closed the connection, or no `connection_ack` arrived within `timeout`
+    pub async fn connect_and_wait(&mut self, timeout: Duration) -> Result<(), WinCCError> {
+        if self.handle.is_some() {
+            let mut state = self.state();
+            let state = tokio::time::timeout(timeout, state.wait_for(|s| *s != ConnectionState::Connecting))
+                .await
+                .map_err(|_| WinCCError::WebSocketError("Timed out waiting for connection_ack".to_string()))?
+                .map(|s| *s)
+                .unwrap_or(ConnectionState::Disconnected);
+            return match state {
+                ConnectionState::Connected => Ok(()),
+                _ => Err(WinCCError::WebSocketError("WebSocket not connected".to_string())),
+            };
+        }
+
+        let (ack_tx, ack_rx) = oneshot::channel();
+        self.start(Some(ack_tx), None)?;
+
+        let result = self.wait_for_ack(ack_rx, timeout).await;
+        if result.is_err() {
+            self.disconnect().await;
+        }
+        result
+    }
+
+    /// Close the connection and connect again, replaying all active subscriptions
+    ///
+    /// For connections known to be bad, e.g. after a subscription error. The current
+    /// connection is closed, a new one is opened with the current token, `connection_init`
+    /// is sent and, after `connection_ack`, every subscription that is still active is
+    /// subscribed again with its id, so `Subscription` handles and callbacks stay valid.
+    /// Returns after the new `connection_ack`, waiting at most the connect timeout.
+    ///
+    /// If the connection task has already ended (the server closed the connection), its
+    /// subscriptions were closed with an error and are not replayed; a new connection without
+    /// subscriptions is opened, as with `connect_and_wait`.
+    ///
+    /// Errors:
+    /// - WebSocketError - The new connection failed, was rejected, or no `connection_ack`
+    ///   arrived in time; the client is then disconnected
+    pub async fn reconnect(&mut self) -> Result<(), WinCCError> {
+        let timeout = self.connect_timeout;
+        let (ack_tx, ack_rx) = oneshot::channel();
+        let running = self.handle.as_ref().is_some_and(|handle| !handle.is_finished());
+        let requested = match (&self.command_tx, running) {
+            (Some(tx), true) => tx.send(WSCommand::Reconnect { ack: ack_tx }).await.is_ok(),
+            _ => false,
+        };
+        if !requested {
+            log::debug!("Connection task not running, starting a new connection");
+            self.disconnect().await;
+            return self.connect_and_wait(timeout).await;
+        }
+
+        let result = self.wait_for_ack(ack_rx, timeout).await;
+        if result.is_err() {
+            self.disconnect().await;
+        }
+        result
+    }
+
+    /// Wait for the `connection_ack` reported by the connection task
+    async fn wait_for_ack(
+        &self,
+        ack_rx: oneshot::Receiver<Result<(), String>>,
+        timeout: Duration,
+    ) -> Result<(), WinCCError> {
+        match tokio::time::timeout(timeout, ack_rx).await {
+            Ok(Ok(Ok(()))) => Ok(()),
+            Ok(Ok(Err(e))) => Err(match self.close_info() {
+                Some(close_info) if close_info.is_unauthorized() => close_info.to_error(),
+                _ => WinCCError::WebSocketError(e),
+            }),
+            Ok(Err(_)) => Err(WinCCError::WebSocketError("Connection task ended before connection_ack".to_string())),
+            Err(_) => Err(WinCCError::WebSocketError("Timed out waiting for connection_ack".to_string())),
+        }
+    }
+
+    fn start(
+        &mut self,
+        ack_tx: Option<oneshot::Sender<Result<(), String>>>,
+        handshake_tx: Option<oneshot::Sender<Result<(), String>>>,
+    ) -> Result<(), WinCCError> {
+        if self.handle.is_some() {
+            log::debug!("WebSocket already connected");
             return Ok(());
         }
 
-        println!("Starting WebSocket connection...");
+        validate_ws_url(&self.url)?;
+        log::debug!("Starting WebSocket connection...");
         let (command_tx, mut command_rx) = mpsc::channel::<WSCommand>(100);
         self.command_tx = Some(command_tx.clone());
-        println!("Command channel created");
+        log::debug!("Command channel created");
 
         let url = self.url.clone();
-        let token = self.token.lock().unwrap().clone();
+        let mut token_rx = self.token.subscribe();
+        let token = token_rx.borrow_and_update().clone();
         let subscriptions = self.subscriptions.clone();
+        let (dispatch_buffer, overflow_policy) = (self.dispatch_buffer, self.overflow_policy);
+        let ping_interval = self.ping_interval;
+        let idle_timeout = self.idle_timeout;
+        let headers = self.headers.clone();
+        let init_params = self.init_params.clone();
+        let user_agent = self.user_agent.clone();
+        let mut ws_config = WebSocketConfig::default();
+        if let Some(size) = self.max_message_size {
+            ws_config.max_message_size = Some(size);
+        }
+        if let Some(size) = self.max_frame_size {
+            ws_config.max_frame_size = Some(size);
+        }
+        let connector = self.tls_connector.clone().map(Connector::NativeTls);
+        let proxy = self.proxy.clone();
+        let protocol = self.protocol;
+        let connect_timeout = self.connect_timeout;
+        let state = self.state.clone();
+        let last_close = self.close_info.clone();
+        *last_close.lock().unwrap() = None;
+        state.send_replace(ConnectionState::Connecting);
 
         let handle = tokio::spawn(async move {
+            let mut ack_tx = ack_tx;
             let mut connection_ready = false;
             let mut pending_commands = Vec::new();
-            // Try with graphql-transport-ws subprotocol using proper request building
-            println!("Connecting to WebSocket URL: {}", url);
+            let mut handshake_tx = handshake_tx;
+            let mut token = token;
+            // Query and variables of the subscriptions sent to the server, replayed after a reconnect
+            let mut operations: HashMap<String, (String, HashMap<String, Value>)> = HashMap::new();
+            let mut reconnect_token: Option<String> = None;
+            // Reason the connection was dropped by the client, reported instead of the generic close message
+            let mut failure: Option<String> = None;
             
-            // Build proper WebSocket request with subprotocol
-            use tungstenite::client::IntoClientRequest;
-            let mut request = url.into_client_request().expect("Failed to build request");
-            request.headers_mut().insert(
-                "Sec-WebSocket-Protocol", 
-                "graphql-transport-ws".parse().expect("Invalid protocol header")
-            );
-            
-            let (ws_stream, _response) = match connect_async(request).await {
-                Ok(result) => {
-                    println!("WebSocket handshake successful, status: {}", result.1.status());
-                    result
-                },
-                Err(e) => {
-                    eprintln!("WebSocket connection failed: {}", e);
-                    return;
+            loop {
+                // Request the selected subprotocol using proper request building
+                log::debug!("Connecting to WebSocket URL: {}", url);
+                
+                // Build proper WebSocket request with subprotocol
+                use tungstenite::client::IntoClientRequest;
+                let mut request = url.as_str().into_client_request().expect("Failed to build request");
+                request.headers_mut().insert(
+                    "Sec-WebSocket-Protocol", 
+                    protocol.subprotocol().parse().expect("Invalid protocol header")
+                );
+                match user_agent.parse::<http::HeaderValue>() {
+                    Ok(value) => {
+                        request.headers_mut().insert(http::header::USER_AGENT, value);
+                    }
+                    Err(_) => log::error!("Skipping invalid WebSocket User-Agent: {}", user_agent),
                 }
-            };
+                for (name, value) in &headers {
+                    match (name.parse::<http::HeaderName>(), value.parse::<http::HeaderValue>()) {
+                        (Ok(name), Ok(value)) => {
+                            request.headers_mut().insert(name, value);
+                        }
+                        _ => log::error!("Skipping invalid WebSocket header: {}", name),
+                    }
+                }
+                
+                let target = (
+                    request.uri().host().unwrap_or_default().to_string(),
+                    request.uri().port_u16().unwrap_or(if request.uri().scheme_str() == Some("wss") { 443 } else { 80 }),
+                );
+                let proxy = proxy.clone().or_else(|| proxy_from_env(request.uri().scheme_str() == Some("wss"), &target.0));
+                
+                let connector = connector.clone();
+                let connect = async {
+                    match proxy {
+                        Some(proxy) => {
+                            log::debug!("Connecting through proxy {}", redact_proxy_url(&proxy));
+                            let stream = connect_via_proxy(&proxy, &target.0, target.1).await?;
+                            client_async_tls_with_config(request, stream, Some(ws_config), connector)
+                                .await
+                                .map_err(|e| e.to_string())
+                        }
+                        None => connect_async_tls_with_config(request, Some(ws_config), false, connector)
+                            .await
+                            .map_err(|e| e.to_string()),
+                    }
+                };
+                let connect_result = tokio::time::timeout(connect_timeout, connect)
+                    .await
+                    .unwrap_or_else(|_| Err(format!("timed out after {:?}", connect_timeout)));
+                
+                let (ws_stream, _response) = match connect_result {
+                    Ok(result) => {
+                        log::debug!("WebSocket handshake successful, status: {}", result.1.status());
+                        if let Some(tx) = handshake_tx.take() {
+                            let _ = tx.send(Ok(()));
+                        }
+                        result
+                    },
+                    Err(e) => {
+                        log::error!("WebSocket connection failed: {}", e);
+                        state.send_replace(ConnectionState::Disconnected);
+                        let message = format!("WebSocket connection failed: {}", e);
+                        if let Some(tx) = handshake_tx.take() {
+                            let _ = tx.send(Err(message.clone()));
+                        }
+                        if let Some(tx) = ack_tx.take() {
+                            let _ = tx.send(Err(message));
+                        }
+                        break;
+                    }
+                };
 
-            let (mut write, mut read) = ws_stream.split();
+                let (mut write, mut read) = ws_stream.split();
 
-            // Send connection init for graphql-transport-ws protocol
-            let init_msg = WSMessage::ConnectionInit {
-                payload: {
-                    let mut payload = HashMap::new();
-                    if !token.is_empty() {
-                        payload.insert("Authorization".to_string(), format!("Bearer {}", token));
-                    }
-                    payload
-                },
-            };
+                // Send connection init, identical in both protocols
+                let init_msg = WSMessage::ConnectionInit {
+                    payload: {
+                        let mut payload = HashMap::new();
+                        if !token.is_empty() {
+                            payload.insert("Authorization".to_string(), format!("Bearer {}", token));
+                        }
+                        payload.extend(headers.clone());
+                        payload.extend(init_params.clone());
+                        payload
+                    },
+                };
 
-            if let Ok(json) = serde_json::to_string(&init_msg) {
-                println!("Sending connection_init: {}", json);
-                let _ = write.send(Message::Text(json)).await;
-            } else {
-                eprintln!("Failed to serialize connection_init message");
-                return;
-            }
+                if let Ok(json) = protocol.encode(&init_msg) {
+                    // The payload carries the bearer token, so it is never logged
+                    log::debug!("Sending connection_init");
+                    let _ = write.send(Message::Text(json)).await;
+                } else {
+                    log::error!("Failed to serialize connection_init message");
+                    break;
+                }
 
-            loop {
-                tokio::select! {
-                    Some(msg) = read.next() => {
-                        match msg {
-                            Ok(Message::Text(text)) => {
-                                println!("Received WebSocket message: {}", text);
-                                if let Ok(ws_msg) = serde_json::from_str::<WSMessage>(&text) {
-                                    println!("Parsed message type: {:?}", ws_msg);
-                                    match ws_msg {
-                                        WSMessage::ConnectionAck => {
-                                            println!("WebSocket connection acknowledged - ready for subscriptions");
-                                            connection_ready = true;
-                                            
-                                            // Process any pending subscription commands
-                                            for cmd in pending_commands.drain(..) {
-                                                if let WSCommand::Subscribe { id, query, variables, callbacks } = cmd {
-                                                    println!("Processing pending subscribe command for ID: {}", id);
-                                                    subscriptions.lock().unwrap().insert(id.clone(), callbacks);
-                                                    
+                let mut ping_timer = tokio::time::interval_at(
+                    tokio::time::Instant::now() + ping_interval,
+                    ping_interval,
+                );
+                ping_timer.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+                let mut last_received = Instant::now();
+                let mut last_ping: Option<Instant> = None;
+
+                loop {
+                    tokio::select! {
+                        Some(msg) = read.next() => {
+                            if msg.is_ok() {
+                                last_received = Instant::now();
+                            }
+                            match msg {
+                                Ok(Message::Text(text)) => {
+                                    log::trace!("Received WebSocket message: {}", text);
+                                    if let Some(ws_msg) = protocol.decode(&text) {
+                                        log::trace!("Parsed message type: {:?}", ws_msg);
+                                        match ws_msg {
+                                            WSMessage::ConnectionAck => {
+                                                log::debug!("WebSocket connection acknowledged - ready for subscriptions");
+                                                connection_ready = true;
+                                                state.send_replace(ConnectionState::Connected);
+                                                if let Some(tx) = ack_tx.take() {
+                                                    let _ = tx.send(Ok(()));
+                                                }
+                                                
+                                                // Replay the subscriptions of the previous connection after a token update
+                                                for (id, (query, variables)) in &operations {
+                                                    log::debug!("Resubscribing {} after reconnect", id);
                                                     let subscribe_msg = WSMessage::Subscribe {
                                                         id: id.clone(),
-                                                        payload: SubscribePayload { query, variables },
+                                                        payload: SubscribePayload { query: query.clone(), variables: variables.clone() },
                                                     };
-                                                    
-                                                    if let Ok(json) = serde_json::to_string(&subscribe_msg) {
-                                                        println!("Sending pending subscribe message: {}", json);
-                                                        match write.send(Message::Text(json)).await {
-                                                            Ok(_) => println!("Pending subscribe message sent successfully"),
-                                                            Err(e) => eprintln!("Failed to send pending subscribe message: {}", e),
+                                                    if let Ok(json) = protocol.encode(&subscribe_msg) {
+                                                        if let Err(e) = write.send(Message::Text(json)).await {
+                                                            log::error!("Failed to resend subscribe message: {}", e);
+                                                        }
+                                                    }
+                                                }
+                                                
+                                                // Process any pending subscription commands
+                                                for cmd in pending_commands.drain(..) {
+                                                    if let WSCommand::Subscribe { id, query, variables, callbacks } = cmd {
+                                                        log::debug!("Processing pending subscribe command for ID: {}", id);
+                                                        let Some(dispatcher) = Dispatcher::spawn(callbacks, dispatch_buffer, overflow_policy) else {
+                                                            continue;
+                                                        };
+                                                        subscriptions.lock().unwrap().insert(id.clone(), dispatcher);
+                                                        operations.insert(id.clone(), (query.clone(), variables.clone()));
+                                                        
+                                                        let subscribe_msg = WSMessage::Subscribe {
+                                                            id: id.clone(),
+                                                            payload: SubscribePayload { query, variables },
+                                                        };
+                                                        
+                                                        if let Ok(json) = protocol.encode(&subscribe_msg) {
+                                                            log::trace!("Sending pending subscribe message: {}", json);
+                                                            match write.send(Message::Text(json)).await {
+                                                                Ok(_) => log::debug!("Pending subscribe message sent successfully"),
+                                                                Err(e) => log::error!("Failed to send pending subscribe message: {}", e),
+                                                            }
                                                         }
                                                     }
                                                 }
                                             }
-                                        }
-                                        WSMessage::ConnectionError { payload } => {
-                                            eprintln!("Connection error: {:?}", payload);
-                                            break;
-                                        }
-                                        WSMessage::Next { id, payload } => {
-                                            println!("Received data for subscription {}: {:?}", id, payload);
-                                            if let Some(callbacks) = subscriptions.lock().unwrap().get(&id) {
-                                                (callbacks.on_data)(payload);
+                                            WSMessage::ConnectionError { payload } => {
+                                                log::error!("Connection error: {:?}", payload);
+                                                if let Some(tx) = ack_tx.take() {
+                                                    let _ = tx.send(Err(format!("Connection error: {}", payload)));
+                                                }
+                                                break;
                                             }
-                                        }
-                                        WSMessage::Error { id, payload } => {
-                                            eprintln!("Subscription error for {}: {:?}", id, payload);
-                                            if let Some(callbacks) = subscriptions.lock().unwrap().get(&id) {
-                                                if let Some(on_error) = &callbacks.on_error {
-                                                    (on_error)(payload.to_string());
+                                            WSMessage::Next { id, payload } => {
+                                                log::trace!("Received data for subscription {}: {:?}", id, payload);
+                                                let queue = subscriptions.lock().unwrap().get(&id).map(|dispatcher| dispatcher.queue.clone());
+                                                if let Some(queue) = queue {
+                                                    queue.push_data(payload).await;
                                                 }
                                             }
-                                        }
-                                        WSMessage::Complete { id } => {
-                                            println!("Subscription {} completed", id);
-                                            if let Some(callbacks) = subscriptions.lock().unwrap().remove(&id) {
-                                                if let Some(on_complete) = &callbacks.on_complete {
-                                                    (on_complete)();
+                                            WSMessage::Error { id, payload } => {
+                                                log::error!("Subscription error for {}: {:?}", id, payload);
+                                                if let Some(dispatcher) = subscriptions.lock().unwrap().get(&id) {
+                                                    dispatcher.queue.push_control(DispatchEvent::Error(payload.to_string()));
                                                 }
                                             }
+                                            WSMessage::Ping { .. } => {
+                                                log::trace!("Received ping, sending pong");
+                                                if let Ok(json) = protocol.encode(&WSMessage::Pong { payload: None }) {
+                                                    let _ = write.send(Message::Text(json)).await;
+                                                }
+                                            }
+                                            WSMessage::Complete { id } => {
+                                                log::debug!("Subscription {} completed", id);
+                                                operations.remove(&id);
+                                                if let Some(dispatcher) = subscriptions.lock().unwrap().remove(&id) {
+                                                    dispatcher.queue.push_control(DispatchEvent::Complete);
+                                                    dispatcher.queue.close();
+                                                }
+                                            }
+                                            _ => {
+                                                log::debug!("Unhandled message type: {:?}", ws_msg);
+                                            }
                                         }
-                                        _ => {
-                                            println!("Unhandled message type: {:?}", ws_msg);
-                                        }
+                                    } else {
+                                        log::error!("Failed to parse WebSocket message: {}", text);
                                     }
-                                } else {
-                                    eprintln!("Failed to parse WebSocket message: {}", text);
+                                }
+                                Ok(Message::Ping(data)) => {
+                                    log::trace!("Received ping frame, sending pong");
+                                    let _ = write.send(Message::Pong(data)).await;
+                                }
+                                Ok(Message::Close(close_frame)) => {
+                                    log::debug!("WebSocket connection closed: {:?}", close_frame);
+                                    if let Some(frame) = close_frame {
+                                        *last_close.lock().unwrap() = Some(CloseInfo {
+                                            code: u16::from(frame.code),
+                                            reason: frame.reason.to_string(),
+                                        });
+                                    }
+                                    break;
+                                }
+                                Err(tungstenite::Error::Capacity(CapacityError::MessageTooLong { size, max_size })) => {
+                                    let message = format!(
+                                        "WebSocket message of {} bytes exceeds the limit of {} bytes (see max_message_size / max_frame_size)",
+                                        size, max_size
+                                    );
+                                    log::error!("{}", message);
+                                    failure = Some(message);
+                                    break;
+                                }
+                                Err(e) => {
+                                    log::error!("WebSocket error: {}", e);
+                                    break;
+                                }
+                                _ => {
+                                    log::trace!("Received other message type: {:?}", msg);
                                 }
                             }
-                            Ok(Message::Close(close_frame)) => {
-                                println!("WebSocket connection closed: {:?}", close_frame);
-                                break;
+                        }
+                        _ = idle_deadline(idle_timeout, last_received) => {
+                            log::error!("No message received from server within {:?}, connection is dead", idle_timeout.unwrap_or_default());
+                            break;
+                        }
+                        _ = ping_timer.tick() => {
+                            if let Some(sent) = last_ping {
+                                if last_received < sent {
+                                    log::error!("No response from server within {:?} after ping, connection is dead", ping_interval);
+                                    break;
+                                }
                             }
-                            Err(e) => {
-                                eprintln!("WebSocket error: {}", e);
+                            log::trace!("Sending keepalive ping");
+                            if let Err(e) = write.send(Message::Ping(Vec::new())).await {
+                                log::error!("Failed to send keepalive ping: {}", e);
                                 break;
                             }
-                            _ => {
-                                println!("Received other message type: {:?}", msg);
+                            last_ping = Some(Instant::now());
+                        }
+                        Ok(()) = token_rx.changed() => {
+                            let new_token = token_rx.borrow_and_update().clone();
+                            if new_token == token {
+                                continue;
                             }
+                            // connection_init can only be sent once per connection, so reconnect
+                            // with the new token and replay the active subscriptions
+                            log::debug!("Token updated, reconnecting and replaying {} subscriptions", operations.len());
+                            reconnect_token = Some(new_token);
+                            break;
                         }
-                    }
-                    Some(cmd) = command_rx.recv() => {
-                        match cmd {
-                            WSCommand::Subscribe { id, query, variables, callbacks } => {
-                                println!("Processing subscribe command for ID: {}", id);
-                                if !connection_ready {
-                                    println!("Connection not ready yet, queuing command...");
-                                    pending_commands.push(WSCommand::Subscribe { id, query, variables, callbacks });
-                                    continue;
+                        Some(cmd) = command_rx.recv() => {
+                            match cmd {
+                                WSCommand::Subscribe { id, query, variables, callbacks } => {
+                                    log::debug!("Processing subscribe command for ID: {}", id);
+                                    if !connection_ready {
+                                        log::debug!("Connection not ready yet, queuing command...");
+                                        pending_commands.push(WSCommand::Subscribe { id, query, variables, callbacks });
+                                        continue;
+                                    }
+                                    
+                                    let Some(dispatcher) = Dispatcher::spawn(callbacks, dispatch_buffer, overflow_policy) else {
+                                        continue;
+                                    };
+                                    subscriptions.lock().unwrap().insert(id.clone(), dispatcher);
+                                    operations.insert(id.clone(), (query.clone(), variables.clone()));
+                                    
+                                    let subscribe_msg = WSMessage::Subscribe {
+                                        id: id.clone(),
+                                        payload: SubscribePayload { query, variables },
+                                    };
+                                    
+                                    if let Ok(json) = protocol.encode(&subscribe_msg) {
+                                        log::trace!("Sending subscribe message: {}", json);
+                                        match write.send(Message::Text(json)).await {
+                                            Ok(_) => log::debug!("Subscribe message sent successfully"),
+                                            Err(e) => log::error!("Failed to send subscribe message: {}", e),
+                                        }
+                                    } else {
+                                        log::error!("Failed to serialize subscribe message");
+                                    }
                                 }
-                                
-                                subscriptions.lock().unwrap().insert(id.clone(), callbacks);
-                                
-                                let subscribe_msg = WSMessage::Subscribe {
-                                    id: id.clone(),
-                                    payload: SubscribePayload { query, variables },
-                                };
-                                
-                                if let Ok(json) = serde_json::to_string(&subscribe_msg) {
-                                    println!("Sending subscribe message: {}", json);
-                                    match write.send(Message::Text(json)).await {
-                                        Ok(_) => println!("Subscribe message sent successfully"),
-                                        Err(e) => eprintln!("Failed to send subscribe message: {}", e),
+                                WSCommand::Unsubscribe { id, done } => {
+                                    // Undelivered data is discarded; `done` is answered once the callback
+                                    // thread has finished, so no more data is delivered afterwards
+                                    let removed = subscriptions.lock().unwrap().remove(&id);
+                                    operations.remove(&id);
+                                    let pending = pending_commands.iter().position(|cmd| {
+                                        matches!(cmd, WSCommand::Subscribe { id: pending_id, .. } if *pending_id == id)
+                                    });
+                                    
+                                    let result = if let Some(index) = pending {
+                                        // Never sent to the server, nothing to complete
+                                        if let WSCommand::Subscribe { callbacks, .. } = pending_commands.remove(index) {
+                                            if let Some(on_complete) = &callbacks.on_complete {
+                                                (on_complete)();
+                                            }
+                                        }
+                                        Ok(())
+                                    } else {
+                                        match protocol.encode(&WSMessage::Complete { id }) {
+                                            Ok(json) => write.send(Message::Text(json)).await
+                                                .map_err(|e| format!("Failed to send complete message: {}", e)),
+                                            Err(e) => Err(format!("Failed to serialize complete message: {}", e)),
+                                        }
+                                    };
+                                    
+                                    match removed {
+                                        Some(mut dispatcher) => {
+                                            dispatcher.queue.clear_data();
+                                            dispatcher.queue.push_control(DispatchEvent::Complete);
+                                            dispatcher.queue.close();
+                                            tokio::spawn(async move {
+                                                let _ = (&mut dispatcher.finished).await;
+                                                if let Some(done) = done {
+                                                    let _ = done.send(result);
+                                                }
+                                            });
+                                        }
+                                        None => {
+                                            if let Some(done) = done {
+                                                let _ = done.send(result);
+                                            }
+                                        }
                                     }
-                                } else {
-                                    eprintln!("Failed to serialize subscribe message");
                                 }
-                            }
-                            WSCommand::Unsubscribe { id } => {
-                                subscriptions.lock().unwrap().remove(&id);
-                                
-                                let complete_msg = WSMessage::Complete { id };
-                                if let Ok(json) = serde_json::to_string(&complete_msg) {
-                                    let _ = write.send(Message::Text(json)).await;
+                                WSCommand::UnsubscribeAll { done } => {
+                                    // Same as Unsubscribe for every subscription; `done` is answered
+                                    // once all callback threads have finished
+                                    let removed: Vec<_> = subscriptions.lock().unwrap().drain().collect();
+                                    operations.clear();
+                                    log::debug!("Unsubscribing all {} subscriptions", removed.len());
+                                    
+                                    // Subscriptions never sent to the server have nothing to complete
+                                    for cmd in pending_commands.drain(..) {
+                                        if let WSCommand::Subscribe { callbacks, .. } = cmd {
+                                            if let Some(on_complete) = &callbacks.on_complete {
+                                                (on_complete)();
+                                            }
+                                        }
+                                    }
+                                    
+                                    let mut result = Ok(());
+                                    let mut dispatchers = Vec::with_capacity(removed.len());
+                                    for (id, dispatcher) in removed {
+                                        let sent = match protocol.encode(&WSMessage::Complete { id }) {
+                                            Ok(json) => write.send(Message::Text(json)).await
+                                                .map_err(|e| format!("Failed to send complete message: {}", e)),
+                                            Err(e) => Err(format!("Failed to serialize complete message: {}", e)),
+                                        };
+                                        if result.is_ok() {
+                                            result = sent;
+                                        }
+                                        dispatcher.queue.clear_data();
+                                        dispatcher.queue.push_control(DispatchEvent::Complete);
+                                        dispatcher.queue.close();
+                                        dispatchers.push(dispatcher);
+                                    }
+                                    tokio::spawn(async move {
+                                        for dispatcher in &mut dispatchers {
+                                            let _ = (&mut dispatcher.finished).await;
+                                        }
+                                        let _ = done.send(result);
+                                    });
+                                }
+                                WSCommand::Reconnect { ack } => {
+                                    log::debug!("Reconnect requested, replaying {} subscriptions", operations.len());
+                                    ack_tx = Some(ack);
+                                    reconnect_token = Some(token.clone());
+                                    break;
+                                }
+                                WSCommand::Disconnect => {
+                                    let _ = write.send(Message::Close(None)).await;
+                                    break;
                                 }
-                            }
-                            WSCommand::UpdateToken { token: _ } => {
-                                // For token update, we'd need to reconnect
-                                // This is simplified - in production you'd handle this more gracefully
-                                break;
-                            }
-                            WSCommand::Disconnect => {
-                                let _ = write.send(Message::Close(None)).await;
-                                break;
                             }
                         }
                     }
                 }
+
+                let Some(new_token) = reconnect_token.take() else {
+                    break;
+                };
+                let _ = write.send(Message::Close(None)).await;
+                token = new_token;
+                connection_ready = false;
+                state.send_replace(ConnectionState::Connecting);
             }
 
-            // Clean up subscriptions on disconnect
-            for (_, callbacks) in subscriptions.lock().unwrap().iter() {
-                if let Some(on_error) = &callbacks.on_error {
-                    (on_error)("WebSocket connection closed".to_string());
+            let close_info = last_close.lock().unwrap().clone();
+            let closed_message = match (&close_info, failure) {
+                (_, Some(failure)) => failure,
+                (Some(close_info), None) => format!("WebSocket connection closed by server ({})", close_info),
+                (None, None) => "WebSocket connection closed".to_string(),
+            };
+            state.send_replace(ConnectionState::Disconnected);
+            if let Some(tx) = ack_tx.take() {
+                let _ = tx.send(Err(format!("{} before connection_ack", closed_message)));
+            }
+
+            // Clean up subscriptions on disconnect; the callback threads end after delivering these
+            for (_, dispatcher) in subscriptions.lock().unwrap().drain() {
+                if let Some(close_info) = &close_info {
+                    dispatcher.queue.push_control(DispatchEvent::Close(close_info.clone()));
                 }
+                dispatcher.queue.push_control(DispatchEvent::Error(closed_message.clone()));
+                dispatcher.queue.close();
             }
-            subscriptions.lock().unwrap().clear();
         });
 
         self.handle = Some(handle);
 
-        // Don't wait here - let the connection establish in the background
+        // Don't wait here - let the connection establish in the background,
+        // connect_and_wait waits for the acknowledgement
         Ok(())
     }
 
@@ -328,24 +1542,23 @@ impl GraphQLWSClient {
         &self,
         query: String,
         variables: HashMap<String, Value>,
-        callbacks: SubscriptionCallbacks,
+        mut callbacks: SubscriptionCallbacks,
     ) -> Result<Subscription, WinCCError> {
         let id = format!("sub_{}", self.subscription_counter.fetch_add(1, Ordering::SeqCst));
-        println!("Creating subscription with ID: {}", id);
+        let operation_name = graphql::operation_name(&query).map(str::to_string);
+        log::debug!("Creating subscription with ID: {} ({})", id, operation_name.as_deref().unwrap_or("anonymous"));
+        
+        // The marker lives as long as the connection task keeps the callbacks
+        let alive = Arc::new(());
+        let marker = Arc::downgrade(&alive);
+        let on_data = callbacks.on_data.clone();
+        callbacks.on_data = Arc::new(move |payload| {
+            let _ = &alive;
+            (on_data)(payload)
+        });
         
         if let Some(tx) = &self.command_tx {
-            println!("Command channel available, sending subscribe command");
-            let (unsubscribe_tx, mut unsubscribe_rx) = mpsc::channel(1);
-            
-            let cmd_tx = tx.clone();
-            let sub_id = id.clone();
-            tokio::spawn(async move {
-                if let Some(_) = unsubscribe_rx.recv().await {
-                    println!("Unsubscribe requested for: {}", sub_id);
-                    let _ = cmd_tx.send(WSCommand::Unsubscribe { id: sub_id }).await;
-                }
-            });
-
+            log::debug!("Command channel available, sending subscribe command");
             match tx.send(WSCommand::Subscribe {
                 id: id.clone(),
                 query,
@@ -354,25 +1567,268 @@ impl GraphQLWSClient {
             })
             .await {
                 Ok(_) => {
-                    println!("Subscribe command queued successfully");
-                    Ok(Subscription { id, unsubscribe_tx })
+                    log::debug!("Subscribe command queued successfully");
+                    Ok(Subscription { id, operation_name, command_tx: tx.clone(), alive: marker })
                 }
                 Err(e) => {
-                    eprintln!("Failed to queue subscribe command: {}", e);
+                    log::error!("Failed to queue subscribe command: {}", e);
                     Err(WinCCError::OperationFailed("Failed to send subscribe command".to_string()))
                 }
             }
         } else {
-            eprintln!("WebSocket command channel not available");
+            log::error!("WebSocket command channel not available");
             Err(WinCCError::OperationFailed("WebSocket not connected".to_string()))
         }
     }
 
+    pub async fn subscribe_typed<T: DeserializeOwned + 'static>(
+        &self,
+        query: String,
+        variables: HashMap<String, Value>,
+        field_name: &str,
+        callbacks: TypedSubscriptionCallbacks<T>,
+    ) -> Result<Subscription, WinCCError> {
+        self.subscribe(query, variables, callbacks.into_callbacks(field_name)).await
+    }
+
+    /// Subscribe and wait until the server responds to the subscription
+    ///
+    /// `subscribe` returns as soon as the subscription is queued, before it was sent. This
+    /// waits at most `timeout` for the first `next`, `error` or `complete` message of the
+    /// subscription (the protocol has no separate acknowledgement per subscription). The first
+    /// event is also passed to the callbacks. Only use it for subscriptions the server answers
+    /// right away, e.g. tag values, which start with the current values; an active alarms
+    /// subscription may stay silent while no alarm is active.
+    ///
+    /// Errors:
+    /// - WebSocketError - The server answered with an error, closed the connection or did not
+    ///   respond within `timeout` (the subscription is then unsubscribed)
+    /// - OperationFailed - The WebSocket is not connected
+    pub async fn subscribe_with_timeout(
+        &self,
+        query: String,
+        variables: HashMap<String, Value>,
+        mut callbacks: SubscriptionCallbacks,
+        timeout: Duration,
+    ) -> Result<Subscription, WinCCError> {
+        let (started_tx, started_rx) = oneshot::channel::<Result<(), WinCCError>>();
+        let started_tx = Arc::new(Mutex::new(Some(started_tx)));
+        let started = move |result: Result<(), WinCCError>| {
+            if let Some(tx) = started_tx.lock().unwrap().take() {
+                let _ = tx.send(result);
+            }
+        };
+
+        let on_data = callbacks.on_data.clone();
+        let data_started = started.clone();
+        callbacks.on_data = Arc::new(move |payload| {
+            data_started(Ok(()));
+            (on_data)(payload)
+        });
+        let on_error = callbacks.on_error.take();
+        let error_started = started.clone();
+        callbacks.on_error = Some(Arc::new(move |message| {
+            error_started(Err(WinCCError::WebSocketError(message.clone())));
+            if let Some(on_error) = &on_error {
+                (on_error)(message);
+            }
+        }));
+        let on_complete = callbacks.on_complete.take();
+        let complete_started = started.clone();
+        callbacks.on_complete = Some(Arc::new(move || {
+            complete_started(Ok(()));
+            if let Some(on_complete) = &on_complete {
+                (on_complete)();
+            }
+        }));
+        let on_close = callbacks.on_close.take();
+        callbacks.on_close = Some(Arc::new(move |close_info| {
+            started(Err(close_info.to_error()));
+            if let Some(on_close) = &on_close {
+                (on_close)(close_info);
+            }
+        }));
+
+        let subscription = self.subscribe(query, variables, callbacks).await?;
+        match tokio::time::timeout(timeout, started_rx).await {
+            Ok(Ok(Ok(()))) => Ok(subscription),
+            Ok(Ok(Err(e))) => Err(e),
+            Ok(Err(_)) => Err(WinCCError::WebSocketError("Subscription ended without a response".to_string())),
+            Err(_) => {
+                log::warn!("No response to subscription {} within {:?}", subscription.id(), timeout);
+                let id = subscription.id().to_string();
+                let _ = subscription.unsubscribe().await;
+                Err(WinCCError::WebSocketError(format!("No response to subscription {} within {:?}", id, timeout)))
+            }
+        }
+    }
+
+    /// Subscribe and receive the notifications as a `Stream`
+    ///
+    /// ```no_run
+    /// # async fn example(ws_client: winccua_graphql_client::GraphQLWSClient) -> Result<(), winccua_graphql_client::WinCCError> {
+    /// use futures_util::StreamExt;
+    /// use winccua_graphql_client::subscriptions;
+    ///
+    /// let mut variables = std::collections::HashMap::new();
+    /// variables.insert("names".to_string(), serde_json::json!(["HMI_Tag_1"]));
+    /// let mut stream = ws_client.subscribe_stream(subscriptions::TAG_VALUES.to_string(), variables).await?;
+    /// while let Some(notification) = stream.next().await {
+    ///     println!("{}", notification?);
+    /// }
+    /// # Ok(())
+    /// # }
+    /// ```
+    pub async fn subscribe_stream(
+        &self,
+        query: String,
+        variables: HashMap<String, Value>,
+    ) -> Result<SubscriptionStream, WinCCError> {
+        let (tx, rx) = mpsc::unbounded_channel();
+        let error_tx = tx.clone();
+        let close_tx = tx.clone();
+        let callbacks = SubscriptionCallbacks::new(move |payload| {
+            let _ = tx.send(Ok(payload));
+        })
+        .with_error(move |e| {
+            let _ = error_tx.send(Err(WinCCError::WebSocketError(e)));
+        })
+        .with_close(move |close_info| {
+            // Sent before the on_error message, so the stream ends with the typed error
+            let _ = close_tx.send(Err(close_info.to_error()));
+        });
+        // The stream ends when the callbacks, and with them the senders, are dropped
+
+        let subscription = self.subscribe(query, variables, callbacks).await?;
+        Ok(SubscriptionStream { subscription, rx, done: false })
+    }
+
+    /// Connect and subscribe on a background thread that owns a single-threaded Tokio runtime
+    ///
+    /// For applications without an async runtime; the callbacks are invoked on the
+    /// subscription's callback thread. Returns after `connection_ack` was received (waiting at most the connect timeout)
+    /// and the subscription was sent. The client is consumed; its connection is closed when the
+    /// returned handle is stopped or dropped. The token is not updated after the subscription
+    /// started.
+    ///
+    /// Errors:
+    /// - WebSocketError - The connection or handshake failed or timed out
+    /// - OperationFailed - The background thread or runtime could not be started
+    pub fn subscribe_blocking(
+        mut self,
+        query: String,
+        variables: HashMap<String, Value>,
+        callbacks: SubscriptionCallbacks,
+    ) -> Result<BlockingSubscription, WinCCError> {
+        let (ready_tx, ready_rx) = std::sync::mpsc::channel();
+        let (stop_tx, stop_rx) = oneshot::channel::<()>();
+
+        let thread = std::thread::Builder::new()
+            .name("winccua-subscription".to_string())
+            .spawn(move || {
+                let runtime = match tokio::runtime::Builder::new_current_thread().enable_all().build() {
+                    Ok(runtime) => runtime,
+                    Err(e) => {
+                        let _ = ready_tx.send(Err(WinCCError::OperationFailed(format!("Failed to create runtime: {}", e))));
+                        return;
+                    }
+                };
+                runtime.block_on(async move {
+                    let timeout = self.connect_timeout;
+                    if let Err(e) = self.connect_and_wait(timeout).await {
+                        let _ = ready_tx.send(Err(e));
+                        return;
+                    }
+                    let subscription = match self.subscribe(query, variables, callbacks).await {
+                        Ok(subscription) => subscription,
+                        Err(e) => {
+                            let _ = ready_tx.send(Err(e));
+                            self.disconnect().await;
+                            return;
+                        }
+                    };
+                    let _ = ready_tx.send(Ok((
+                        subscription.id().to_string(),
+                        subscription.operation_name().map(str::to_string),
+                    )));
+
+                    let mut state = self.state();
+                    tokio::select! {
+                        _ = stop_rx => {
+                            if let Err(e) = subscription.unsubscribe().await {
+                                log::debug!("Unsubscribe on stop failed: {}", e);
+                            }
+                        }
+                        _ = state.wait_for(|s| *s == ConnectionState::Disconnected) => {
+                            log::debug!("Connection of blocking subscription closed");
+                        }
+                    }
+                    self.disconnect().await;
+                });
+            })
+            .map_err(|e| WinCCError::OperationFailed(format!("Failed to start subscription thread: {}", e)))?;
+
+        match ready_rx.recv() {
+            Ok(Ok((id, operation_name))) => Ok(BlockingSubscription {
+                id,
+                operation_name,
+                stop_tx: Some(stop_tx),
+                thread: Some(thread),
+            }),
+            Ok(Err(e)) => {
+                let _ = thread.join();
+                Err(e)
+            }
+            Err(_) => {
+                let _ = thread.join();
+                Err(WinCCError::OperationFailed("Subscription thread ended unexpectedly".to_string()))
+            }
+        }
+    }
+
+    /// Stop all subscriptions of this connection and wait until they are stopped (at most 5 seconds)
+    ///
+    /// Sends `Complete` for every active subscription, as `Subscription::unsubscribe` does for
+    /// one; when this returns Ok, no further callbacks are invoked except `on_complete`, which
+    /// is called once per subscription. Existing `Subscription` handles become inactive. The
+    /// connection stays open for new subscriptions.
+    ///
+    /// Errors:
+    /// - WebSocketError - The connection is closed or the unsubscribe was not confirmed in time
+    pub async fn unsubscribe_all(&self) -> Result<(), WinCCError> {
+        self.unsubscribe_all_with_timeout(DEFAULT_UNSUBSCRIBE_TIMEOUT).await
+    }
+
+    /// Stop all subscriptions and wait at most `timeout` until they are stopped
+    pub async fn unsubscribe_all_with_timeout(&self, timeout: Duration) -> Result<(), WinCCError> {
+        let tx = self.command_tx.as_ref()
+            .ok_or_else(|| WinCCError::WebSocketError("WebSocket not connected".to_string()))?;
+        let (done_tx, done_rx) = oneshot::channel();
+        tx.send(WSCommand::UnsubscribeAll { done: done_tx })
+            .await
+            .map_err(|_| WinCCError::WebSocketError("WebSocket not connected".to_string()))?;
+
+        match tokio::time::timeout(timeout, done_rx).await {
+            Ok(Ok(result)) => result.map_err(WinCCError::WebSocketError),
+            Ok(Err(_)) => Err(WinCCError::WebSocketError("WebSocket closed before unsubscribe was confirmed".to_string())),
+            Err(_) => Err(WinCCError::WebSocketError("Timed out waiting for unsubscribe".to_string())),
+        }
+    }
+
+    /// Use a new token for the connection, e.g. after a session extension
+    ///
+    /// If connected and the token changed, the client reconnects with the new token and
+    /// resubscribes all active subscriptions with their existing IDs, so `Subscription`
+    /// handles and callbacks stay valid. Notifications sent by the server while reconnecting
+    /// are lost.
     pub fn update_token(&self, token: String) {
-        *self.token.lock().unwrap() = token.clone();
-        
-        if let Some(tx) = &self.command_tx {
-            let _ = tx.try_send(WSCommand::UpdateToken { token });
+        self.token_updater().update(token);
+    }
+
+    /// Handle that allows updating the token from another thread
+    pub(crate) fn token_updater(&self) -> TokenUpdater {
+        TokenUpdater {
+            token: self.token.clone(),
         }
     }
 
@@ -387,4 +1843,129 @@ impl GraphQLWSClient {
 
         self.command_tx = None;
     }
-}
\ No newline at end of file
+}
+
+impl Drop for GraphQLWSClient {
+    /// Stops the connection task if the client is dropped without awaiting `disconnect`
+    ///
+    /// The task is asked to close the connection as `disconnect` does, without waiting for
+    /// it; if it cannot take the request (command queue full or task already gone), it is
+    /// aborted, closing the socket without a close frame.
+    fn drop(&mut self) {
+        if let Some(handle) = self.handle.take() {
+            let requested = self
+                .command_tx
+                .as_ref()
+                .is_some_and(|tx| tx.try_send(WSCommand::Disconnect).is_ok());
+            if !requested {
+                handle.abort();
+            }
+        }
+    }
+}
+
+/// Check that a subscription URL is a valid `ws://` or `wss://` URL
+pub(crate) fn validate_ws_url(url: &str) -> Result<(), WinCCError> {
+    match url::Url::parse(url) {
+        Ok(parsed) if matches!(parsed.scheme(), "ws" | "wss") && parsed.has_host() => Ok(()),
+        Ok(_) => Err(WinCCError::InvalidParameter(format!("ws_url must use ws:// or wss://, got {}", url))),
+        Err(e) => Err(WinCCError::InvalidParameter(format!("Invalid ws_url {}: {}", url, e))),
+    }
+}
+
+/// Completes when the idle timeout has elapsed since `last_received`, never if it is disabled
+async fn idle_deadline(idle_timeout: Option<Duration>, last_received: Instant) {
+    match idle_timeout {
+        Some(timeout) => tokio::time::sleep_until(tokio::time::Instant::from_std(last_received + timeout)).await,
+        None => std::future::pending().await,
+    }
+}
+
+/// Proxy URL from the environment for the given target host, honoring `NO_PROXY`
+fn proxy_from_env(secure: bool, host: &str) -> Option<String> {
+    let var = |names: &[&str]| {
+        names.iter()
+            .filter_map(|name| std::env::var(name).ok())
+            .find(|value| !value.is_empty())
+    };
+
+    if let Some(no_proxy) = var(&["NO_PROXY", "no_proxy"]) {
+        let bypass = no_proxy.split(',').map(str::trim).any(|entry| {
+            let entry = entry.trim_start_matches('.');
+            entry == "*" || (!entry.is_empty() && (host == entry || host.ends_with(&format!(".{}", entry))))
+        });
+        if bypass {
+            return None;
+        }
+    }
+
+    if secure {
+        var(&["HTTPS_PROXY", "https_proxy", "ALL_PROXY", "all_proxy"])
+    } else {
+        var(&["HTTP_PROXY", "http_proxy", "ALL_PROXY", "all_proxy"])
+    }
+}
+
+/// Proxy URL without credentials, for logging
+fn redact_proxy_url(proxy_url: &str) -> String {
+    match url::Url::parse(proxy_url) {
+        Ok(mut url) => {
+            let _ = url.set_username("");
+            let _ = url.set_password(None);
+            url.to_string()
+        }
+        Err(_) => "<invalid proxy url>".to_string(),
+    }
+}
+
+/// Open a TCP tunnel to `host:port` through an HTTP proxy using `CONNECT`
+async fn connect_via_proxy(proxy_url: &str, host: &str, port: u16) -> Result<TcpStream, String> {
+    use base64::Engine;
+
+    let proxy = url::Url::parse(proxy_url).map_err(|e| format!("Invalid proxy URL: {}", e))?;
+    if proxy.scheme() != "http" {
+        return Err(format!("Unsupported proxy scheme for WebSocket connections: {}", proxy.scheme()));
+    }
+    let proxy_host = proxy.host_str().ok_or_else(|| "Proxy URL has no host".to_string())?;
+    let proxy_port = proxy.port_or_known_default().unwrap_or(8080);
+
+    let mut stream = TcpStream::connect((proxy_host, proxy_port))
+        .await
+        .map_err(|e| format!("Proxy connection failed: {}", e))?;
+
+    let mut connect = format!("CONNECT {host}:{port} HTTP/1.1\r\nHost: {host}:{port}\r\n");
+    if !proxy.username().is_empty() {
+        let decode = |value: &str| percent_encoding::percent_decode_str(value).decode_utf8_lossy().into_owned();
+        let username = decode(proxy.username());
+        let password = decode(proxy.password().unwrap_or_default());
+        let credentials = base64::engine::general_purpose::STANDARD.encode(format!("{}:{}", username, password));
+        connect.push_str(&format!("Proxy-Authorization: Basic {}\r\n", credentials));
+    }
+    connect.push_str("\r\n");
+    stream.write_all(connect.as_bytes())
+        .await
+        .map_err(|e| format!("Proxy connection failed: {}", e))?;
+
+    // Read the proxy response header byte by byte so no tunneled data is consumed
+    let mut response = Vec::new();
+    let mut byte = [0u8; 1];
+    while !response.ends_with(b"\r\n\r\n") {
+        if response.len() > 8192 {
+            return Err("Proxy response header too large".to_string());
+        }
+        let n = stream.read(&mut byte)
+            .await
+            .map_err(|e| format!("Proxy connection failed: {}", e))?;
+        if n == 0 {
+            return Err("Proxy closed the connection".to_string());
+        }
+        response.push(byte[0]);
+    }
+
+    let response = String::from_utf8_lossy(&response);
+    let status_line = response.lines().next().unwrap_or_default();
+    match status_line.split_whitespace().nth(1) {
+        Some(code) if code.starts_with('2') => Ok(stream),
+        _ => Err(format!("Proxy CONNECT failed: {}", status_line)),
+    }
+}
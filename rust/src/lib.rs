@@ -10,17 +10,28 @@
 //! - Comprehensive error handling
 //! - All WinCC Unified API endpoints
 
+pub mod cancel;
 pub mod client;
 pub mod error;
+pub mod filter;
 pub mod graphql;
 pub mod types;
 pub mod graphql_ws;
+pub mod metrics;
+pub mod retry;
+pub mod transport;
 
-pub use client::WinCCUnifiedClient;
-pub use error::{WinCCError, WinCCResult};
+pub use cancel::CancelToken;
+pub use client::{BatchRequest, LoggedAlarmsPages, LoggedTagValuesPages, SessionGuard, WinCCUnifiedClient, WinCCUnifiedClientBuilder};
+pub use error::{GraphQLError, WinCCError, WinCCResult};
+pub use filter::AlarmFilterBuilder;
 pub use types::*;
-pub use graphql_ws::{GraphQLWSClient, SubscriptionCallbacks, Subscription};
+pub use graphql_ws::{BlockingSubscription, CloseInfo, ConnectionState, GraphQLWSClient, OverflowPolicy, SubscriptionCallbacks, TypedSubscriptionCallbacks, Subscription, SubscriptionStream, WsProtocol, DEFAULT_USER_AGENT};
 pub use graphql::subscriptions;
+pub use metrics::Metrics;
+pub use retry::RetryPolicy;
+pub use transport::{MockTransport, Transport};
 
 // Re-export common types for convenience
-pub use serde_json::Value;
\ No newline at end of file
+pub use serde_json::Value;
+pub use url::Url;
\ No newline at end of file
@@ -0,0 +1,64 @@
+//! Observability hooks for GraphQL queries and mutations
+
+use crate::error::WinCCError;
+use std::time::Duration;
+
+/// Receives an event for every GraphQL query and mutation sent by `WinCCUnifiedClient`
+///
+/// Set with `WinCCUnifiedClientBuilder::metrics`, e.g. to count requests and record their
+/// latency in a Prometheus exporter. Without it no events are produced. All methods have
+/// empty default implementations, so an implementation only overrides what it records.
+///
+/// Each operation produces `on_request` and then either `on_response` or `on_error`.
+/// The latency covers the complete operation, including retries. A response with GraphQL
+/// errors is reported to `on_error`. An array-batched request is reported once, with
+/// the operation name `batch`, by its HTTP outcome; GraphQL errors of the individual
+/// operations are not reported. Subscriptions are not reported.
+///
+/// The hooks are called on the thread that sends the request and should return quickly.
+///
+/// ```
+/// use std::sync::atomic::{AtomicUsize, Ordering};
+/// use std::time::Duration;
+/// use winccua_graphql_client::{Metrics, WinCCError};
+///
+/// #[derive(Default)]
+/// struct Counters {
+///     requests: AtomicUsize,
+///     errors: AtomicUsize,
+/// }
+///
+/// impl Metrics for Counters {
+///     fn on_request(&self, _operation: &str) {
+///         self.requests.fetch_add(1, Ordering::Relaxed);
+///     }
+///
+///     fn on_response(&self, operation: &str, duration: Duration, status: u16) {
+///         println!("{} took {:?} (HTTP {})", operation, duration, status);
+///     }
+///
+///     fn on_error(&self, _operation: &str, _error: &WinCCError) {
+///         self.errors.fetch_add(1, Ordering::Relaxed);
+///     }
+/// }
+/// ```
+pub trait Metrics: Send + Sync {
+    /// An operation is about to be sent; `operation` is the GraphQL operation name
+    /// (e.g. `TagValues`) or `anonymous`
+    fn on_request(&self, operation: &str) {
+        let _ = operation;
+    }
+
+    /// An operation completed without errors after `duration`
+    ///
+    /// `status` is the HTTP status of the response; custom transports report 200.
+    fn on_response(&self, operation: &str, duration: Duration, status: u16) {
+        let _ = (operation, duration, status);
+    }
+
+    /// An operation failed: the request could not be sent, the server answered with an
+    /// HTTP error status (see `WinCCError::status`), or the response carried GraphQL errors
+    fn on_error(&self, operation: &str, error: &WinCCError) {
+        let _ = (operation, error);
+    }
+}
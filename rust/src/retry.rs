@@ -0,0 +1,136 @@
+//! Retry behavior for transient HTTP failures
+
+use crate::error::WinCCError;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+/// Retry policy for GraphQL HTTP requests
+///
+/// Requests are retried on connection errors, timeouts, HTTP 5xx responses and
+/// HTTP 429 (rate limited). Other HTTP 4xx responses and GraphQL errors are never
+/// retried. The delay before retry `n` is `base_delay * 2^(n-1)`, capped at
+/// `max_delay`, with random jitter of up to half the delay subtracted. A
+/// `Retry-After` delay sent with a 429 response is used instead when present; if it
+/// exceeds `max_delay`, the request is not retried and the `RateLimited` error is
+/// returned, so the caller can reschedule it.
+///
+/// ```
+/// use std::time::Duration;
+/// use winccua_graphql_client::RetryPolicy;
+///
+/// let policy = RetryPolicy::new(4)
+///     .with_base_delay(Duration::from_millis(500))
+///     .with_max_delay(Duration::from_secs(10))
+///     .retry_mutations(false);
+/// assert_eq!(policy.max_attempts(), 4);
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_attempts: u32,
+    base_delay: Duration,
+    max_delay: Duration,
+    retry_mutations: bool,
+}
+
+impl Default for RetryPolicy {
+    /// 3 attempts, 200ms base delay, 5s maximum delay, mutations are retried
+    fn default() -> Self {
+        Self {
+            max_attempts: 3,
+            base_delay: Duration::from_millis(200),
+            max_delay: Duration::from_secs(5),
+            retry_mutations: true,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Create a policy with the given total number of attempts (including the first one)
+    pub fn new(max_attempts: u32) -> Self {
+        Self {
+            max_attempts: max_attempts.max(1),
+            ..Self::default()
+        }
+    }
+
+    /// Set the delay before the first retry
+    pub fn with_base_delay(mut self, delay: Duration) -> Self {
+        self.base_delay = delay;
+        self
+    }
+
+    /// Set the upper bound for the delay between attempts
+    pub fn with_max_delay(mut self, delay: Duration) -> Self {
+        self.max_delay = delay;
+        self
+    }
+
+    /// Whether mutations are retried (default true)
+    ///
+    /// Disable this if a repeated write or acknowledgement is not acceptable when
+    /// the first attempt reached the server but the response was lost.
+    pub fn retry_mutations(mut self, retry: bool) -> Self {
+        self.retry_mutations = retry;
+        self
+    }
+
+    /// Total number of attempts, including the first one
+    pub fn max_attempts(&self) -> u32 {
+        self.max_attempts
+    }
+
+    /// Number of attempts allowed for an operation
+    pub(crate) fn attempts_for(&self, is_mutation: bool) -> u32 {
+        if is_mutation && !self.retry_mutations {
+            1
+        } else {
+            self.max_attempts
+        }
+    }
+
+    /// Delay before retrying after `error` as retry number `retry` (starting at 1), or None if
+    /// the server asks to wait longer than `max_delay`
+    pub(crate) fn retry_delay(&self, retry: u32, error: &WinCCError) -> Option<Duration> {
+        match error.retry_after() {
+            Some(delay) if delay > self.max_delay => None,
+            Some(delay) => Some(delay),
+            None => Some(self.delay(retry)),
+        }
+    }
+
+    /// Delay before retry number `retry` (starting at 1)
+    pub(crate) fn delay(&self, retry: u32) -> Duration {
+        let factor = 2u32.saturating_pow(retry.saturating_sub(1));
+        let delay = self.base_delay.saturating_mul(factor).min(self.max_delay);
+        delay.mul_f64(1.0 - jitter() / 2.0)
+    }
+
+    /// Whether a failed attempt may succeed when repeated
+    pub(crate) fn is_retryable(error: &WinCCError) -> bool {
+        match error {
+            WinCCError::HttpError(e) => {
+                e.is_connect()
+                    || e.is_timeout()
+                    || e.status().is_some_and(|status| status.is_server_error())
+            }
+            WinCCError::ServerError { status, .. } => *status >= 500,
+            WinCCError::RateLimited { .. } => true,
+            _ => false,
+        }
+    }
+}
+
+/// Pseudo-random value in `[0, 1)` derived from the clock, sufficient to spread retries
+fn jitter() -> f64 {
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.subsec_nanos())
+        .unwrap_or(0);
+    // Scramble the low bits so consecutive calls do not produce similar values
+    let mixed = nanos.wrapping_mul(2_654_435_761) >> 8;
+    f64::from(mixed) / f64::from(1u32 << 24)
+}
+
+/// Whether a GraphQL document is a mutation
+pub(crate) fn is_mutation(query: &str) -> bool {
+    query.trim_start().starts_with("mutation")
+}
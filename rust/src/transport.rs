@@ -0,0 +1,117 @@
+//! Pluggable transport for GraphQL queries and mutations
+
+use crate::error::{WinCCError, WinCCResult};
+use serde_json::Value;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+
+/// Executes GraphQL operations for `WinCCUnifiedClient`
+///
+/// By default the client sends requests over HTTP with reqwest. A custom transport set
+/// with `WinCCUnifiedClientBuilder::transport` replaces the HTTP layer, e.g. to test code
+/// that uses the client without a live server (see `MockTransport`).
+///
+/// The client passes the operation name, the GraphQL document and its variables and expects
+/// the complete GraphQL response object (`{"data": ..., "errors": [...]}`); GraphQL errors in
+/// the response are handled by the client as for HTTP responses.
+///
+/// A custom transport replaces everything the client does on the HTTP level, so it is
+/// responsible for:
+/// - authentication: the session token (`token()`) and the `Authorization` header are not
+///   sent for it
+/// - custom headers set with `set_header` (they still apply to WebSocket connections)
+/// - retries (`retry_policy`) and timeouts (`request_timeout`)
+/// - array-batching: `batch` executes its operations one after another
+pub trait Transport: Send + Sync {
+    /// Execute one GraphQL operation and return the GraphQL response object
+    ///
+    /// `operation_name` is the name passed to `execute_named`, or otherwise the name of the
+    /// operation defined in `query`, as sent in the `operationName` field of HTTP requests.
+    fn execute(&self, operation_name: Option<&str>, query: &str, variables: &Value) -> WinCCResult<Value>;
+}
+
+/// Transport that returns queued responses instead of contacting a server
+///
+/// Responses are returned in the order they were queued, one per operation. Clones share
+/// the queue and the recorded requests, so a clone can be handed to the client and the
+/// original kept for assertions.
+///
+/// ```
+/// use serde_json::json;
+/// use winccua_graphql_client::{MockTransport, WinCCUnifiedClient};
+///
+/// let mock = MockTransport::new();
+/// mock.push_response(json!({
+///     "data": { "tagValues": [{ "name": "HMI_Tag_1", "value": { "value": 42 } }] }
+/// }));
+///
+/// let client = WinCCUnifiedClient::with_transport(mock.clone());
+/// let values = client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+/// assert_eq!(values[0].value.as_ref().and_then(|v| v.value.clone()), Some(json!(42)));
+/// assert_eq!(mock.requests()[0].1["names"], json!(["HMI_Tag_1"]));
+/// ```
+#[derive(Clone, Default)]
+pub struct MockTransport {
+    state: Arc<Mutex<MockState>>,
+}
+
+#[derive(Default)]
+struct MockState {
+    responses: VecDeque<WinCCResult<Value>>,
+    requests: Vec<(String, Value)>,
+    operation_names: Vec<Option<String>>,
+}
+
+impl MockTransport {
+    /// Create a mock without queued responses
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Queue a GraphQL response object for the next operation
+    pub fn push_response(&self, response: Value) {
+        self.state.lock().unwrap().responses.push_back(Ok(response));
+    }
+
+    /// Queue an error (e.g. a transport failure) for the next operation
+    pub fn push_error(&self, error: WinCCError) {
+        self.state.lock().unwrap().responses.push_back(Err(error));
+    }
+
+    /// Queue a GraphQL response object, builder style
+    pub fn with_response(self, response: Value) -> Self {
+        self.push_response(response);
+        self
+    }
+
+    /// Operations executed so far as (query, variables)
+    pub fn requests(&self) -> Vec<(String, Value)> {
+        self.state.lock().unwrap().requests.clone()
+    }
+
+    /// Operation names of the operations executed so far, in the order of `requests`
+    pub fn operation_names(&self) -> Vec<Option<String>> {
+        self.state.lock().unwrap().operation_names.clone()
+    }
+
+    /// Number of queued responses not yet consumed
+    pub fn pending_responses(&self) -> usize {
+        self.state.lock().unwrap().responses.len()
+    }
+}
+
+impl Transport for MockTransport {
+    /// Returns the next queued response
+    ///
+    /// Errors:
+    /// - OperationFailed - No response is queued
+    fn execute(&self, operation_name: Option<&str>, query: &str, variables: &Value) -> WinCCResult<Value> {
+        let mut state = self.state.lock().unwrap();
+        state.requests.push((query.to_string(), variables.clone()));
+        state.operation_names.push(operation_name.map(str::to_string));
+        state
+            .responses
+            .pop_front()
+            .unwrap_or_else(|| Err(WinCCError::OperationFailed("No mock response queued".to_string())))
+    }
+}
@@ -1,10 +1,109 @@
 //! Type definitions for WinCC Unified GraphQL API
 
+use crate::error::{WinCCError, WinCCResult};
+use chrono::{DateTime, Utc};
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use url::Url;
+
+/// Define a GraphQL enum that keeps values unknown to this client in an `Unknown` variant
+/// (or the variant named after `other`) instead of failing deserialization
+macro_rules! graphql_enum {
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)*
+        }
+    ) => {
+        graphql_enum! {
+            $(#[$meta])*
+            pub enum $name {
+                $($(#[$variant_meta])* $variant => $value,)*
+            }
+            other Unknown
+        }
+    };
+    (
+        $(#[$meta:meta])*
+        pub enum $name:ident {
+            $($(#[$variant_meta:meta])* $variant:ident => $value:literal,)*
+        }
+        other $other:ident
+    ) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum $name {
+            $($(#[$variant_meta])* $variant,)*
+            /// Value not known to this client version
+            $other(String),
+        }
+
+        impl $name {
+            /// GraphQL enum value
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $($name::$variant => $value,)*
+                    $name::$other(value) => value,
+                }
+            }
+        }
+
+        impl From<&str> for $name {
+            fn from(value: &str) -> Self {
+                match value {
+                    $($value => $name::$variant,)*
+                    other => $name::$other(other.to_string()),
+                }
+            }
+        }
+
+        impl std::fmt::Display for $name {
+            fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+
+        impl Serialize for $name {
+            fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.serialize_str(self.as_str())
+            }
+        }
+
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                String::deserialize(deserializer).map(|value| $name::from(value.as_str()))
+            }
+        }
+    };
+}
+
+/// Parse an ISO-8601 / RFC3339 timestamp as returned by WinCC Unified (e.g. `2023-12-31T23:59:59.999Z`)
+/// 
+/// Errors:
+/// - InvalidTimestamp - The string is not a valid RFC3339 timestamp
+pub fn parse_timestamp(timestamp: &str) -> WinCCResult<DateTime<Utc>> {
+    DateTime::parse_from_rfc3339(timestamp)
+        .map(|t| t.with_timezone(&Utc))
+        .map_err(|e| WinCCError::InvalidTimestamp(format!("{}: {}", timestamp, e)))
+}
+
+/// Parse an optional timestamp field, returning Ok(None) if the field is not set
+fn parse_optional_timestamp(timestamp: &Option<String>) -> WinCCResult<Option<DateTime<Utc>>> {
+    timestamp.as_deref().map(parse_timestamp).transpose()
+}
+
+/// Numeric value of a `Variant` threshold field, also if the server sends it as a string
+fn variant_f64(value: &Option<Value>) -> Option<f64> {
+    match value.as_ref()? {
+        Value::Number(n) => n.as_f64(),
+        Value::String(s) => s.trim().parse().ok(),
+        _ => None,
+    }
+}
 
 /// Session information containing user details and authentication token
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Session {
     pub user: Option<User>,
     pub token: Option<String>,
@@ -12,8 +111,26 @@ pub struct Session {
     pub error: Option<ErrorInfo>,
 }
 
+impl Session {
+    /// Session expiry as UTC timestamp
+    pub fn expires_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.expires)
+    }
+}
+
+/// How the token of a client was obtained, see `WinCCUnifiedClient::auth_method`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AuthMethod {
+    /// `login` with username and password
+    Password,
+    /// `login_swac` (or `swac_login_flow`) with a signed SWAC claim
+    Swac,
+    /// Token set directly with `set_token`, the builder `token` or `with_token`
+    Preset,
+}
+
 /// User information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct User {
     pub id: Option<String>,
     pub name: Option<String>,
@@ -26,29 +143,64 @@ pub struct User {
 }
 
 /// User group information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct UserGroup {
     pub id: Option<String>,
     pub name: Option<String>,
 }
 
 /// Error information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ErrorInfo {
     pub code: Option<String>,
     pub description: Option<String>,
 }
 
+impl ErrorInfo {
+    /// Whether the error reports a failure: a code other than "0"
+    pub fn is_error(&self) -> bool {
+        self.code.as_deref().is_some_and(|code| code != "0")
+    }
+
+    /// Error message for the entry `name`, e.g. `HMI_Tag_2: Cannot resolve provided name (code 2)`
+    pub fn message_for(&self, name: &str) -> String {
+        format!(
+            "{}: {} (code {})",
+            name,
+            self.description.as_deref().unwrap_or("Unknown error"),
+            self.code.as_deref().unwrap_or_default()
+        )
+    }
+}
+
 /// Nonce for SWAC authentication
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Nonce {
     pub value: Option<String>,
     #[serde(rename = "validFor")]
     pub valid_for: Option<i32>,
 }
 
+/// What the application needs to send the user to the identity provider in `swac_login_flow`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SwacChallenge {
+    /// Identity provider (UMC) URL to redirect the user to
+    pub identity_provider_url: Url,
+    /// Nonce to pass to the identity provider
+    pub nonce: String,
+    /// Validity of the nonce in seconds, as reported by the server
+    pub valid_for: Option<i32>,
+}
+
+/// Claim and signed claim returned by the identity provider after a SWAC login
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct SwacClaim {
+    pub claim: String,
+    pub signed_claim: String,
+}
+
 /// Tag value result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TagValueResult {
     pub name: Option<String>,
     pub value: Option<TagValue>,
@@ -56,22 +208,236 @@ pub struct TagValueResult {
 }
 
 /// Tag value with timestamp and quality
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TagValue {
     pub value: Option<Value>,
     pub timestamp: Option<String>,
     pub quality: Option<Quality>,
 }
 
+impl TagValue {
+    /// Value timestamp as UTC timestamp
+    pub fn timestamp_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.timestamp)
+    }
+
+    /// Value as integer, if it is a JSON integer
+    pub fn as_i64(&self) -> Option<i64> {
+        self.value.as_ref()?.as_i64()
+    }
+
+    /// Value as unsigned integer, if it is a non-negative JSON integer
+    pub fn as_u64(&self) -> Option<u64> {
+        self.value.as_ref()?.as_u64()
+    }
+
+    /// Value of an `Int64`/`UInt64` tag without loss of precision
+    ///
+    /// Covers the whole range of both types, where `as_f64` rounds above 2^53. JSON
+    /// integers are read as they were received; strings holding an integer are parsed too.
+    /// Returns None for floats and other values.
+    pub fn as_i128(&self) -> Option<i128> {
+        match self.value.as_ref()? {
+            Value::Number(n) => n.as_i64().map(i128::from).or_else(|| n.as_u64().map(i128::from)),
+            Value::String(s) => s.trim().parse().ok(),
+            _ => None,
+        }
+    }
+
+    /// Value as floating point number, if it is a JSON number
+    pub fn as_f64(&self) -> Option<f64> {
+        self.value.as_ref()?.as_f64()
+    }
+
+    /// Value as boolean, if it is a JSON boolean
+    pub fn as_bool(&self) -> Option<bool> {
+        self.value.as_ref()?.as_bool()
+    }
+
+    /// Value as string slice, if it is a JSON string
+    pub fn as_str(&self) -> Option<&str> {
+        self.value.as_ref()?.as_str()
+    }
+
+    /// Value as array, if it is a JSON array
+    pub fn as_array(&self) -> Option<&Vec<Value>> {
+        self.value.as_ref()?.as_array()
+    }
+
+    /// Deserialize the value into any type, returns None if it does not match
+    pub fn get<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_value(self.value.clone()?).ok()
+    }
+
+    /// Convert the value according to the WinCC data type reported by browse (e.g. `Int32`, `Real`, `Bool`).
+    /// 
+    /// Strings holding numbers or booleans are parsed, so `"123"` for an Int32 tag becomes `123`.
+    /// Same conversion as `coerce_to`, but returns None if the tag has no value or the value
+    /// cannot be converted.
+    pub fn coerce(&self, data_type: &str) -> Option<Value> {
+        self.coerce_to(data_type).ok()
+    }
+
+    /// Normalize the value to the canonical JSON representation of a WinCC data type
+    /// 
+    /// Integer types (`SInt`..`ULInt`, `Int8`..`UInt64`, `Byte`, `Word`, `DWord`, `LWord`)
+    /// become JSON integers and are range checked, accepting integral floats, numeric
+    /// strings and booleans (0/1); `Real`/`LReal`/`Float`/`Double` become floats; `Bool` becomes a boolean (from numbers or `"true"`/`"false"`/`"1"`/`"0"`);
+    /// `String`/`WString`/`Char`/`WChar` become strings. Other data types are returned unchanged.
+    /// 
+    /// Errors:
+    /// - TagError - The tag has no value, or the value cannot be represented in the data type
+    pub fn coerce_to(&self, data_type: &str) -> WinCCResult<Value> {
+        let value = self.value.as_ref()
+            .ok_or_else(|| WinCCError::TagError(format!("No value to convert to {}", data_type)))?;
+        let cannot_convert = || WinCCError::TagError(format!("Cannot convert {} to {}", value, data_type));
+        let lower = data_type.to_ascii_lowercase();
+        
+        if let Some((min, max)) = integer_range(&lower) {
+            let integer = match value {
+                Value::Number(n) => n.as_i64().map(i128::from)
+                    .or_else(|| n.as_u64().map(i128::from))
+                    .or_else(|| n.as_f64().filter(|f| f.fract() == 0.0 && f.abs() < 1e19).map(|f| f as i128)),
+                Value::Bool(b) => Some(i128::from(*b)),
+                Value::String(s) => s.trim().parse::<i128>().ok(),
+                _ => None,
+            };
+            return match integer {
+                Some(integer) if (min..=max).contains(&integer) => match u64::try_from(integer) {
+                    Ok(unsigned) => Ok(Value::from(unsigned)),
+                    Err(_) => Ok(Value::from(integer as i64)),
+                },
+                _ => Err(cannot_convert()),
+            };
+        }
+        
+        match lower.as_str() {
+            "bool" | "boolean" => match value {
+                Value::Bool(_) => Ok(value.clone()),
+                Value::Number(n) => n.as_f64().map(|f| Value::Bool(f != 0.0)).ok_or_else(cannot_convert),
+                Value::String(s) => match s.trim().to_ascii_lowercase().as_str() {
+                    "true" | "1" => Ok(Value::Bool(true)),
+                    "false" | "0" => Ok(Value::Bool(false)),
+                    _ => Err(cannot_convert()),
+                },
+                _ => Err(cannot_convert()),
+            },
+            "real" | "lreal" | "float" | "double" => match value {
+                Value::Number(n) => n.as_f64().map(Value::from).ok_or_else(cannot_convert),
+                Value::String(s) => s.trim().parse::<f64>().ok()
+                    .filter(|f| f.is_finite())
+                    .map(Value::from)
+                    .ok_or_else(cannot_convert),
+                _ => Err(cannot_convert()),
+            },
+            "string" | "wstring" | "char" | "wchar" => match value {
+                Value::String(_) => Ok(value.clone()),
+                Value::Number(n) => Ok(Value::String(n.to_string())),
+                Value::Bool(b) => Ok(Value::String(b.to_string())),
+                _ => Err(cannot_convert()),
+            },
+            _ => Ok(value.clone()),
+        }
+    }
+}
+
+/// Value range of a WinCC integer data type (lower case name)
+fn integer_range(data_type: &str) -> Option<(i128, i128)> {
+    let range = match data_type {
+        "sint" | "int8" => (i8::MIN.into(), i8::MAX.into()),
+        "usint" | "uint8" | "byte" => (0, u8::MAX.into()),
+        "int" | "int16" => (i16::MIN.into(), i16::MAX.into()),
+        "uint" | "uint16" | "word" => (0, u16::MAX.into()),
+        "dint" | "int32" => (i32::MIN.into(), i32::MAX.into()),
+        "udint" | "uint32" | "dword" => (0, u32::MAX.into()),
+        "lint" | "int64" => (i64::MIN.into(), i64::MAX.into()),
+        "ulint" | "uint64" | "lword" => (0, u64::MAX.into()),
+        _ => return None,
+    };
+    Some(range)
+}
+
+graphql_enum! {
+    /// Main quality of a value (MainQuality)
+    pub enum QualityCode {
+        /// The value is not useful for reasons indicated by the sub status
+        Bad => "BAD",
+        /// The quality is less than normal, but the value may still be useful
+        Uncertain => "UNCERTAIN",
+        GoodNonCascade => "GOOD_NON_CASCADE",
+        GoodCascade => "GOOD_CASCADE",
+    }
+}
+
+graphql_enum! {
+    /// Quality sub status (QualitySubStatus)
+    pub enum QualitySubStatus {
+        NonSpecific => "NON_SPECIFIC",
+        ConfigurationError => "CONFIGURATION_ERROR",
+        NotConnected => "NOT_CONNECTED",
+        SensorFailure => "SENSOR_FAILURE",
+        DeviceFailure => "DEVICE_FAILURE",
+        NoCommunicationWithLastUsableValue => "NO_COMMUNICATION_WITH_LAST_USABLE_VALUE",
+        NoCommunicationNoUsableValue => "NO_COMMUNICATION_NO_USABLE_VALUE",
+        OutOfService => "OUT_OF_SERVICE",
+        LastUsableValue => "LAST_USABLE_VALUE",
+        SubstituteValue => "SUBSTITUTE_VALUE",
+        InitialValue => "INITIAL_VALUE",
+        SensorConversion => "SENSOR_CONVERSION",
+        RangeViolation => "RANGE_VIOLATION",
+        SubNormal => "SUB_NORMAL",
+        ConfigError => "CONFIG_ERROR",
+        SimulatedValue => "SIMULATED_VALUE",
+        SensorCalibration => "SENSOR_CALIBRATION",
+        UpdateEvent => "UPDATE_EVENT",
+        AdvisoryAlarm => "ADVISORY_ALARM",
+        CriticalAlarm => "CRITICAL_ALARM",
+        UnackUpdateEvent => "UNACK_UPDATE_EVENT",
+        UnackAdvisoryAlarm => "UNACK_ADVISORY_ALARM",
+        UnackCriticalAlarm => "UNACK_CRITICAL_ALARM",
+        InitFailsafe => "INIT_FAILSAFE",
+        MaintenanceRequired => "MAINTENANCE_REQUIRED",
+        InitAcked => "INIT_ACKED",
+        InitReq => "INITREQ",
+        NotInvited => "NOT_INVITED",
+        DoNotSelect => "DO_NOT_SELECT",
+        LocalOverride => "LOCAL_OVERRIDE",
+    }
+}
+
+graphql_enum! {
+    /// Limit status of a value (QualityLimit)
+    pub enum LimitStatus {
+        Ok => "OK",
+        LowLimitViolation => "LOW_LIMIT_VIOLATION",
+        HighLimitViolation => "HIGH_LIMIT_VIOLATION",
+        Constant => "CONSTANT",
+    }
+}
+
+graphql_enum! {
+    /// Extended quality sub status (QualityExtendedSubStatus)
+    pub enum ExtendedSubStatus {
+        NonSpecific => "NON_SPECIFIC",
+        AggregatedValue => "AGGREGATED_VALUE",
+        UnusableValue => "UNUSABLE_VALUE",
+        Disabled => "DISABLED",
+        ManualInput => "MANUAL_INPUT",
+        CorrectedValue => "CORRECTED_VALUE",
+        LastUsableValue => "LAST_USABLE_VALUE",
+        InitialValue => "INITIAL_VALUE",
+    }
+}
+
 /// Quality information for tag values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct Quality {
-    pub quality: Option<String>,
+    pub quality: Option<QualityCode>,
     #[serde(rename = "subStatus")]
-    pub sub_status: Option<String>,
-    pub limit: Option<String>,
+    pub sub_status: Option<QualitySubStatus>,
+    pub limit: Option<LimitStatus>,
     #[serde(rename = "extendedSubStatus")]
-    pub extended_sub_status: Option<String>,
+    pub extended_sub_status: Option<ExtendedSubStatus>,
     #[serde(rename = "sourceQuality")]
     pub source_quality: Option<bool>,
     #[serde(rename = "sourceTime")]
@@ -80,44 +446,194 @@ pub struct Quality {
     pub time_corrected: Option<bool>,
 }
 
+impl Quality {
+    /// True if the main quality is GOOD_CASCADE or GOOD_NON_CASCADE
+    pub fn is_good(&self) -> bool {
+        matches!(self.quality, Some(QualityCode::GoodCascade) | Some(QualityCode::GoodNonCascade))
+    }
+
+    /// True if the main quality is BAD
+    pub fn is_bad(&self) -> bool {
+        self.quality == Some(QualityCode::Bad)
+    }
+
+    /// True if the main quality is UNCERTAIN
+    pub fn is_uncertain(&self) -> bool {
+        self.quality == Some(QualityCode::Uncertain)
+    }
+}
+
+/// Flags indicating invalid parts of an alarm
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default, Serialize, Deserialize)]
+pub struct InvalidFlags {
+    #[serde(rename = "invalidConfiguration")]
+    pub invalid_configuration: Option<bool>,
+    #[serde(rename = "invalidTimestamp")]
+    pub invalid_timestamp: Option<bool>,
+    #[serde(rename = "invalidAlarmParameter")]
+    pub invalid_alarm_parameter: Option<bool>,
+    #[serde(rename = "invalidEventText")]
+    pub invalid_event_text: Option<bool>,
+}
+
+impl InvalidFlags {
+    /// True if any of the flags is set
+    pub fn any(&self) -> bool {
+        [
+            self.invalid_configuration,
+            self.invalid_timestamp,
+            self.invalid_alarm_parameter,
+            self.invalid_event_text,
+        ]
+        .contains(&Some(true))
+    }
+}
+
 /// Input for writing tag values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 
+/// `timestamp` and `quality` are only sent when set. Unset fields fall back to the
+/// mutation-level timestamp and quality passed to `write_tag_values`.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TagValueInput {
     pub name: String,
     pub value: Value,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub timestamp: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub quality: Option<QualityInput>,
 }
 
+impl TagValueInput {
+    /// Create an input for the given tag name and value without timestamp and quality
+    pub fn new(name: &str, value: impl Into<Value>) -> Self {
+        Self {
+            name: name.to_string(),
+            value: value.into(),
+            timestamp: None,
+            quality: None,
+        }
+    }
+
+    /// Set the quality for this tag, overriding the mutation-level fallback quality
+    pub fn with_quality(mut self, quality: QualityInput) -> Self {
+        self.quality = Some(quality);
+        self
+    }
+
+    /// Set the timestamp (ISO 8601) for this tag, overriding the mutation-level fallback timestamp
+    pub fn with_timestamp(mut self, timestamp: &str) -> Self {
+        self.timestamp = Some(timestamp.to_string());
+        self
+    }
+}
+
 /// Quality input for writing tag values
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct QualityInput {
     pub quality: String,
-    #[serde(rename = "subStatus")]
+    #[serde(rename = "subStatus", skip_serializing_if = "Option::is_none")]
     pub sub_status: Option<String>,
 }
 
+impl QualityInput {
+    /// Create a quality input without sub status, e.g. `QualityInput::new("GOOD_CASCADE")`
+    pub fn new(quality: &str) -> Self {
+        Self {
+            quality: quality.to_string(),
+            sub_status: None,
+        }
+    }
+
+    /// Set the quality sub status
+    pub fn with_sub_status(mut self, sub_status: &str) -> Self {
+        self.sub_status = Some(sub_status.to_string());
+        self
+    }
+}
+
 /// Result of tag write operation
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct WriteTagValuesResult {
     pub name: Option<String>,
     pub error: Option<ErrorInfo>,
 }
 
+graphql_enum! {
+    /// Type of a browsed object (ObjectTypesEnum)
+    pub enum ObjectType {
+        Tag => "TAG",
+        SimpleTag => "SIMPLETAG",
+        StructureTag => "STRUCTURETAG",
+        TagType => "TAGTYPE",
+        StructureTagType => "STRUCTURETAGTYPE",
+        SimpleTagType => "SIMPLETAGTYPE",
+        Alarm => "ALARM",
+        AlarmClass => "ALARMCLASS",
+        LoggingTag => "LOGGINGTAG",
+    }
+    other Other
+}
+
+impl ObjectType {
+    /// True for tag instances (TAG, SIMPLETAG, STRUCTURETAG)
+    pub fn is_tag(&self) -> bool {
+        matches!(self, ObjectType::Tag | ObjectType::SimpleTag | ObjectType::StructureTag)
+    }
+
+    /// True for alarms (ALARM)
+    pub fn is_alarm(&self) -> bool {
+        *self == ObjectType::Alarm
+    }
+}
+
 /// Browse result for tags, alarms, etc.
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct BrowseTagsResult {
     pub name: Option<String>,
     #[serde(rename = "displayName")]
     pub display_name: Option<String>,
     #[serde(rename = "objectType")]
-    pub object_type: Option<String>,
+    pub object_type: Option<ObjectType>,
     #[serde(rename = "dataType")]
     pub data_type: Option<String>,
 }
 
+impl BrowseTagsResult {
+    /// True if the object is a tag instance (TAG, SIMPLETAG, STRUCTURETAG)
+    pub fn is_tag(&self) -> bool {
+        self.object_type.as_ref().is_some_and(ObjectType::is_tag)
+    }
+
+    /// True if the object is an alarm
+    pub fn is_alarm(&self) -> bool {
+        self.object_type.as_ref().is_some_and(ObjectType::is_alarm)
+    }
+}
+
+/// Alarm class configured on the server, as listed by `WinCCUnifiedClient::get_alarm_classes`
+///
+/// The GraphQL API exposes alarm classes only through `browse`, so only the name and
+/// display name are known. Class details show up on the alarms themselves
+/// (`alarm_class_name`, `alarm_class_id`, `alarm_class_symbol`, `text_color`, `back_color`,
+/// `state_machine`).
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct AlarmClass {
+    /// Configured name, including the system prefix (e.g. `System1::Warning`)
+    pub name: String,
+    /// Display name in the requested language
+    #[serde(rename = "displayName")]
+    pub display_name: Option<String>,
+}
+
+impl AlarmClass {
+    /// Name without the system prefix (e.g. `Warning`)
+    pub fn short_name(&self) -> &str {
+        self.name.rsplit_once("::").map_or(&self.name, |(_, name)| name)
+    }
+}
+
 /// Logged tag values result
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoggedTagValuesResult {
     #[serde(rename = "loggingTagName")]
     pub logging_tag_name: Option<String>,
@@ -125,15 +641,164 @@ pub struct LoggedTagValuesResult {
     pub values: Option<Vec<LoggedValue>>,
 }
 
+/// Sorting mode for logged tag values
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum SortingMode {
+    #[default]
+    #[serde(rename = "TIME_ASC")]
+    TimeAsc,
+    #[serde(rename = "TIME_DESC")]
+    TimeDesc,
+}
+
+impl SortingMode {
+    /// GraphQL enum value (LoggedTagValuesSortingModeEnum)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SortingMode::TimeAsc => "TIME_ASC",
+            SortingMode::TimeDesc => "TIME_DESC",
+        }
+    }
+}
+
+/// Bounding values mode for logged tag values
+/// 
+/// Left and right refer to time, not to the position in the result: the left bounding value
+/// is the last value before the start time, the right one the first value after the end time.
+/// With `SortingMode::TimeDesc` the left bounding value is the last entry of the result.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+pub enum BoundingMode {
+    #[default]
+    #[serde(rename = "NO_BOUNDING_VALUES")]
+    None,
+    /// Last value before the start time
+    #[serde(rename = "LEFT_BOUNDING_VALUES")]
+    Left,
+    /// First value after the end time
+    #[serde(rename = "RIGHT_BOUNDING_VALUES")]
+    Right,
+    #[serde(rename = "LEFTRIGHT_BOUNDING_VALUES")]
+    LeftRight,
+}
+
+impl BoundingMode {
+    /// GraphQL enum value (LoggedTagValuesBoundingModeEnum)
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BoundingMode::None => "NO_BOUNDING_VALUES",
+            BoundingMode::Left => "LEFT_BOUNDING_VALUES",
+            BoundingMode::Right => "RIGHT_BOUNDING_VALUES",
+            BoundingMode::LeftRight => "LEFTRIGHT_BOUNDING_VALUES",
+        }
+    }
+}
+
 /// Individual logged value
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoggedValue {
     pub value: Option<TagValue>,
     pub flags: Option<Vec<String>>,
 }
 
+graphql_enum! {
+    /// User response an active alarm expects (AlarmUserResponse)
+    pub enum AlarmUserResponse {
+        /// No user response required or expected
+        None => "NONE",
+        /// The alarm expects an acknowledgement
+        Acknowledgement => "ACKNOWLEDGEMENT",
+        /// The alarm requires a reset to complete its state machine
+        Reset => "RESET",
+        /// The alarm expects an explicit (single) acknowledgement
+        SingleAcknowledgement => "SINGLE_ACKNOWLEDGEMENT",
+        /// The alarm requires an explicit (single) reset
+        SingleReset => "SINGLE_RESET",
+    }
+}
+
+impl AlarmUserResponse {
+    /// True if the alarm waits for an acknowledgement (`acknowledge_alarms`)
+    pub fn requires_acknowledgement(&self) -> bool {
+        matches!(self, AlarmUserResponse::Acknowledgement | AlarmUserResponse::SingleAcknowledgement)
+    }
+    
+    /// True if the alarm waits for a reset (`reset_alarms`)
+    pub fn requires_reset(&self) -> bool {
+        matches!(self, AlarmUserResponse::Reset | AlarmUserResponse::SingleReset)
+    }
+}
+
+/// Entry of the `alarmParameterValues` of an alarm
+/// 
+/// The schema declares the entries as `Variant`. Objects with `name`, `value`, `type`
+/// (or `dataType`) and `quality` are parsed into the fields; any other entry (e.g. a plain
+/// number or string, or an object that does not match this shape) is kept unchanged in `value`.
+/// Also used for `loopInAlarmParameterValues`.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct AlarmParameterValue {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<Value>,
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub data_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quality: Option<Quality>,
+}
+
+impl<'de> Deserialize<'de> for AlarmParameterValue {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        #[derive(Deserialize)]
+        struct Entry {
+            name: Option<String>,
+            value: Option<Value>,
+            #[serde(rename = "type", alias = "dataType")]
+            data_type: Option<String>,
+            quality: Option<Quality>,
+        }
+        
+        let raw = Value::deserialize(deserializer)?;
+        let entry = match &raw {
+            Value::Object(fields) if fields.contains_key("value") || fields.contains_key("name") => {
+                serde_json::from_value::<Entry>(raw.clone()).ok()
+            }
+            _ => None,
+        };
+        Ok(match entry {
+            Some(entry) => AlarmParameterValue {
+                name: entry.name,
+                value: entry.value,
+                data_type: entry.data_type,
+                quality: entry.quality,
+            },
+            None => AlarmParameterValue {
+                name: None,
+                value: Some(raw),
+                data_type: None,
+                quality: None,
+            },
+        })
+    }
+}
+
+/// Parse `loopInAlarmParameterValues`, a single Variant that holds either a list of parameters or one parameter
+fn deserialize_loop_in_alarm_parameters<'de, D: serde::Deserializer<'de>>(
+    deserializer: D,
+) -> Result<Option<Vec<AlarmParameterValue>>, D::Error> {
+    let values = match Option::<Value>::deserialize(deserializer)? {
+        None | Some(Value::Null) => return Ok(None),
+        Some(Value::Array(values)) => values,
+        Some(value) => vec![value],
+    };
+    values
+        .into_iter()
+        .map(|value| serde_json::from_value(value).map_err(serde::de::Error::custom))
+        .collect::<Result<Vec<_>, _>>()
+        .map(Some)
+}
+
 /// Active alarm information
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActiveAlarm {
     pub name: Option<String>,
     #[serde(rename = "instanceID")]
@@ -167,7 +832,7 @@ pub struct ActiveAlarm {
     pub state_machine: Option<String>,
     pub priority: Option<i32>,
     #[serde(rename = "alarmParameterValues")]
-    pub alarm_parameter_values: Option<Vec<Value>>,
+    pub alarm_parameter_values: Option<Vec<AlarmParameterValue>>,
     #[serde(rename = "alarmType")]
     pub alarm_type: Option<Vec<String>>,
     #[serde(rename = "eventText")]
@@ -215,7 +880,7 @@ pub struct ActiveAlarm {
     pub value_quality: Option<Quality>,
     pub quality: Option<Quality>,
     #[serde(rename = "invalidFlags")]
-    pub invalid_flags: Option<Value>,
+    pub invalid_flags: Option<InvalidFlags>,
     #[serde(rename = "deadBand")]
     pub dead_band: Option<Value>,
     pub producer: Option<String>,
@@ -228,15 +893,150 @@ pub struct ActiveAlarm {
     pub system_severity: Option<i32>,
     #[serde(rename = "loopInAlarm")]
     pub loop_in_alarm: Option<String>,
-    #[serde(rename = "loopInAlarmParameterValues")]
-    pub loop_in_alarm_parameter_values: Option<Value>,
+    #[serde(
+        rename = "loopInAlarmParameterValues",
+        default,
+        deserialize_with = "deserialize_loop_in_alarm_parameters"
+    )]
+    pub loop_in_alarm_parameter_values: Option<Vec<AlarmParameterValue>>,
     pub path: Option<String>,
     #[serde(rename = "userResponse")]
-    pub user_response: Option<String>,
+    pub user_response: Option<AlarmUserResponse>,
+}
+
+/// Set of ActiveAlarm fields requested from the server
+/// 
+/// Smaller field sets reduce the response size for large alarm lists. Fields that are
+/// not requested are `None` in the returned `ActiveAlarm`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum AlarmFieldSet {
+    /// Name, instance ID, state, priority, raise time and event text
+    Minimal,
+    /// Minimal fields plus times, alarm class, colors, area, origin, languages, info/state texts and value
+    Standard,
+    /// All ActiveAlarm fields
+    #[default]
+    Full,
+}
+
+/// Lightweight active alarm for list views, see `WinCCUnifiedClient::get_active_alarms_minimal`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct ActiveAlarmSummary {
+    pub name: Option<String>,
+    #[serde(rename = "instanceID")]
+    pub instance_id: Option<i32>,
+    pub state: Option<String>,
+    pub priority: Option<i32>,
+    #[serde(rename = "raiseTime")]
+    pub raise_time: Option<String>,
+    #[serde(rename = "eventText")]
+    pub event_text: Option<Vec<String>>,
 }
 
+impl ActiveAlarm {
+    /// Raise time as UTC timestamp
+    pub fn raise_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.raise_time)
+    }
+
+    /// Acknowledgment time as UTC timestamp
+    pub fn acknowledgment_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.acknowledgment_time)
+    }
+
+    /// Clear time as UTC timestamp
+    pub fn clear_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.clear_time)
+    }
+
+    /// Reset time as UTC timestamp
+    pub fn reset_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.reset_time)
+    }
+
+    /// Modification time as UTC timestamp
+    pub fn modification_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.modification_time)
+    }
+
+    /// Limit that triggered the alarm as number
+    ///
+    /// Numeric strings are parsed; returns None if the field is not set or not numeric,
+    /// `value_limit` keeps the raw value.
+    pub fn value_limit_f64(&self) -> Option<f64> {
+        variant_f64(&self.value_limit)
+    }
+
+    /// Dead band of the alarm as number, see `value_limit_f64`
+    pub fn dead_band_f64(&self) -> Option<f64> {
+        variant_f64(&self.dead_band)
+    }
+}
+
+/// Localized text field of an `ActiveAlarm` or `LoggedAlarm`, see `text_for_language`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum AlarmTextField {
+    EventText,
+    InfoText,
+    AlarmText1,
+    AlarmText2,
+    AlarmText3,
+    AlarmText4,
+    AlarmText5,
+    AlarmText6,
+    AlarmText7,
+    AlarmText8,
+    AlarmText9,
+    StateText,
+    AlarmClassSymbol,
+    AlarmType,
+    ChangeReason,
+}
+
+/// Entry of `texts` at the position of `language` in `languages` (compared case-insensitively)
+fn text_in_language<'a>(languages: Option<&Vec<String>>, texts: Option<&'a Vec<String>>, language: &str) -> Option<&'a str> {
+    let index = languages?.iter().position(|l| l.eq_ignore_ascii_case(language))?;
+    texts?.get(index).map(String::as_str)
+}
+
+/// Implements `text_for_language` for the alarm types sharing the localized text fields
+macro_rules! impl_alarm_texts {
+    ($alarm:ty) => {
+        impl $alarm {
+            /// Text of `field` in `language`, e.g. `alarm.text_for_language(AlarmTextField::EventText, "de-DE")`
+            ///
+            /// The text arrays are ordered like the requested languages, which the server
+            /// returns in `languages`. Returns None if `languages` was not requested, the
+            /// language was not requested, or the field is missing.
+            pub fn text_for_language(&self, field: AlarmTextField, language: &str) -> Option<&str> {
+                let texts = match field {
+                    AlarmTextField::EventText => &self.event_text,
+                    AlarmTextField::InfoText => &self.info_text,
+                    AlarmTextField::AlarmText1 => &self.alarm_text1,
+                    AlarmTextField::AlarmText2 => &self.alarm_text2,
+                    AlarmTextField::AlarmText3 => &self.alarm_text3,
+                    AlarmTextField::AlarmText4 => &self.alarm_text4,
+                    AlarmTextField::AlarmText5 => &self.alarm_text5,
+                    AlarmTextField::AlarmText6 => &self.alarm_text6,
+                    AlarmTextField::AlarmText7 => &self.alarm_text7,
+                    AlarmTextField::AlarmText8 => &self.alarm_text8,
+                    AlarmTextField::AlarmText9 => &self.alarm_text9,
+                    AlarmTextField::StateText => &self.state_text,
+                    AlarmTextField::AlarmClassSymbol => &self.alarm_class_symbol,
+                    AlarmTextField::AlarmType => &self.alarm_type,
+                    AlarmTextField::ChangeReason => &self.change_reason,
+                };
+                text_in_language(self.languages.as_ref(), texts.as_ref(), language)
+            }
+        }
+    };
+}
+
+impl_alarm_texts!(ActiveAlarm);
+impl_alarm_texts!(LoggedAlarm);
+
 /// Logged alarm information (similar to ActiveAlarm but for historical data)
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct LoggedAlarm {
     pub name: Option<String>,
     #[serde(rename = "instanceID")]
@@ -269,7 +1069,7 @@ pub struct LoggedAlarm {
     pub state_machine: Option<String>,
     pub priority: Option<i32>,
     #[serde(rename = "alarmParameterValues")]
-    pub alarm_parameter_values: Option<Vec<Value>>,
+    pub alarm_parameter_values: Option<Vec<AlarmParameterValue>>,
     #[serde(rename = "alarmType")]
     pub alarm_type: Option<Vec<String>>,
     #[serde(rename = "eventText")]
@@ -315,26 +1115,113 @@ pub struct LoggedAlarm {
     pub value_quality: Option<Quality>,
     pub quality: Option<Quality>,
     #[serde(rename = "invalidFlags")]
-    pub invalid_flags: Option<Value>,
+    pub invalid_flags: Option<InvalidFlags>,
     pub deadband: Option<Value>,
     pub producer: Option<String>,
     pub duration: Option<String>,
     #[serde(rename = "durationIso")]
     pub duration_iso: Option<String>,
+    /// Whether comments are attached to the logged alarm state. The GraphQL schema only
+    /// exposes this flag; the comment texts themselves cannot be queried.
     #[serde(rename = "hasComments")]
     pub has_comments: Option<bool>,
 }
 
+impl LoggedAlarm {
+    /// Raise time as UTC timestamp
+    pub fn raise_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.raise_time)
+    }
+
+    /// Acknowledgment time as UTC timestamp
+    pub fn acknowledgment_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.acknowledgment_time)
+    }
+
+    /// Clear time as UTC timestamp
+    pub fn clear_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.clear_time)
+    }
+
+    /// Reset time as UTC timestamp
+    pub fn reset_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.reset_time)
+    }
+
+    /// Modification time as UTC timestamp
+    pub fn modification_time_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.modification_time)
+    }
+
+    /// Limit that triggered the alarm as number, see `ActiveAlarm::value_limit_f64`
+    pub fn value_limit_f64(&self) -> Option<f64> {
+        variant_f64(&self.value_limit)
+    }
+
+    /// Dead band of the alarm as number, see `ActiveAlarm::value_limit_f64`
+    pub fn dead_band_f64(&self) -> Option<f64> {
+        variant_f64(&self.deadband)
+    }
+}
+
 /// Input for alarm identifier operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+/// 
+/// `instance_id` is only sent when set; the server default is 0 (all instances).
+/// 
+/// ```
+/// use winccua_graphql_client::AlarmIdentifierInput;
+/// 
+/// let ids: Vec<AlarmIdentifierInput> = vec!["System::Alarm1".into(), ("System::Alarm2", 3).into()];
+/// assert_eq!(ids[1].instance_id, Some(3));
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AlarmIdentifierInput {
     pub name: String,
-    #[serde(rename = "instanceID")]
+    #[serde(rename = "instanceID", skip_serializing_if = "Option::is_none")]
     pub instance_id: Option<i32>,
 }
 
+impl AlarmIdentifierInput {
+    /// Identify an alarm by name without instance ID
+    pub fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            instance_id: None,
+        }
+    }
+
+    /// Identify a specific instance of an alarm
+    pub fn with_instance(name: &str, instance_id: i32) -> Self {
+        Self {
+            name: name.to_string(),
+            instance_id: Some(instance_id),
+        }
+    }
+}
+
+impl From<&str> for AlarmIdentifierInput {
+    fn from(name: &str) -> Self {
+        Self::new(name)
+    }
+}
+
+impl From<String> for AlarmIdentifierInput {
+    fn from(name: String) -> Self {
+        Self {
+            name,
+            instance_id: None,
+        }
+    }
+}
+
+impl From<(&str, i32)> for AlarmIdentifierInput {
+    fn from((name, instance_id): (&str, i32)) -> Self {
+        Self::with_instance(name, instance_id)
+    }
+}
+
 /// Result of alarm mutation operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct AlarmMutationResult {
     #[serde(rename = "alarmName")]
     pub alarm_name: Option<String>,
@@ -342,7 +1229,7 @@ pub struct AlarmMutationResult {
 }
 
 /// Result of active alarm mutation operations
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ActiveAlarmMutationResult {
     #[serde(rename = "alarmName")]
     pub alarm_name: Option<String>,
@@ -351,36 +1238,58 @@ pub struct ActiveAlarmMutationResult {
     pub error: Option<ErrorInfo>,
 }
 
+graphql_enum! {
+    /// Reason of a subscription notification
+    pub enum NotificationReason {
+        /// The object was added to the subscription (initial value or newly matching)
+        Added => "Added",
+        /// The value or state changed
+        Modified => "Modified",
+        /// The object is no longer part of the subscription
+        Removed => "Removed",
+        /// The object was renamed and is therefore no longer part of the subscription
+        RemovedNameChanged => "Removed (Name changed)",
+    }
+    other Other
+}
+
 /// Tag value notification for subscriptions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct TagValueNotification {
     pub name: Option<String>,
     pub value: Option<TagValue>,
     pub error: Option<ErrorInfo>,
     #[serde(rename = "notificationReason")]
-    pub notification_reason: Option<String>,
+    pub notification_reason: Option<NotificationReason>,
 }
 
 /// Active alarm notification for subscriptions
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ActiveAlarmNotification {
     #[serde(flatten)]
     pub alarm: ActiveAlarm,
     #[serde(rename = "notificationReason")]
-    pub notification_reason: Option<String>,
+    pub notification_reason: Option<NotificationReason>,
 }
 
 /// Redu state notification
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ReduStateNotification {
     pub value: Option<ReduStateValue>,
     #[serde(rename = "notificationReason")]
-    pub notification_reason: Option<String>,
+    pub notification_reason: Option<NotificationReason>,
 }
 
 /// Redu state value
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Serialize, Deserialize)]
 pub struct ReduStateValue {
     pub value: Option<String>, // "ACTIVE" or "PASSIVE"
     pub timestamp: Option<String>,
-}
\ No newline at end of file
+}
+
+impl ReduStateValue {
+    /// State change timestamp as UTC timestamp
+    pub fn timestamp_utc(&self) -> WinCCResult<Option<DateTime<Utc>>> {
+        parse_optional_timestamp(&self.timestamp)
+    }
+}
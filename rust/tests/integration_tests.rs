@@ -4,6 +4,7 @@ use winccua_graphql_client::{WinCCUnifiedClient, TagValueInput, AlarmIdentifierI
 use serde_json::json;
 
 #[test]
+#[allow(unused_variables, clippy::assertions_on_constants)]
 fn test_client_creation() {
     let client = WinCCUnifiedClient::new(
         "https://example.com/graphql"
@@ -39,15 +40,35 @@ fn test_alarm_identifier_input_serialization() {
     assert!(serialized.contains("1"));
 }
 
+#[test]
+fn test_alarm_identifier_input_constructors() {
+    let input = AlarmIdentifierInput::new("System::Alarm1");
+    assert_eq!(input.instance_id, None);
+    assert_eq!(serde_json::to_value(&input).unwrap(), json!({"name": "System::Alarm1"}));
+    
+    let input = AlarmIdentifierInput::with_instance("System::Alarm1", 2);
+    assert_eq!(serde_json::to_value(&input).unwrap(), json!({"name": "System::Alarm1", "instanceID": 2}));
+    
+    let inputs: Vec<AlarmIdentifierInput> = vec![
+        "System::Alarm1".into(),
+        "System::Alarm2".to_string().into(),
+        ("System::Alarm3", 4).into(),
+    ];
+    assert_eq!(inputs[1].name, "System::Alarm2");
+    assert_eq!(inputs[2].instance_id, Some(4));
+}
+
 #[test]
 fn test_error_handling() {
     use winccua_graphql_client::WinCCError;
     
-    let error = WinCCError::LoginError("Test error".to_string());
-    assert!(error.to_string().contains("Test error"));
+    let error = WinCCError::LoginError { code: 101, description: "Test error".to_string() };
+    assert!(error.to_string().contains("101 - Test error"));
+    assert_eq!(error.code(), Some(101));
 }
 
 #[test]
+#[allow(unused_imports)]
 fn test_json_structures() {
     use winccua_graphql_client::{Session, User, ErrorInfo};
     
@@ -66,4 +87,3324 @@ fn test_json_structures() {
     let session: Session = serde_json::from_value(session_json).unwrap();
     assert_eq!(session.user.as_ref().unwrap().name.as_ref().unwrap(), "testuser");
     assert_eq!(session.token.as_ref().unwrap(), "abc123");
-}
\ No newline at end of file
+}
+
+#[test]
+fn test_typed_subscription_callbacks() {
+    use std::sync::{Arc, Mutex};
+    use winccua_graphql_client::{TagValueNotification, TypedSubscriptionCallbacks};
+    
+    let received = Arc::new(Mutex::new(Vec::new()));
+    let errors = Arc::new(Mutex::new(Vec::new()));
+    let received_clone = received.clone();
+    let errors_clone = errors.clone();
+    
+    let callbacks = TypedSubscriptionCallbacks::new(move |notification: TagValueNotification| {
+        received_clone.lock().unwrap().push(notification);
+    })
+    .with_error(move |err| {
+        errors_clone.lock().unwrap().push(err);
+    })
+    .into_callbacks("tagValues");
+    
+    (callbacks.on_data)(json!({
+        "data": {
+            "tagValues": {
+                "name": "HMI_Tag_1",
+                "value": { "value": 42, "timestamp": "2023-12-31T23:59:59.999Z" },
+                "notificationReason": "Modified"
+            }
+        }
+    }));
+    (callbacks.on_data)(json!({ "data": { "activeAlarms": {} } }));
+    
+    let received = received.lock().unwrap();
+    assert_eq!(received.len(), 1);
+    assert_eq!(received[0].name.as_deref(), Some("HMI_Tag_1"));
+    assert_eq!(received[0].notification_reason, Some(winccua_graphql_client::NotificationReason::Modified));
+    assert_eq!(errors.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_client_builder() {
+    use std::time::Duration;
+    use winccua_graphql_client::WinCCError;
+    
+    let client = WinCCUnifiedClient::builder()
+        .http_url("https://example.com/graphql")
+        .ws_url("wss://example.com/graphql")
+        .connect_timeout(Duration::from_secs(5))
+        .request_timeout(Duration::from_secs(30))
+        .token("abc123")
+        .danger_accept_invalid_certs(true)
+        .build();
+    assert!(client.is_ok());
+    
+    let missing_url = WinCCUnifiedClient::builder().build();
+    assert!(matches!(missing_url, Err(WinCCError::InvalidParameter(_))));
+}
+
+#[test]
+fn test_sorting_and_bounding_modes() {
+    use winccua_graphql_client::{BoundingMode, SortingMode};
+    
+    assert_eq!(SortingMode::TimeAsc.as_str(), "TIME_ASC");
+    assert_eq!(SortingMode::TimeDesc.as_str(), "TIME_DESC");
+    assert_eq!(BoundingMode::None.as_str(), "NO_BOUNDING_VALUES");
+    assert_eq!(BoundingMode::LeftRight.as_str(), "LEFTRIGHT_BOUNDING_VALUES");
+    assert_eq!(serde_json::to_value(BoundingMode::Left).unwrap(), json!("LEFT_BOUNDING_VALUES"));
+    assert_eq!(serde_json::to_value(SortingMode::TimeDesc).unwrap(), json!(SortingMode::TimeDesc.as_str()));
+}
+
+#[test]
+fn test_graphql_error_codes() {
+    use winccua_graphql_client::WinCCError;
+    
+    let error = WinCCError::from_graphql_errors(&[json!({
+        "message": "Only leaf elements of a Structure Tag can be addressed",
+        "extensions": { "code": "202" }
+    })]);
+    assert_eq!(error.code(), Some(202));
+    assert!(matches!(error, WinCCError::ApiError { code: 202, .. }));
+    
+    let error = WinCCError::from_graphql_errors(&[json!({ "message": "Something went wrong" })]);
+    assert_eq!(error.code(), None);
+    assert!(matches!(error, WinCCError::GraphQLError { .. }));
+    
+    // A top-level code is used by the error and by its entries alike
+    let error = WinCCError::from_graphql_errors(&[
+        json!({ "message": "Syntax error in query string", "code": 301 }),
+        json!({ "message": "Malformed", "locations": "none", "code": "2" }),
+    ]);
+    assert_eq!(error.code(), Some(301));
+    let codes: Vec<_> = error.graphql_errors().iter().map(|e| e.code()).collect();
+    assert_eq!(codes, vec![Some(301), Some(2)]);
+}
+
+#[test]
+fn test_auto_extend_requires_session() {
+    use winccua_graphql_client::WinCCError;
+    
+    let client = WinCCUnifiedClient::new("https://example.com/graphql");
+    assert!(matches!(client.enable_auto_extend(), Err(WinCCError::SessionError(_))));
+    assert!(!client.is_auto_extend_enabled());
+    client.disable_auto_extend();
+}
+
+#[test]
+fn test_timestamp_parsing() {
+    use chrono::{Datelike, Timelike};
+    use winccua_graphql_client::{TagValue, WinCCError};
+    
+    let mut tag_value = TagValue {
+        value: Some(json!(1)),
+        timestamp: Some("2023-12-31T23:59:59.999Z".to_string()),
+        quality: None,
+    };
+    let timestamp = tag_value.timestamp_utc().unwrap().unwrap();
+    assert_eq!(timestamp.year(), 2023);
+    assert_eq!(timestamp.nanosecond(), 999_000_000);
+    
+    tag_value.timestamp = Some("not a timestamp".to_string());
+    assert!(matches!(tag_value.timestamp_utc(), Err(WinCCError::InvalidTimestamp(_))));
+    
+    tag_value.timestamp = None;
+    assert!(tag_value.timestamp_utc().unwrap().is_none());
+}
+
+#[test]
+fn test_tag_value_helpers() {
+    use winccua_graphql_client::{TagValue, WinCCError};
+    
+    let tag_value = |value| TagValue { value: Some(value), timestamp: None, quality: None };
+    
+    assert_eq!(tag_value(json!(42)).as_i64(), Some(42));
+    assert_eq!(tag_value(json!(1.5)).as_f64(), Some(1.5));
+    assert_eq!(tag_value(json!(true)).as_bool(), Some(true));
+    assert_eq!(tag_value(json!("text")).as_str(), Some("text"));
+    assert_eq!(tag_value(json!([1, 2])).as_array().map(|a| a.len()), Some(2));
+    assert_eq!(tag_value(json!([1, 2])).get::<Vec<i32>>(), Some(vec![1, 2]));
+    assert_eq!(tag_value(json!("text")).as_i64(), None);
+    
+    assert_eq!(tag_value(json!("123")).coerce("Int32"), Some(json!(123)));
+    assert_eq!(tag_value(json!("1.25")).coerce("LReal"), Some(json!(1.25)));
+    assert_eq!(tag_value(json!("true")).coerce("Bool"), Some(json!(true)));
+    assert_eq!(tag_value(json!("abc")).coerce("Int32"), None);
+    assert_eq!(tag_value(json!("abc")).coerce("WString"), Some(json!("abc")));
+    assert_eq!(tag_value(json!(300)).coerce("Byte"), None);
+    
+    assert_eq!(tag_value(json!(1)).coerce_to("Bool").unwrap(), json!(true));
+    assert_eq!(tag_value(json!("false")).coerce_to("Bool").unwrap(), json!(false));
+    assert_eq!(tag_value(json!(5.0)).coerce_to("Int16").unwrap(), json!(5));
+    assert_eq!(tag_value(json!("-7")).coerce_to("DInt").unwrap(), json!(-7));
+    assert_eq!(tag_value(json!(true)).coerce_to("Byte").unwrap(), json!(1));
+    assert_eq!(tag_value(json!(u64::MAX)).coerce_to("ULInt").unwrap(), json!(u64::MAX));
+    assert_eq!(tag_value(json!(3)).coerce_to("LReal").unwrap(), json!(3.0));
+    assert_eq!(tag_value(json!(42)).coerce_to("WString").unwrap(), json!("42"));
+    assert_eq!(tag_value(json!("2024-01-01T00:00:00Z")).coerce_to("DateTime").unwrap(), json!("2024-01-01T00:00:00Z"));
+    assert!(matches!(tag_value(json!(300)).coerce_to("Byte"), Err(WinCCError::TagError(_))));
+    assert!(matches!(tag_value(json!(-1)).coerce_to("UInt32"), Err(WinCCError::TagError(_))));
+    assert!(matches!(tag_value(json!(1.5)).coerce_to("Int32"), Err(WinCCError::TagError(_))));
+    assert!(matches!(tag_value(json!("yes")).coerce_to("Bool"), Err(WinCCError::TagError(_))));
+    assert!(matches!(tag_value(json!([1])).coerce_to("Real"), Err(WinCCError::TagError(_))));
+    let empty = TagValue { value: None, timestamp: None, quality: None };
+    assert!(matches!(empty.coerce_to("Int32"), Err(WinCCError::TagError(_))));
+}
+
+#[test]
+fn test_custom_headers() {
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::WinCCError;
+    
+    let (http_url, _, headers) = spawn_http_server_recording_headers(vec![(200, r#"{"data":{"tagValues":[]}}"#)]);
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let ws_url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = runtime.spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        let init = next_json(&mut ws).await;
+        assert_eq!(init["payload"], json!({"Authorization": "Bearer token", "x-api-key": "secret"}));
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    });
+    let mut client = WinCCUnifiedClient::with_token_and_ws(&http_url, &ws_url, "token");
+    
+    // Headers can be changed on a client shared between threads
+    let shared = std::sync::Arc::new(client.clone());
+    assert!(shared.set_header("X-Api-Key", "secret").is_ok());
+    assert!(shared.set_header("X-Tenant-Id", "tenant-1").is_ok());
+    assert!(matches!(shared.set_header("Invalid Header", "value"), Err(WinCCError::InvalidParameter(_))));
+    shared.remove_header("X-Tenant-Id");
+    
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    let request = headers.lock().unwrap()[0].clone();
+    assert_eq!(request.get("x-api-key").map(String::as_str), Some("secret"));
+    assert_eq!(request.get("authorization").map(String::as_str), Some("Bearer token"));
+    assert_eq!(request.get("x-tenant-id"), None);
+    
+    runtime.block_on(async {
+        // The server only acknowledges a payload with the custom header
+        client.connect_ws_and_wait(Duration::from_secs(5)).await.unwrap();
+        client.disconnect_ws().await;
+    });
+}
+
+#[test]
+fn test_graphql_error_details() {
+    use winccua_graphql_client::WinCCError;
+    
+    let error = WinCCError::from_graphql_response(
+        &[json!({
+            "message": "Syntax error in query string",
+            "locations": [{ "line": 1, "column": 10 }],
+            "path": ["activeAlarms"],
+            "extensions": { "code": 301 }
+        })],
+        Some(json!({ "activeAlarms": null })),
+    );
+    
+    let errors = error.graphql_errors();
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), Some(301));
+    assert_eq!(errors[0].path.as_ref().unwrap()[0], json!("activeAlarms"));
+    assert_eq!(errors[0].locations.as_ref().unwrap().len(), 1);
+    assert!(error.partial_data().is_some());
+}
+
+#[test]
+fn test_batch_request_builder() {
+    use winccua_graphql_client::graphql::queries;
+    
+    let client = WinCCUnifiedClient::new("https://example.com/graphql");
+    assert!(client.batch().is_empty());
+    assert!(client.batch().execute().unwrap().is_empty());
+    
+    let batch = client.batch()
+        .add(queries::TAG_VALUES, Some(json!({ "names": ["HMI_Tag_1"] })))
+        .add(queries::ACTIVE_ALARMS, None);
+    assert_eq!(batch.len(), 2);
+}
+
+#[tokio::test]
+async fn test_ws_connection_state_on_failed_connect() {
+    use winccua_graphql_client::{ConnectionState, GraphQLWSClient};
+    
+    let mut ws_client = GraphQLWSClient::new("ws://127.0.0.1:1/graphql".to_string(), String::new());
+    assert_eq!(ws_client.connection_state(), ConnectionState::Disconnected);
+    
+    let result = ws_client.connect().await;
+    match result {
+        Err(winccua_graphql_client::WinCCError::WebSocketError(message)) => {
+            assert!(message.contains("WebSocket connection failed"));
+        }
+        other => panic!("Expected WebSocketError, got {:?}", other),
+    }
+    assert_eq!(ws_client.connection_state(), ConnectionState::Disconnected);
+}
+
+#[tokio::test]
+async fn test_ws_connect_timeout() {
+    use winccua_graphql_client::{GraphQLWSClient, WinCCError};
+    
+    // Accepts TCP connections but never answers the upgrade request
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let (_socket, _) = listener.accept().await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new())
+        .with_connect_timeout(std::time::Duration::from_millis(200));
+    let started = std::time::Instant::now();
+    let result = ws_client.connect().await;
+    
+    assert!(matches!(result, Err(WinCCError::WebSocketError(message)) if message.contains("timed out")));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[tokio::test]
+async fn test_ws_connect_and_wait_fails_without_server() {
+    use winccua_graphql_client::{ConnectionState, GraphQLWSClient, WinCCError};
+    
+    let mut ws_client = GraphQLWSClient::new("ws://127.0.0.1:1/graphql".to_string(), String::new());
+    let result = ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await;
+    
+    assert!(matches!(result, Err(WinCCError::WebSocketError(_))));
+    assert_eq!(ws_client.connection_state(), ConnectionState::Disconnected);
+}
+
+#[test]
+fn test_builder_tls_configuration() {
+    let result = WinCCUnifiedClient::builder()
+        .http_url("https://localhost:4000/graphql")
+        .add_root_certificate_pem(b"not a certificate")
+        .build();
+    assert!(result.is_err());
+    
+    let result = WinCCUnifiedClient::builder()
+        .http_url("https://localhost:4000/graphql")
+        .identity_pkcs12(b"not an archive", "secret")
+        .build();
+    assert!(result.is_err());
+    
+    let client = WinCCUnifiedClient::builder()
+        .http_url("https://localhost:4000/graphql")
+        .ws_url("wss://localhost:4000/graphql")
+        .danger_accept_invalid_certs(true)
+        .build();
+    assert!(client.is_ok());
+}
+
+#[tokio::test]
+async fn test_ws_proxy_connect_rejected() {
+    use tokio::io::{AsyncReadExt, AsyncWriteExt};
+    use winccua_graphql_client::{GraphQLWSClient, WinCCError};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let proxy_url = format!("http://user:secret@{}", listener.local_addr().unwrap());
+    let proxy = tokio::spawn(async move {
+        let (mut socket, _) = listener.accept().await.unwrap();
+        let mut buf = vec![0u8; 1024];
+        let n = socket.read(&mut buf).await.unwrap();
+        socket.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").await.unwrap();
+        String::from_utf8_lossy(&buf[..n]).to_string()
+    });
+    
+    let mut ws_client = GraphQLWSClient::new("ws://wincc-server:4000/graphql".to_string(), String::new())
+        .with_proxy(proxy_url);
+    let result = ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await;
+    
+    match result {
+        Err(WinCCError::WebSocketError(message)) => assert!(message.contains("407")),
+        other => panic!("Expected WebSocketError, got {:?}", other),
+    }
+    let request = proxy.await.unwrap();
+    assert!(request.starts_with("CONNECT wincc-server:4000 HTTP/1.1\r\n"));
+    assert!(request.contains("Proxy-Authorization: Basic dXNlcjpzZWNyZXQ=\r\n"));
+}
+
+/// Serve the given (status, body) responses in order, one per connection, and return the URL
+/// and the JSON bodies of the requests received
+fn spawn_http_server(responses: Vec<(u16, &'static str)>) -> (String, std::sync::Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+    spawn_http_server_with_headers(responses.into_iter().map(|(status, body)| (status, "", body)).collect())
+}
+
+/// Like `spawn_http_server`, with extra header lines (each ending in `\r\n`) per response
+fn spawn_http_server_with_headers(
+    responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, std::sync::Arc<std::sync::Mutex<Vec<serde_json::Value>>>) {
+    let (url, requests, _) = serve_http(responses);
+    (url, requests)
+}
+
+/// Like `spawn_http_server`, also returning the headers of each request (names in lower case)
+fn spawn_http_server_recording_headers(
+    responses: Vec<(u16, &'static str)>,
+) -> (String, std::sync::Arc<std::sync::Mutex<Vec<serde_json::Value>>>, RequestHeaders) {
+    serve_http(responses.into_iter().map(|(status, body)| (status, "", body)).collect())
+}
+
+/// Headers of the requests received by a test server, one map per request
+type RequestHeaders = std::sync::Arc<std::sync::Mutex<Vec<std::collections::HashMap<String, String>>>>;
+
+/// Serve (status, header lines, body) responses and record the body and headers of each request
+fn serve_http(
+    responses: Vec<(u16, &'static str, &'static str)>,
+) -> (String, std::sync::Arc<std::sync::Mutex<Vec<serde_json::Value>>>, RequestHeaders) {
+    use std::io::{BufRead, BufReader, Read, Write};
+    
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let requests = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let request_headers = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let (thread_requests, thread_headers) = (requests.clone(), request_headers.clone());
+    
+    std::thread::spawn(move || {
+        for (status, headers, body) in responses {
+            let (stream, _) = match listener.accept() {
+                Ok(connection) => connection,
+                Err(_) => return,
+            };
+            let mut reader = BufReader::new(stream);
+            let mut received = std::collections::HashMap::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(':') {
+                    received.insert(name.trim().to_ascii_lowercase(), value.trim().to_string());
+                }
+            }
+            let content_length = received.get("content-length").and_then(|value| value.parse().ok()).unwrap_or(0);
+            let mut request_body = vec![0u8; content_length];
+            let _ = reader.read_exact(&mut request_body);
+            // Record the headers first, so a test that sees the body also sees the headers
+            thread_headers.lock().unwrap().push(received);
+            thread_requests.lock().unwrap().push(serde_json::from_slice(&request_body).unwrap_or_default());
+            
+            let response = format!(
+                "HTTP/1.1 {} Status\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n{}\r\n{}",
+                status, body.len(), headers, body
+            );
+            let _ = reader.get_mut().write_all(response.as_bytes());
+        }
+    });
+    
+    (url, requests, request_headers)
+}
+
+#[test]
+fn test_retry_policy() {
+    use std::time::Duration;
+    use winccua_graphql_client::{RetryPolicy, WinCCError};
+    
+    let policy = RetryPolicy::new(3).with_base_delay(Duration::from_millis(10));
+    
+    // 5xx responses are retried
+    let (url, requests) = spawn_http_server(vec![
+        (502, "bad gateway"),
+        (503, "unavailable"),
+        (200, r#"{"data":{"nonce":{"value":"abc","validFor":60}}}"#),
+    ]);
+    let client = WinCCUnifiedClient::builder().http_url(&url).retry_policy(policy.clone()).build().unwrap();
+    let nonce = client.get_nonce().unwrap();
+    assert_eq!(nonce.value.as_deref(), Some("abc"));
+    assert_eq!(requests.lock().unwrap().len(), 3);
+    
+    // 4xx responses are not retried
+    let (url, requests) = spawn_http_server(vec![(400, "bad request"), (200, "{}")]);
+    let client = WinCCUnifiedClient::builder().http_url(&url).retry_policy(policy.clone()).build().unwrap();
+    assert!(matches!(client.get_nonce(), Err(WinCCError::ServerError { status: 400, .. })));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    
+    // Mutations can be excluded
+    let (url, requests) = spawn_http_server(vec![(502, "bad gateway"), (200, "{}")]);
+    let client = WinCCUnifiedClient::builder()
+        .http_url(&url)
+        .retry_policy(policy.retry_mutations(false))
+        .build()
+        .unwrap();
+    assert!(client.execute_raw("mutation { logout }", None).is_err());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+    
+    // No retries by default
+    let (url, requests) = spawn_http_server(vec![(502, "bad gateway"), (200, "{}")]);
+    let client = WinCCUnifiedClient::new(&url);
+    assert!(client.get_nonce().is_err());
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_tag_value_input_builder() {
+    use winccua_graphql_client::QualityInput;
+    
+    let inputs = vec![
+        TagValueInput::new("System::Tag1", 1)
+            .with_quality(QualityInput::new("UNCERTAIN").with_sub_status("SUBSTITUTE_VALUE")),
+        TagValueInput::new("System::Tag2", 2.5).with_timestamp("2024-01-01T00:00:00Z"),
+    ];
+    
+    let serialized = serde_json::to_value(&inputs).unwrap();
+    
+    // The per-tag quality is sent and takes precedence over the mutation-level fallback
+    assert_eq!(serialized[0]["quality"], json!({"quality": "UNCERTAIN", "subStatus": "SUBSTITUTE_VALUE"}));
+    assert!(serialized[0].get("timestamp").is_none());
+    
+    // Without a per-tag quality the field is omitted so the fallback applies
+    assert!(serialized[1].get("quality").is_none());
+    assert_eq!(serialized[1]["timestamp"], "2024-01-01T00:00:00Z");
+    assert_eq!(serialized[1]["value"], 2.5);
+}
+
+/// Accept one WebSocket connection that negotiates the given subprotocol
+#[allow(clippy::result_large_err)]
+async fn accept_ws(
+    listener: &tokio::net::TcpListener,
+    protocol: &'static str,
+) -> tokio_tungstenite::WebSocketStream<tokio::net::TcpStream> {
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    
+    let (stream, _) = listener.accept().await.unwrap();
+    tokio_tungstenite::accept_hdr_async(stream, |_: &Request, mut response: Response| {
+        response.headers_mut().insert("Sec-WebSocket-Protocol", protocol.parse().unwrap());
+        Ok(response)
+    })
+    .await
+    .unwrap()
+}
+
+/// Read the next text message from a WebSocket as JSON
+async fn next_json(ws: &mut tokio_tungstenite::WebSocketStream<tokio::net::TcpStream>) -> serde_json::Value {
+    use futures_util::StreamExt;
+    
+    loop {
+        match ws.next().await.unwrap().unwrap() {
+            tokio_tungstenite::tungstenite::Message::Text(text) => return serde_json::from_str(&text).unwrap(),
+            _ => continue,
+        }
+    }
+}
+
+#[tokio::test]
+async fn test_ws_unsubscribe_is_confirmed() {
+    use futures_util::SinkExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let subscribe = next_json(&mut ws).await;
+        assert_eq!(subscribe["type"], "subscribe");
+        let id = subscribe["id"].clone();
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": {"tagValues": {}}}}).to_string())).await.unwrap();
+        
+        let complete = next_json(&mut ws).await;
+        assert_eq!(complete, json!({"type": "complete", "id": id}));
+    });
+    
+    let data = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let (data_cb, completed_cb) = (data.clone(), completed.clone());
+    let callbacks = SubscriptionCallbacks::new(move |_| {
+        data_cb.fetch_add(1, Ordering::SeqCst);
+    })
+    .with_complete(move || {
+        completed_cb.fetch_add(1, Ordering::SeqCst);
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, "token".to_string());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    let subscription = ws_client
+        .subscribe("subscription { tagValues }".to_string(), Default::default(), callbacks)
+        .await
+        .unwrap();
+    
+    while data.load(Ordering::SeqCst) == 0 {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    subscription.unsubscribe().await.unwrap();
+    assert_eq!(completed.load(Ordering::SeqCst), 1);
+    
+    server.await.unwrap();
+    ws_client.disconnect().await;
+}
+
+#[tokio::test]
+async fn test_ws_legacy_protocol() {
+    use futures_util::SinkExt;
+    use std::sync::Arc;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, SubscriptionCallbacks, WsProtocol};
+    
+    assert_eq!(WsProtocol::default().subprotocol(), "graphql-transport-ws");
+    assert_eq!(WsProtocol::SubscriptionsTransportWs.subprotocol(), "graphql-ws");
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        ws.send(Message::Text(json!({"type": "ka"}).to_string())).await.unwrap();
+        
+        let start = next_json(&mut ws).await;
+        assert_eq!(start["type"], "start");
+        let id = start["id"].clone();
+        ws.send(Message::Text(json!({"type": "data", "id": id, "payload": {"data": {"reduState": {}}}}).to_string())).await.unwrap();
+        
+        assert_eq!(next_json(&mut ws).await, json!({"type": "stop", "id": id}));
+    });
+    
+    let received = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let received_cb = received.clone();
+    let callbacks = SubscriptionCallbacks::new(move |data| received_cb.lock().unwrap().push(data));
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new()).with_protocol(WsProtocol::SubscriptionsTransportWs);
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    let subscription = ws_client
+        .subscribe("subscription { reduState }".to_string(), Default::default(), callbacks)
+        .await
+        .unwrap();
+    
+    while received.lock().unwrap().is_empty() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert_eq!(received.lock().unwrap()[0], json!({"data": {"reduState": {}}}));
+    subscription.unsubscribe().await.unwrap();
+    
+    server.await.unwrap();
+    ws_client.disconnect().await;
+}
+
+#[test]
+fn test_active_alarm_lookup_filters() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"activeAlarms":[{"name":"System1::Alarm_1","instanceID":3}]}}"#),
+        (200, r#"{"data":{"activeAlarms":[]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    let alarm = client.get_active_alarm_by_instance("System1::Alarm_1", 3).unwrap().unwrap();
+    assert_eq!(alarm.instance_id, Some(3));
+    
+    let names = vec!["System1::Alarm_1".to_string(), "System1::O'Brien".to_string()];
+    assert!(client.get_active_alarms_by_names(&names).unwrap().is_empty());
+    assert!(client.get_active_alarms_by_names(&[]).unwrap().is_empty());
+    
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0]["variables"]["filterString"], "name = 'System1::Alarm_1' AND instanceID = 3");
+    assert_eq!(requests[1]["variables"]["filterString"], "name = 'System1::Alarm_1' OR name = 'System1::O''Brien'");
+}
+
+#[test]
+fn test_alarm_invalid_flags() {
+    use winccua_graphql_client::ActiveAlarm;
+    
+    let alarm: ActiveAlarm = serde_json::from_value(json!({
+        "name": "System1::Alarm_1",
+        "invalidFlags": {
+            "invalidConfiguration": false,
+            "invalidTimestamp": true,
+            "invalidAlarmParameter": false,
+            "invalidEventText": null
+        }
+    }))
+    .unwrap();
+    
+    let flags = alarm.invalid_flags.unwrap();
+    assert_eq!(flags.invalid_timestamp, Some(true));
+    assert_eq!(flags.invalid_configuration, Some(false));
+    assert_eq!(flags.invalid_event_text, None);
+    assert!(flags.any());
+    assert!(!winccua_graphql_client::InvalidFlags::default().any());
+}
+
+#[test]
+fn test_quality_enums() {
+    use winccua_graphql_client::{LimitStatus, Quality, QualityCode, QualitySubStatus};
+    
+    let quality: Quality = serde_json::from_value(json!({
+        "quality": "GOOD_CASCADE",
+        "subStatus": "NON_SPECIFIC",
+        "limit": "HIGH_LIMIT_VIOLATION",
+        "extendedSubStatus": "SOME_FUTURE_STATUS"
+    }))
+    .unwrap();
+    
+    assert_eq!(quality.quality, Some(QualityCode::GoodCascade));
+    assert_eq!(quality.sub_status, Some(QualitySubStatus::NonSpecific));
+    assert_eq!(quality.limit, Some(LimitStatus::HighLimitViolation));
+    assert!(quality.is_good());
+    assert!(!quality.is_bad());
+    assert!(!quality.is_uncertain());
+    
+    // Unknown values are kept instead of failing
+    let extended = quality.extended_sub_status.unwrap();
+    assert_eq!(extended.as_str(), "SOME_FUTURE_STATUS");
+    assert_eq!(serde_json::to_value(&extended).unwrap(), json!("SOME_FUTURE_STATUS"));
+    
+    assert_eq!(QualityCode::from("BAD"), QualityCode::Bad);
+    assert_eq!(QualityCode::Uncertain.to_string(), "UNCERTAIN");
+}
+
+#[test]
+fn test_logged_tag_values_paged() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":1,"timestamp":"2024-01-01T00:00:01.000Z"}},
+            {"value":{"value":2,"timestamp":"2024-01-01T00:00:02.000Z"}},
+            {"value":{"value":3,"timestamp":"2024-01-01T00:00:02.000Z"}}]}]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":2,"timestamp":"2024-01-01T00:00:02.000Z"}},
+            {"value":{"value":3,"timestamp":"2024-01-01T00:00:02.000Z"}},
+            {"value":{"value":4,"timestamp":"2024-01-01T00:00:03.000Z"}}]}]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":4,"timestamp":"2024-01-01T00:00:03.000Z"}},
+            {"value":{"value":5,"timestamp":"2024-01-01T00:00:04.000Z"}}]}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    let names = vec!["Tag:Log".to_string()];
+    let values: Vec<i64> = client
+        .logged_tag_values_paged(&names, None, Some("2024-01-02T00:00:00Z"), 3)
+        .map(|page| page.unwrap())
+        .flat_map(|page| page.values.unwrap())
+        .map(|value| value.value.unwrap().as_i64().unwrap())
+        .collect();
+    assert_eq!(values, vec![1, 2, 3, 4, 5]);
+    
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests.len(), 3);
+    assert_eq!(requests[0]["variables"]["startTime"], "1970-01-01T00:00:00.000Z");
+    // startTime is exclusive, so further pages start 1ms before the last timestamp
+    assert_eq!(requests[1]["variables"]["startTime"], "2024-01-01T00:00:01.999Z");
+    assert_eq!(requests[2]["variables"]["startTime"], "2024-01-01T00:00:02.999Z");
+    assert_eq!(requests[2]["variables"]["maxNumberOfValues"], 3);
+    
+    let mut pages = client.logged_tag_values_paged(&names, None, None, 0);
+    assert!(pages.next().unwrap().is_err());
+    assert!(pages.next().is_none());
+    
+    // More values at one timestamp than fit on a page are reported instead of skipped
+    let (url, _) = spawn_http_server(vec![
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":1,"timestamp":"2024-01-01T00:00:01.000Z"}},
+            {"value":{"value":2,"timestamp":"2024-01-01T00:00:02.000Z"}}]}]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":2,"timestamp":"2024-01-01T00:00:02.000Z"}},
+            {"value":{"value":3,"timestamp":"2024-01-01T00:00:02.000Z"}}]}]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[{"loggingTagName":"Tag:Log","values":[
+            {"value":{"value":2,"timestamp":"2024-01-01T00:00:02.000Z"}},
+            {"value":{"value":3,"timestamp":"2024-01-01T00:00:02.000Z"}}]}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    let mut pages = client.logged_tag_values_paged(&names, None, None, 2);
+    assert_eq!(pages.next().unwrap().unwrap().values.unwrap().len(), 2);
+    assert_eq!(pages.next().unwrap().unwrap().values.unwrap().len(), 1);
+    assert!(matches!(pages.next(), Some(Err(winccua_graphql_client::WinCCError::TagError(_)))));
+    assert!(pages.next().is_none());
+}
+
+#[test]
+fn test_subscribe_accepts_str_slices() {
+    use winccua_graphql_client::{SubscriptionCallbacks, WinCCError};
+    
+    // The blocking HTTP client must not be dropped inside the async runtime
+    let client = WinCCUnifiedClient::new("http://localhost:4000/graphql");
+    let callbacks = SubscriptionCallbacks::new(|_| {});
+    
+    tokio::runtime::Runtime::new().unwrap().block_on(async {
+        // Without connect_ws the calls fail, but slices and owned strings are both accepted
+        let result = client.subscribe_to_tag_values(["Tag1", "Tag2"], callbacks.clone()).await;
+        assert!(matches!(result, Err(WinCCError::OperationFailed(_))));
+        let result = client.subscribe_to_tag_values(vec!["Tag1".to_string()], callbacks.clone()).await;
+        assert!(matches!(result, Err(WinCCError::OperationFailed(_))));
+        let result = client
+            .subscribe_to_active_alarms(["System1"], "priority > 500", "en-US", &["en-US", "de-DE"], callbacks)
+            .await;
+        assert!(matches!(result, Err(WinCCError::OperationFailed(_))));
+    });
+}
+
+#[test]
+fn test_single_tag_read() {
+    use winccua_graphql_client::WinCCError;
+    
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"tagValues":[{"name":"HMI_Tag_1","value":{"value":21.5}}]}}"#),
+        (200, r#"{"data":{"tagValues":[{"name":"HMI_Tag_1","value":{"value":21.5}}]}}"#),
+        (200, r#"{"data":{"tagValues":[{"name":"Missing","error":{"code":"2","description":"Cannot resolve provided name"}}]}}"#),
+        (200, r#"{"data":{"tagValues":[]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    let result = client.get_tag_value("HMI_Tag_1").unwrap();
+    assert_eq!(result.name.as_deref(), Some("HMI_Tag_1"));
+    assert_eq!(client.read_f64("HMI_Tag_1").unwrap(), Some(21.5));
+    assert!(matches!(client.read_f64("Missing"), Err(WinCCError::TagError(_))));
+    assert!(matches!(client.get_tag_value("HMI_Tag_1"), Err(WinCCError::TagError(_))));
+    
+    assert_eq!(requests.lock().unwrap()[0]["variables"]["names"], json!(["HMI_Tag_1"]));
+}
+
+#[test]
+fn test_rate_limited() {
+    use std::time::Duration;
+    use winccua_graphql_client::{RetryPolicy, WinCCError};
+    
+    let (url, _) = spawn_http_server_with_headers(vec![(429, "Retry-After: 7\r\n", "")]);
+    let client = WinCCUnifiedClient::new(&url);
+    let error = client.get_nonce().unwrap_err();
+    assert!(matches!(error, WinCCError::RateLimited { retry_after: Some(d) } if d == Duration::from_secs(7)));
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(7)));
+    
+    // The retry policy waits for the Retry-After delay and retries
+    let (url, requests) = spawn_http_server_with_headers(vec![
+        (429, "Retry-After: 0\r\n", ""),
+        (200, "", r#"{"data":{"nonce":{"value":"abc","validFor":60}}}"#),
+    ]);
+    let client = WinCCUnifiedClient::builder()
+        .http_url(&url)
+        .retry_policy(RetryPolicy::new(2).with_base_delay(Duration::from_secs(60)))
+        .build()
+        .unwrap();
+    let started = std::time::Instant::now();
+    assert_eq!(client.get_nonce().unwrap().value.as_deref(), Some("abc"));
+    assert!(started.elapsed() < Duration::from_secs(30));
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    
+    // A Retry-After beyond the maximum delay is returned instead of waited for
+    let (url, requests) = spawn_http_server_with_headers(vec![
+        (429, "Retry-After: 3600\r\n", ""),
+        (200, "", r#"{"data":{"nonce":{"value":"abc","validFor":60}}}"#),
+    ]);
+    let client = WinCCUnifiedClient::builder()
+        .http_url(&url)
+        .retry_policy(RetryPolicy::new(2).with_max_delay(Duration::from_secs(10)))
+        .build()
+        .unwrap();
+    let started = std::time::Instant::now();
+    let error = client.get_nonce().unwrap_err();
+    assert_eq!(error.retry_after(), Some(Duration::from_secs(3600)));
+    assert!(started.elapsed() < Duration::from_secs(5));
+    assert_eq!(requests.lock().unwrap().len(), 1);
+}
+
+#[test]
+fn test_notification_reason() {
+    use winccua_graphql_client::{NotificationReason, ReduStateNotification};
+    
+    let reasons: Vec<NotificationReason> =
+        serde_json::from_value(json!(["Added", "Modified", "Removed", "Removed (Name changed)", "Something else"])).unwrap();
+    assert_eq!(reasons, vec![
+        NotificationReason::Added,
+        NotificationReason::Modified,
+        NotificationReason::Removed,
+        NotificationReason::RemovedNameChanged,
+        NotificationReason::Other("Something else".to_string()),
+    ]);
+    assert_eq!(NotificationReason::RemovedNameChanged.to_string(), "Removed (Name changed)");
+    
+    let notification: ReduStateNotification = serde_json::from_value(json!({"notificationReason": "Modified"})).unwrap();
+    assert_eq!(notification.notification_reason, Some(NotificationReason::Modified));
+}
+
+#[test]
+fn test_write_tag_values_now() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"writeTagValues":[{"name":"HMI_Tag_1"},{"name":"HMI_Tag_2"}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    let inputs = vec![
+        TagValueInput::new("HMI_Tag_1", 1),
+        TagValueInput::new("HMI_Tag_2", 2).with_timestamp("2024-01-01T00:00:00.000Z"),
+    ];
+    let results = client.write_tag_values_now(&inputs).unwrap();
+    assert_eq!(results.len(), 2);
+    
+    let requests = requests.lock().unwrap();
+    let variables = &requests[0]["variables"];
+    let timestamp = variables["timestamp"].as_str().unwrap();
+    assert!(winccua_graphql_client::parse_timestamp(timestamp).is_ok());
+    assert!(timestamp.ends_with('Z'));
+    assert_eq!(timestamp.len(), "2023-12-31T23:59:59.999Z".len());
+    assert_eq!(variables["input"][1]["timestamp"], "2024-01-01T00:00:00.000Z");
+}
+
+#[test]
+fn test_shared_client_token() {
+    fn assert_send_sync<T: Send + Sync + Clone>() {}
+    assert_send_sync::<WinCCUnifiedClient>();
+    
+    let (url, requests, headers) = spawn_http_server_recording_headers(vec![
+        (200, r#"{"data":{"tagValues":[]}}"#),
+        (200, r#"{"data":{"tagValues":[]}}"#),
+    ]);
+    let client = std::sync::Arc::new(WinCCUnifiedClient::new(&url));
+    let clone = client.as_ref().clone();
+    
+    let worker = {
+        let client = client.clone();
+        std::thread::spawn(move || client.set_token("shared-token"))
+    };
+    worker.join().unwrap();
+    
+    // Token changes are visible to clones since they share the session
+    clone.get_tag_values(&["HMI_Tag_1".to_string()], false).unwrap();
+    client.clear_token();
+    clone.get_tag_values(&["HMI_Tag_1".to_string()], false).unwrap();
+    assert_eq!(requests.lock().unwrap().len(), 2);
+    
+    let headers = headers.lock().unwrap();
+    assert_eq!(headers[0].get("authorization").map(String::as_str), Some("Bearer shared-token"));
+    assert_eq!(headers[1].get("authorization"), None);
+}
+
+#[test]
+fn test_active_alarm_field_sets() {
+    use winccua_graphql_client::AlarmFieldSet;
+    
+    let response = r#"{"data":{"activeAlarms":[{"name":"System1::Alarm_1","instanceID":1,"state":"RAISED","priority":10,"raiseTime":"2023-12-31T23:59:59.999Z","eventText":["Overheat"]}]}}"#;
+    let (url, requests) = spawn_http_server(vec![(200, response), (200, response), (200, response)]);
+    let client = WinCCUnifiedClient::new(&url);
+    let languages = ["en-US".to_string()];
+    
+    let summaries = client.get_active_alarms_minimal(&[], "", "en-US", &languages).unwrap();
+    assert_eq!(summaries[0].name.as_deref(), Some("System1::Alarm_1"));
+    assert_eq!(summaries[0].priority, Some(10));
+    
+    let alarms = client.get_active_alarms_with_fields(&[], "", "en-US", &languages, AlarmFieldSet::Standard).unwrap();
+    assert_eq!(alarms[0].instance_id, Some(1));
+    assert!(alarms[0].alarm_text1.is_none());
+    
+    client.get_active_alarms_simple().unwrap();
+    
+    let requests = requests.lock().unwrap();
+    let queries: Vec<&str> = requests.iter().map(|r| r["query"].as_str().unwrap()).collect();
+    assert!(!queries[0].contains("alarmClassName") && !queries[0].contains("alarmText1"));
+    assert!(queries[1].contains("alarmClassName") && !queries[1].contains("alarmText1"));
+    assert!(queries[2].contains("alarmText1"));
+    assert_eq!(AlarmFieldSet::default(), AlarmFieldSet::Full);
+}
+
+#[test]
+fn test_alarm_parameter_values() {
+    use winccua_graphql_client::ActiveAlarm;
+    
+    let alarm: ActiveAlarm = serde_json::from_value(json!({
+        "name": "System1::Alarm_1",
+        "alarmParameterValues": [
+            {"name": "Temperature", "value": 81.5, "type": "Float", "quality": {"quality": "GOOD_CASCADE"}},
+            42,
+            "text",
+            {"name": "Level", "value": 3, "quality": "not a quality"}
+        ]
+    }))
+    .unwrap();
+    
+    let values = alarm.alarm_parameter_values.unwrap();
+    assert_eq!(values.len(), 4);
+    assert_eq!(values[0].name.as_deref(), Some("Temperature"));
+    assert_eq!(values[0].value, Some(json!(81.5)));
+    assert_eq!(values[0].data_type.as_deref(), Some("Float"));
+    assert!(values[0].quality.as_ref().unwrap().is_good());
+    assert_eq!(values[1].value, Some(json!(42)));
+    assert!(values[1].name.is_none());
+    assert_eq!(values[2].value, Some(json!("text")));
+    assert!(values[3].name.is_none());
+    assert_eq!(values[3].value.as_ref().unwrap()["name"], json!("Level"));
+}
+
+#[test]
+fn test_alarm_user_response_and_loop_in_alarm() {
+    use winccua_graphql_client::{ActiveAlarmNotification, AlarmUserResponse};
+    
+    let notification: ActiveAlarmNotification = serde_json::from_value(json!({
+        "name": "System1::Alarm_1",
+        "userResponse": "SINGLE_ACKNOWLEDGEMENT",
+        "loopInAlarm": "ShowScreen",
+        "loopInAlarmParameterValues": [{"name": "Screen", "value": "Overview"}, "Tank1"],
+        "notificationReason": "Added"
+    }))
+    .unwrap();
+    let alarm = notification.alarm;
+    let response = alarm.user_response.unwrap();
+    assert_eq!(response, AlarmUserResponse::SingleAcknowledgement);
+    assert!(response.requires_acknowledgement() && !response.requires_reset());
+    assert_eq!(alarm.loop_in_alarm.as_deref(), Some("ShowScreen"));
+    let parameters = alarm.loop_in_alarm_parameter_values.unwrap();
+    assert_eq!(parameters[0].name.as_deref(), Some("Screen"));
+    assert_eq!(parameters[0].value, Some(json!("Overview")));
+    assert_eq!(parameters[1].value, Some(json!("Tank1")));
+    
+    let alarm: winccua_graphql_client::ActiveAlarm = serde_json::from_value(json!({
+        "userResponse": "FUTURE_RESPONSE",
+        "loopInAlarmParameterValues": {"name": "Screen", "value": "Overview"}
+    }))
+    .unwrap();
+    assert_eq!(alarm.user_response, Some(AlarmUserResponse::Unknown("FUTURE_RESPONSE".to_string())));
+    assert_eq!(alarm.loop_in_alarm_parameter_values.unwrap().len(), 1);
+    
+    let alarm: winccua_graphql_client::ActiveAlarm = serde_json::from_value(json!({"loopInAlarmParameterValues": null})).unwrap();
+    assert!(alarm.loop_in_alarm_parameter_values.is_none() && alarm.user_response.is_none());
+}
+
+#[test]
+fn test_login_error_codes() {
+    use winccua_graphql_client::WinCCError;
+    
+    let (url, _) = spawn_http_server(vec![
+        (200, r#"{"data":{"login":{"token":"abc","expires":"2099-01-01T00:00:00.000Z","error":{"code":"0","description":"Success"}}}}"#),
+        (200, r#"{"data":{"login":{"token":"","error":{"code":"101","description":"Incorrect credentials provided"}}}}"#),
+        (200, r#"{"data":{"login":{"token":"abc","error":{"code":"102","description":"UMC error"}}}}"#),
+        (200, r#"{"data":{"loginSWAC":{"token":null,"error":{"code":"103","description":"Nonce expired"}}}}"#),
+        (200, r#"{"data":{"loginSWAC":{"token":"abc","error":null}}}"#),
+        (200, r#"{"data":{"extendSession":{"token":"","error":{"code":"0"}}}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    let session = client.login("user", "password").unwrap();
+    assert_eq!(session.token.as_deref(), Some("abc"));
+    
+    match client.login("user", "wrong") {
+        Err(WinCCError::LoginError { code, description }) => {
+            assert_eq!(code, 101);
+            assert_eq!(description, "Incorrect credentials provided");
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match client.login("user", "password") {
+        Err(WinCCError::LoginError { code, description }) => assert_eq!((code, description.as_str()), (102, "UMC error")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    match client.login_swac("claim", "signed") {
+        Err(error @ WinCCError::LoginError { .. }) => assert_eq!(error.code(), Some(103)),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(client.login_swac("claim", "signed").is_ok());
+    assert!(matches!(client.extend_session(), Err(WinCCError::SessionError(_))));
+}
+
+#[test]
+fn test_logged_alarms_paged() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"A","instanceID":1,"modificationTime":"2024-01-01T00:00:01.000Z"},
+            {"name":"B","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"}]}}"#),
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"B","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"},
+            {"name":"C","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"}]}}"#),
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"D","instanceID":2,"modificationTime":"2024-01-01T00:00:03.000Z"}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    let languages = ["en-US".to_string()];
+    
+    let pages: Vec<_> = client
+        .logged_alarms_paged(&[], "", "en-US", &languages, Some("2024-01-01T00:00:00.000Z"), None, 2)
+        .collect::<Result<_, _>>()
+        .unwrap();
+    let names: Vec<_> = pages.concat().into_iter().filter_map(|alarm| alarm.name).collect();
+    assert_eq!(names, ["A", "B", "C", "D"]);
+    
+    let requests = requests.lock().unwrap();
+    let start_times: Vec<_> = requests.iter().map(|r| r["variables"]["startTime"].as_str().unwrap().to_string()).collect();
+    assert_eq!(start_times, [
+        "2024-01-01T00:00:00.000Z",
+        "2024-01-01T00:00:01.999Z",
+        "2024-01-01T00:00:01.999Z",
+    ]);
+    assert_eq!(requests[0]["variables"]["maxNumberOfResults"], 2);
+    
+    let mut invalid = client.logged_alarms_paged(&[], "", "en-US", &languages, None, None, 0);
+    assert!(matches!(invalid.next(), Some(Err(winccua_graphql_client::WinCCError::InvalidParameter(_)))));
+    assert!(invalid.next().is_none());    
+    // More alarms at one modificationTime than fit on a page are reported instead of skipped
+    let (url, _) = spawn_http_server(vec![
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"A","instanceID":1,"modificationTime":"2024-01-01T00:00:01.000Z"},
+            {"name":"B","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"}]}}"#),
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"B","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"},
+            {"name":"C","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"}]}}"#),
+        (200, r#"{"data":{"loggedAlarms":[
+            {"name":"B","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"},
+            {"name":"C","instanceID":1,"modificationTime":"2024-01-01T00:00:02.000Z"}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    let mut pages = client.logged_alarms_paged(&[], "", "en-US", &languages, Some("2024-01-01T00:00:00.000Z"), None, 2);
+    assert_eq!(pages.next().unwrap().unwrap().len(), 2);
+    assert_eq!(pages.next().unwrap().unwrap().len(), 1);
+    assert!(matches!(pages.next(), Some(Err(winccua_graphql_client::WinCCError::AlarmError(_)))));
+    assert!(pages.next().is_none());
+}
+
+#[test]
+fn test_mock_transport() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {"token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}}))
+        .with_response(json!({"data": {"tagValues": [{"name": "HMI_Tag_1", "value": {"value": 42, "timestamp": "2024-01-01T00:00:00.000Z"}}]}}))
+        .with_response(json!({"errors": [{"message": "Syntax error", "extensions": {"code": 301}}]}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    assert_eq!(client.login("user", "password").unwrap().token.as_deref(), Some("abc"));
+    let values = client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    assert_eq!(values[0].value.as_ref().and_then(|v| v.as_i64()), Some(42));
+    
+    let error = client.get_active_alarms_simple().unwrap_err();
+    assert_eq!(error.code(), Some(301));
+    assert!(matches!(client.get_active_alarms_simple(), Err(WinCCError::OperationFailed(_))));
+    
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert!(requests[0].0.contains("mutation"));
+    assert_eq!(requests[0].1["username"], "user");
+    assert_eq!(requests[1].1["names"], json!(["HMI_Tag_1"]));
+    assert_eq!(mock.pending_responses(), 0);
+    
+    let batch_mock = MockTransport::new()
+        .with_response(json!({"data": {"browse": []}}))
+        .with_response(json!({"errors": [{"message": "failed"}]}));
+    let client = WinCCUnifiedClient::builder().transport(batch_mock).build().unwrap();
+    let results = client.batch()
+        .add("query { browse { name } }", None)
+        .add("query { browse { name } }", None)
+        .execute()
+        .unwrap();
+    assert!(results[0].is_ok());
+    assert!(results[1].is_err());
+}
+
+#[test]
+fn test_tag_values_partial() {
+    use winccua_graphql_client::MockTransport;
+    
+    let mock = MockTransport::new()
+        .with_response(json!({
+            "data": {"tagValues": [{"name": "HMI_Tag_1", "value": {"value": 1}}, {"name": "HMI_Tag_2", "error": {"code": "2"}}]},
+            "errors": [{"message": "Cannot resolve provided name", "path": ["tagValues", 1], "extensions": {"code": 2}}]
+        }))
+        .with_response(json!({"data": {"tagValues": [{"name": "HMI_Tag_1"}]}}))
+        .with_response(json!({"data": {"tagValues": null}, "errors": [{"message": "Access denied", "extensions": {"code": 401}}]}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    let names = ["HMI_Tag_1".to_string(), "HMI_Tag_2".to_string()];
+    
+    let (values, errors) = client.get_tag_values_partial(&names, false).unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(errors.len(), 1);
+    assert_eq!(errors[0].code(), Some(2));
+    
+    let (values, errors) = client.get_tag_values_partial(&names, false).unwrap();
+    assert_eq!(values.len(), 1);
+    assert!(errors.is_empty());
+    
+    let error = client.get_tag_values_partial(&names, false).unwrap_err();
+    assert_eq!(error.code(), Some(401));
+}
+
+#[test]
+fn test_logging_tags_for() {
+    use winccua_graphql_client::MockTransport;
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"browse": [
+        {"name": "System1::HMI_Tag_1:LoggingTag_1", "objectType": "LOGGINGTAG"},
+        {"name": "System1::HMI_Tag_1:LoggingTag_2", "objectType": "LOGGINGTAG"},
+        {"name": "System1::HMI_Tag_10:LoggingTag_1", "objectType": "LOGGINGTAG"},
+        {"name": "System1::HMI_Tag_1", "objectType": "SIMPLETAG"}
+    ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    let logging_tags = client.get_logging_tags_for("HMI_Tag_1").unwrap();
+    let names: Vec<_> = logging_tags.iter().filter_map(|tag| tag.name.as_deref()).collect();
+    assert_eq!(names, ["System1::HMI_Tag_1:LoggingTag_1", "System1::HMI_Tag_1:LoggingTag_2"]);
+    
+    let variables = &mock.requests()[0].1;
+    assert_eq!(variables["nameFilters"], json!(["HMI_Tag_1:*"]));
+    assert_eq!(variables["objectTypeFilters"], json!(["LOGGINGTAG"]));
+}
+
+#[tokio::test]
+async fn test_ws_idle_timeout() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{ConnectionState, GraphQLWSClient};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        // Keep the connection open without sending anything
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new())
+        .with_idle_timeout(std::time::Duration::from_millis(300));
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let mut state = ws_client.state();
+    let disconnected = tokio::time::timeout(
+        std::time::Duration::from_secs(5),
+        state.wait_for(|s| *s == ConnectionState::Disconnected),
+    ).await;
+    assert!(disconnected.is_ok());
+}
+
+#[tokio::test]
+async fn test_ws_subscribe_stream() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, WinCCError};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let id = next_json(&mut ws).await["id"].clone();
+        for value in [1, 2] {
+            ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": {"tagValues": {"value": value}}}}).to_string())).await.unwrap();
+        }
+        ws.send(Message::Text(json!({"type": "complete", "id": id}).to_string())).await.unwrap();
+        
+        let id = next_json(&mut ws).await["id"].clone();
+        ws.send(Message::Text(json!({"type": "error", "id": id, "payload": [{"message": "Cannot resolve provided name"}]}).to_string())).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let stream = ws_client.subscribe_stream("subscription { tagValues }".to_string(), Default::default()).await.unwrap();
+    let items: Vec<_> = tokio::time::timeout(std::time::Duration::from_secs(5), stream.collect::<Vec<_>>()).await.unwrap();
+    let values: Vec<_> = items.into_iter().map(|item| item.unwrap()["data"]["tagValues"]["value"].clone()).collect();
+    assert_eq!(values, [json!(1), json!(2)]);
+    
+    let mut stream = ws_client.subscribe_stream("subscription { tagValues }".to_string(), Default::default()).await.unwrap();
+    let first = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next()).await.unwrap();
+    assert!(matches!(first, Some(Err(WinCCError::WebSocketError(message))) if message.contains("Cannot resolve")));
+    assert!(stream.next().await.is_none());
+    
+    ws_client.disconnect().await;
+}
+
+#[test]
+fn test_write_single_tag() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"writeTagValues": [{"name": "HMI_Tag_1", "error": {"code": "0"}}]}}))
+        .with_response(json!({"data": {"writeTagValues": [{"name": "HMI_Tag_2", "error": {"code": "2", "description": "Cannot resolve provided name"}}]}}))
+        .with_response(json!({"data": {"writeTagValues": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    client.write_tag("HMI_Tag_1", 42).unwrap();
+    assert_eq!(mock.requests()[0].1["input"], json!([{"name": "HMI_Tag_1", "value": 42}]));
+    
+    match client.write_tag("HMI_Tag_2", 1.5) {
+        Err(WinCCError::TagError(message)) => assert!(message.contains("Cannot resolve provided name") && message.contains("code 2")),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(matches!(client.write_tag("HMI_Tag_3", "text"), Err(WinCCError::TagError(_))));
+}
+
+#[tokio::test]
+async fn test_ws_subscription_metadata() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let id = next_json(&mut ws).await["id"].clone();
+        let _anonymous = next_json(&mut ws).await;
+        ws.send(Message::Text(json!({"type": "complete", "id": id}).to_string())).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let tag_values = ws_client
+        .subscribe(subscriptions::TAG_VALUES.to_string(), Default::default(), SubscriptionCallbacks::new(|_| {}))
+        .await
+        .unwrap();
+    let anonymous = ws_client
+        .subscribe("subscription { reduState { value { value } } }".to_string(), Default::default(), SubscriptionCallbacks::new(|_| {}))
+        .await
+        .unwrap();
+    
+    assert!(tag_values.id().starts_with("sub_"));
+    assert_ne!(tag_values.id(), anonymous.id());
+    assert_eq!(tag_values.operation_name(), Some("TagValues"));
+    assert_eq!(anonymous.operation_name(), None);
+    assert!(anonymous.is_active());
+    
+    let started = std::time::Instant::now();
+    while tag_values.is_active() {
+        assert!(started.elapsed() < std::time::Duration::from_secs(5));
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    assert!(anonymous.is_active());
+    
+    ws_client.disconnect().await;
+    assert!(!anonymous.is_active());
+}
+
+#[test]
+fn test_operation_name() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"tagValues":[]}}"#),
+        (200, r#"{"data":{"browse":[]}}"#),
+        (200, r#"{"data":{"browse":[]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    client.execute_raw("{ browse { name } }", None).unwrap();
+    client.execute_named(
+        "BrowseAll",
+        "query BrowseAll { browse { name } } query BrowseTags { browse(objectTypeFilters: [TAG]) { name } }",
+        None,
+    ).unwrap();
+    
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0]["operationName"], "TagValues");
+    assert!(requests[1].get("operationName").is_none());
+    assert_eq!(requests[2]["operationName"], "BrowseAll");
+    
+    // Custom transports receive the same operation names
+    let mock = winccua_graphql_client::MockTransport::new()
+        .with_response(json!({"data": {"tagValues": []}}))
+        .with_response(json!({"data": {"browse": []}}))
+        .with_response(json!({"data": {"browse": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    client.execute_raw("{ browse { name } }", None).unwrap();
+    client.execute_named(
+        "BrowseAll",
+        "query BrowseAll { browse { name } } query BrowseTags { browse(objectTypeFilters: [TAG]) { name } }",
+        None,
+    ).unwrap();
+    assert_eq!(mock.operation_names(), [Some("TagValues".to_string()), None, Some("BrowseAll".to_string())]);
+}
+
+#[test]
+fn test_ping() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"session": [{"expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}]}}))
+        .with_response(json!({"data": null, "errors": [{"message": "Unauthorized"}]}))
+        .with_response(json!({"data": {"session": [{"expires": null, "error": {"code": "1", "description": "Session expired"}}]}}))
+        .with_response(json!({"data": {"session": []}}))
+        .with_response(json!({"errors": [{"message": "Internal error"}]}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    assert!(client.ping().unwrap());
+    assert!(matches!(client.ping(), Err(WinCCError::AuthenticationError(_))));
+    match client.ping() {
+        Err(WinCCError::AuthenticationError(message)) => assert_eq!(message, "1 - Session expired"),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert!(matches!(client.ping(), Err(WinCCError::AuthenticationError(_))));
+    assert!(!client.ping().unwrap());
+    assert!(mock.requests()[0].0.contains("session(allSessions: false)"));
+    
+    let (url, _) = spawn_http_server(vec![(401, "")]);
+    let client = WinCCUnifiedClient::new(&url);
+    assert!(matches!(client.ping(), Err(WinCCError::AuthenticationError(_))));
+}
+
+#[test]
+fn test_token_expiry() {
+    use std::time::Duration;
+    use winccua_graphql_client::MockTransport;
+    
+    let soon = (chrono::Utc::now() + chrono::Duration::seconds(10)).to_rfc3339();
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {"token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}}))
+        .with_response(json!({"data": {"extendSession": {"token": "def", "expires": soon, "error": {"code": "0"}}}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    assert!(client.expires_at().is_none());
+    assert!(client.is_token_expired());
+    
+    client.login("user", "password").unwrap();
+    assert_eq!(client.expires_at().unwrap().to_rfc3339(), "2099-01-01T00:00:00+00:00");
+    assert!(!client.is_token_expired());
+    
+    // Expires in 10s, within the default skew of 30s
+    client.extend_session().unwrap();
+    assert!(client.is_token_expired());
+    
+    let client = WinCCUnifiedClient::builder()
+        .transport(mock)
+        .token_expiry_skew(Duration::from_secs(1))
+        .build()
+        .unwrap();
+    client.set_token("ghi");
+    assert!(client.expires_at().is_none());
+    assert!(!client.is_token_expired());
+    client.clear_token();
+    assert!(client.is_token_expired());
+}
+
+#[test]
+fn test_default_languages() {
+    use winccua_graphql_client::MockTransport;
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"activeAlarms": []}}))
+        .with_response(json!({"data": {"activeAlarms": []}}))
+        .with_response(json!({"data": {"loggedAlarms": []}}))
+        .with_response(json!({"data": {"browse": []}}))
+        .with_response(json!({"data": {"activeAlarms": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    assert_eq!(client.default_languages(), ["en-US".to_string()]);
+    assert_eq!(client.browse_language(), "en-US");
+    
+    client.get_active_alarms_simple().unwrap();
+    // Clones share the language settings
+    client.clone().set_default_languages(&["de-DE".to_string(), "en-US".to_string()]);
+    client.clone().set_browse_language("de-DE");
+    client.get_active_alarms_simple().unwrap();
+    client.get_logged_alarms_simple().unwrap();
+    client.browse_simple().unwrap();
+    client.get_active_alarms(&[], "", "fr-FR", &["fr-FR".to_string()]).unwrap();
+    
+    let requests = mock.requests();
+    assert_eq!(requests[0].1["languages"], json!(["en-US"]));
+    assert_eq!(requests[1].1["languages"], json!(["de-DE", "en-US"]));
+    assert_eq!(requests[1].1["filterLanguage"], "de-DE");
+    assert_eq!(requests[2].1["languages"], json!(["de-DE", "en-US"]));
+    assert_eq!(requests[3].1["language"], "de-DE");
+    assert_eq!(requests[4].1["languages"], json!(["fr-FR"]));
+    
+    client.set_default_languages(&[]);
+    assert_eq!(client.default_languages(), ["en-US".to_string()]);
+    assert_eq!(client.browse_language(), "de-DE");
+}
+
+#[tokio::test]
+async fn test_ws_token_update_keeps_subscriptions() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["payload"]["Authorization"], "Bearer old");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let subscribe = next_json(&mut ws).await;
+        let id = subscribe["id"].clone();
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 1}}).to_string())).await.unwrap();
+        
+        // The token update opens a new connection that replays the subscription
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["payload"]["Authorization"], "Bearer new");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let resubscribe = next_json(&mut ws).await;
+        assert_eq!(resubscribe["id"], id);
+        assert_eq!(resubscribe["payload"], subscribe["payload"]);
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 2}}).to_string())).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, "old".to_string());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut variables = std::collections::HashMap::new();
+    variables.insert("names".to_string(), json!(["HMI_Tag_1"]));
+    let subscription = ws_client
+        .subscribe(subscriptions::TAG_VALUES.to_string(), variables, SubscriptionCallbacks::new(move |payload| {
+            let _ = tx.send(payload["data"].clone());
+        }))
+        .await
+        .unwrap();
+    
+    let timeout = std::time::Duration::from_secs(5);
+    assert_eq!(tokio::time::timeout(timeout, rx.recv()).await.unwrap(), Some(json!(1)));
+    ws_client.update_token("new".to_string());
+    assert_eq!(tokio::time::timeout(timeout, rx.recv()).await.unwrap(), Some(json!(2)));
+    assert!(subscription.is_active());
+    
+    ws_client.disconnect().await;
+    server.abort();
+}
+
+#[test]
+fn test_ws_subscribe_blocking() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, GraphQLWSClient, SubscriptionCallbacks};
+    
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = runtime.spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let id = next_json(&mut ws).await["id"].clone();
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 1}}).to_string())).await.unwrap();
+        next_json(&mut ws).await
+    });
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let subscription = GraphQLWSClient::new(url, String::new())
+        .subscribe_blocking(
+            subscriptions::TAG_VALUES.to_string(),
+            Default::default(),
+            SubscriptionCallbacks::new(move |payload| {
+                let _ = tx.send((payload["data"].clone(), std::thread::current().name().map(str::to_string)));
+            }),
+        )
+        .unwrap();
+    assert_eq!(subscription.operation_name(), Some("TagValues"));
+    assert!(subscription.is_active());
+    
+    let (data, thread) = rx.recv_timeout(std::time::Duration::from_secs(5)).unwrap();
+    assert_eq!(data, json!(1));
+    assert_eq!(thread.as_deref(), Some("winccua-dispatch"));
+    
+    let id = subscription.id().to_string();
+    subscription.stop();
+    let complete = runtime.block_on(server).unwrap();
+    assert_eq!(complete["type"], "complete");
+    assert_eq!(complete["id"], id);
+    
+    let client = WinCCUnifiedClient::new("http://localhost:1/graphql");
+    assert!(client.subscribe_blocking(subscriptions::TAG_VALUES, Default::default(), SubscriptionCallbacks::new(|_| {})).is_err());
+}
+
+#[test]
+fn test_tag_values_strict() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"tagValues": [
+            {"name": "HMI_Tag_1", "value": {"value": 1}, "error": {"code": "0"}},
+            {"name": "HMI_Tag_2", "value": {"value": 2}}
+        ]}}))
+        .with_response(json!({"data": {"tagValues": [
+            {"name": "HMI_Tag_1", "value": {"value": 1}},
+            {"name": "HMI_Tag_X", "error": {"code": "2", "description": "Cannot resolve provided name"}},
+            {"name": "HMI_Tag_3"}
+        ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let names = ["HMI_Tag_1".to_string(), "HMI_Tag_2".to_string()];
+    
+    let values = client.get_tag_values_strict(&names, true).unwrap();
+    assert_eq!(values.len(), 2);
+    assert_eq!(values[1].0, "HMI_Tag_2");
+    assert_eq!(values[1].1.value, Some(json!(2)));
+    assert_eq!(mock.requests()[0].1["directRead"], true);
+    
+    match client.get_tag_values_strict(&names, false) {
+        Err(WinCCError::TagError(message)) => {
+            assert_eq!(message, "Failed to read tags: HMI_Tag_X: Cannot resolve provided name (code 2); HMI_Tag_3: no value");
+            assert!(!message.contains("HMI_Tag_1"), "{}", message);
+        }
+        other => panic!("unexpected result: {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_ws_close_info() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::protocol::frame::{coding::CloseCode, CloseFrame};
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, CloseInfo, GraphQLWSClient, SubscriptionCallbacks, WinCCError};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        next_json(&mut ws).await;
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        next_json(&mut ws).await;
+        next_json(&mut ws).await;
+        let frame = CloseFrame { code: CloseCode::from(4401), reason: "Unauthorized".into() };
+        ws.close(Some(frame)).await.unwrap();
+        
+        // Rejected before connection_ack
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        next_json(&mut ws).await;
+        let frame = CloseFrame { code: CloseCode::from(4403), reason: "Forbidden".into() };
+        ws.close(Some(frame)).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url.clone(), "token".to_string());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    assert!(ws_client.close_info().is_none());
+    
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let error_tx = tx.clone();
+    let _subscription = ws_client
+        .subscribe(
+            subscriptions::TAG_VALUES.to_string(),
+            Default::default(),
+            SubscriptionCallbacks::new(|_| {})
+                .with_close(move |close_info| { let _ = tx.send(format!("close {}", close_info)); })
+                .with_error(move |e| { let _ = error_tx.send(e); }),
+        )
+        .await
+        .unwrap();
+    let mut stream = ws_client.subscribe_stream(subscriptions::TAG_VALUES.to_string(), Default::default()).await.unwrap();
+    
+    let timeout = std::time::Duration::from_secs(5);
+    match tokio::time::timeout(timeout, stream.next()).await.unwrap() {
+        Some(Err(WinCCError::AuthenticationError(message))) => assert!(message.contains("4401: Unauthorized"), "{}", message),
+        other => panic!("unexpected item: {:?}", other),
+    }
+    assert!(tokio::time::timeout(timeout, stream.next()).await.unwrap().is_none());
+    assert_eq!(rx.recv().await.unwrap(), "close 4401: Unauthorized");
+    assert_eq!(rx.recv().await.unwrap(), "WebSocket connection closed by server (4401: Unauthorized)");
+    assert_eq!(ws_client.close_info(), Some(CloseInfo { code: 4401, reason: "Unauthorized".to_string() }));
+    assert!(ws_client.close_info().unwrap().is_unauthorized());
+    ws_client.disconnect().await;
+    
+    let mut ws_client = GraphQLWSClient::new(url, "token".to_string());
+    let error = ws_client.connect_and_wait(timeout).await.unwrap_err();
+    assert!(matches!(error, WinCCError::AuthenticationError(_)), "{:?}", error);
+    assert_eq!(ws_client.close_info().map(|c| c.code), Some(4403));
+}
+
+#[test]
+fn test_alarm_filter_builder() {
+    use winccua_graphql_client::{AlarmFilterBuilder, MockTransport};
+    
+    assert_eq!(AlarmFilterBuilder::new().build(), "");
+    let filter = AlarmFilterBuilder::new()
+        .name("System1::Motor's Alarm")
+        .priority_lte(5)
+        .alarm_class("Warning")
+        .raw("origin = 'A' OR origin = 'B'");
+    assert_eq!(
+        filter.to_string(),
+        "name = 'System1::Motor''s Alarm' AND priority <= 5 AND alarmClassName = 'Warning' AND (origin = 'A' OR origin = 'B')"
+    );
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"activeAlarms": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    client.get_active_alarms_filtered(&AlarmFilterBuilder::new().priority_gte(10).state("RAISED")).unwrap();
+    assert_eq!(mock.requests()[0].1["filterString"], "priority >= 10 AND state = 'RAISED'");
+}
+
+#[test]
+fn test_subscription_callbacks_fn_mut() {
+    use winccua_graphql_client::{SubscriptionCallbacks, TagValueNotification, TypedSubscriptionCallbacks};
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let mut total = 0;
+    let callbacks = SubscriptionCallbacks::new_mut(move |payload| {
+        total += payload["data"].as_i64().unwrap();
+        tx.send(total).unwrap();
+    });
+    (callbacks.on_data)(json!({"data": 2}));
+    (callbacks.clone().on_data)(json!({"data": 3}));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![2, 5]);
+    
+    let mut names = Vec::new();
+    let (tx, rx) = std::sync::mpsc::channel();
+    let callbacks = TypedSubscriptionCallbacks::<TagValueNotification>::new_mut(move |notification| {
+        names.push(notification.name.unwrap_or_default());
+        tx.send(names.len()).unwrap();
+    })
+    .into_callbacks("tagValues");
+    (callbacks.on_data)(json!({"data": {"tagValues": {"name": "HMI_Tag_1"}}}));
+    (callbacks.on_data)(json!({"data": {"tagValues": {"name": "HMI_Tag_2"}}}));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![1, 2]);
+}
+
+#[test]
+fn test_url_validation() {
+    use winccua_graphql_client::{GraphQLWSClient, WinCCError};
+    
+    let err = WinCCUnifiedClient::builder().http_url("ws://localhost:4000/graphql").build().err().unwrap();
+    assert!(matches!(&err, WinCCError::InvalidParameter(msg) if msg.contains("http_url must use http:// or https://")));
+    
+    let err = WinCCUnifiedClient::builder()
+        .http_url("https://localhost:4000/graphql")
+        .ws_url("http://localhost:4000/graphql")
+        .build()
+        .err()
+        .unwrap();
+    assert!(matches!(&err, WinCCError::InvalidParameter(msg) if msg.contains("ws_url must use ws:// or wss://")));
+    
+    let err = WinCCUnifiedClient::builder().http_url("localhost:4000").build().err().unwrap();
+    assert!(matches!(err, WinCCError::InvalidParameter(_)));
+    assert!(matches!(WinCCUnifiedClient::try_new("localhost:4000"), Err(WinCCError::InvalidParameter(_))));
+    assert!(matches!(
+        WinCCUnifiedClient::try_new_with_ws("https://localhost:4000/graphql", "localhost:4000"),
+        Err(WinCCError::InvalidParameter(_))
+    ));
+    assert!(WinCCUnifiedClient::try_new_with_ws("https://localhost:4000/graphql", "wss://localhost:4000/graphql").is_ok());
+    
+    assert!(WinCCUnifiedClient::builder()
+        .http_url("HTTPS://localhost:4000/graphql")
+        .ws_url("WSS://localhost:4000/graphql")
+        .build()
+        .is_ok());
+    
+    let mut ws = GraphQLWSClient::new("https://localhost:4000/graphql".to_string(), "token".to_string());
+    let runtime = tokio::runtime::Builder::new_current_thread().enable_all().build().unwrap();
+    let err = runtime.block_on(ws.connect()).unwrap_err();
+    assert!(matches!(&err, WinCCError::InvalidParameter(msg) if msg.contains("ws_url must use ws:// or wss://")));
+}
+
+#[test]
+#[should_panic(expected = "Cannot create client for localhost:4000")]
+fn test_new_panics_with_url() {
+    WinCCUnifiedClient::new("localhost:4000");
+}
+
+#[test]
+fn test_browse_children_and_read_structure() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let browse_struct = json!({"data": {"browse": [
+        {"name": "HMI_Struct.Speed", "objectType": "SIMPLETAG", "dataType": "Int32"},
+        {"name": "HMI_Struct.Motor", "objectType": "STRUCTURETAG"},
+        {"name": "HMI_Struct.Motor.Running", "objectType": "SIMPLETAG", "dataType": "Bool"},
+        {"name": "HMI_Struct.Speed:Log", "objectType": "LOGGINGTAG"}
+    ]}});
+    
+    let mock = MockTransport::new().with_response(browse_struct.clone());
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let children = client.browse_children("HMI_Struct").unwrap();
+    let names: Vec<_> = children.iter().filter_map(|c| c.name.as_deref()).collect();
+    assert_eq!(names, vec!["HMI_Struct.Speed", "HMI_Struct.Motor", "HMI_Struct.Speed:Log"]);
+    assert_eq!(mock.requests()[0].1["nameFilters"], json!(["HMI_Struct.*"]));
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"browse": [
+        {"name": "System1::HMI_Tag_1", "objectType": "SIMPLETAG"}
+    ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    assert_eq!(client.browse_children("System1::").unwrap().len(), 1);
+    assert_eq!(mock.requests()[0].1["nameFilters"], json!(["System1::*"]));
+    assert!(matches!(client.browse_children(""), Err(WinCCError::InvalidParameter(_))));
+    
+    let mock = MockTransport::new()
+        .with_response(browse_struct)
+        .with_response(json!({"data": {"browse": [
+            {"name": "System1::HMI_Struct.Motor.Running", "objectType": "SIMPLETAG"}
+        ]}}))
+        .with_response(json!({"data": {"tagValues": [
+            {"name": "HMI_Struct.Speed", "value": {"value": 1200}},
+            {"name": "System1::HMI_Struct.Motor.Running", "value": {"value": true}}
+        ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let values = client.read_structure("HMI_Struct").unwrap();
+    assert_eq!(values.len(), 2);
+    let requests = mock.requests();
+    assert_eq!(requests[1].1["nameFilters"], json!(["HMI_Struct.Motor.*"]));
+    assert_eq!(requests[2].1["names"], json!(["HMI_Struct.Speed", "System1::HMI_Struct.Motor.Running"]));
+    assert_eq!(requests[2].1["directRead"], json!(false));
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"browse": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    assert!(client.read_structure("HMI_Struct").unwrap().is_empty());
+    assert_eq!(mock.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn test_ws_slow_callback_does_not_stall_connection() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, OverflowPolicy, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let (first_delivered_tx, first_delivered_rx) = tokio::sync::oneshot::channel::<()>();
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let slow = next_json(&mut ws).await["id"].clone();
+        let fast = next_json(&mut ws).await["id"].clone();
+        
+        ws.send(Message::Text(json!({"type": "next", "id": slow, "payload": {"data": 1}}).to_string())).await.unwrap();
+        first_delivered_rx.await.unwrap();
+        for value in 2..=4 {
+            ws.send(Message::Text(json!({"type": "next", "id": slow, "payload": {"data": value}}).to_string())).await.unwrap();
+        }
+        ws.send(Message::Text(json!({"type": "next", "id": fast, "payload": {"data": "fast"}}).to_string())).await.unwrap();
+        let _ = next_json(&mut ws).await;
+    });
+    
+    // Lossless delivery is the default, dropping is opt-in
+    assert_eq!(OverflowPolicy::default(), OverflowPolicy::Backpressure);
+    let mut ws_client = GraphQLWSClient::new(url, String::new())
+        .with_dispatch_buffer(1)
+        .with_overflow_policy(OverflowPolicy::DropOldest);
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let (slow_tx, mut slow_rx) = tokio::sync::mpsc::unbounded_channel();
+    let (release_tx, release_rx) = std::sync::mpsc::channel::<()>();
+    let slow = SubscriptionCallbacks::new_mut(move |payload| {
+        let first = payload["data"] == json!(1);
+        let _ = slow_tx.send(payload["data"].clone());
+        if first {
+            let _ = release_rx.recv();
+        }
+    });
+    let (fast_tx, mut fast_rx) = tokio::sync::mpsc::unbounded_channel();
+    let fast = SubscriptionCallbacks::new(move |payload| {
+        let _ = fast_tx.send(payload["data"].clone());
+    });
+    ws_client.subscribe("subscription Slow { tagValues }".to_string(), Default::default(), slow).await.unwrap();
+    ws_client.subscribe("subscription Fast { tagValues }".to_string(), Default::default(), fast).await.unwrap();
+    
+    let timeout = std::time::Duration::from_secs(5);
+    assert_eq!(tokio::time::timeout(timeout, slow_rx.recv()).await.unwrap(), Some(json!(1)));
+    first_delivered_tx.send(()).unwrap();
+    // Delivered while the slow callback is still blocked
+    assert_eq!(tokio::time::timeout(timeout, fast_rx.recv()).await.unwrap(), Some(json!("fast")));
+    
+    release_tx.send(()).unwrap();
+    // Buffer of one: 2 and 3 were dropped in favour of the latest notification
+    assert_eq!(tokio::time::timeout(timeout, slow_rx.recv()).await.unwrap(), Some(json!(4)));
+    
+    ws_client.disconnect().await;
+    server.abort();
+}
+
+#[test]
+fn test_get_redu_state() {
+    use futures_util::SinkExt;
+    use tokio_tungstenite::tungstenite::Message;
+    
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = runtime.spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let subscribe = next_json(&mut ws).await;
+        assert!(subscribe["payload"]["query"].as_str().unwrap().contains("reduState"));
+        let id = subscribe["id"].clone();
+        let payload = json!({"data": {"reduState": {
+            "value": {"value": "ACTIVE", "timestamp": "2024-01-01T00:00:00.000Z"},
+            "notificationReason": "ADDED"
+        }}});
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": payload}).to_string())).await.unwrap();
+        assert_eq!(next_json(&mut ws).await, json!({"type": "complete", "id": id}));
+        
+        // Second subscription: no notification, the caller times out
+        let id = next_json(&mut ws).await["id"].clone();
+        assert_eq!(next_json(&mut ws).await, json!({"type": "complete", "id": id}));
+    });
+    
+    let mut client = WinCCUnifiedClient::new_with_ws("http://localhost:1/graphql", &url);
+    runtime.block_on(async {
+        client.connect_ws_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+        let state = client.get_redu_state(std::time::Duration::from_secs(5)).await.unwrap();
+        assert_eq!(state.value.as_deref(), Some("ACTIVE"));
+        
+        let err = client.get_redu_state(std::time::Duration::from_millis(100)).await.unwrap_err();
+        assert!(matches!(err, winccua_graphql_client::WinCCError::OperationFailed(_)));
+        server.await.unwrap();
+        client.disconnect_ws().await;
+    });
+}
+
+#[tokio::test]
+async fn test_ws_client_drop_closes_connection() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        assert_eq!(next_json(&mut ws).await["type"], "subscribe");
+        // The client closes the connection when it is dropped
+        loop {
+            match ws.next().await {
+                Some(Ok(Message::Close(_))) | None | Some(Err(_)) => break,
+                Some(Ok(_)) => continue,
+            }
+        }
+    });
+    
+    let (error_tx, error_rx) = std::sync::mpsc::channel();
+    let mut ws_client = GraphQLWSClient::new(url, String::new());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    let subscription = ws_client
+        .subscribe(
+            "subscription { tagValues }".to_string(),
+            Default::default(),
+            SubscriptionCallbacks::new(|_| {}).with_error(move |e| {
+                let _ = error_tx.send(e);
+            }),
+        )
+        .await
+        .unwrap();
+    drop(ws_client);
+    
+    tokio::time::timeout(std::time::Duration::from_secs(5), server).await.unwrap().unwrap();
+    let error = tokio::task::spawn_blocking(move || error_rx.recv_timeout(std::time::Duration::from_secs(5)))
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(error.contains("closed"));
+    while subscription.is_active() {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+}
+
+#[test]
+fn test_run_cancellable() {
+    use winccua_graphql_client::{CancelToken, MockTransport, WinCCError};
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"tagValues": [{"name": "HMI_Tag_1", "value": {"value": 1}}]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let token = CancelToken::new();
+    let values = client
+        .run_cancellable(&token, |client| client.get_tag_values_simple(&["HMI_Tag_1".to_string()]))
+        .unwrap();
+    assert_eq!(values.len(), 1);
+    
+    token.cancel();
+    let result = client.run_cancellable(&token, |client| client.get_tag_values_simple(&["HMI_Tag_1".to_string()]));
+    assert!(matches!(result, Err(WinCCError::Cancelled)));
+    assert_eq!(mock.requests().len(), 1);
+    
+    // Detached requests must be bounded by a request timeout
+    let client = WinCCUnifiedClient::new("http://127.0.0.1:1/graphql");
+    let result = client.run_cancellable(&CancelToken::new(), |client| client.get_tag_values_simple(&["HMI_Tag_1".to_string()]));
+    assert!(matches!(result, Err(WinCCError::InvalidParameter(_))));
+    
+    // A server that accepts the request but never answers
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let client = WinCCUnifiedClient::builder()
+        .http_url(&format!("http://{}/graphql", listener.local_addr().unwrap()))
+        .request_timeout(std::time::Duration::from_secs(10))
+        .build()
+        .ok()
+        .unwrap();
+    let token = CancelToken::new();
+    let ui_token = token.clone();
+    std::thread::spawn(move || {
+        let _connection = listener.accept();
+        std::thread::sleep(std::time::Duration::from_millis(100));
+        ui_token.cancel();
+        std::thread::sleep(std::time::Duration::from_secs(10));
+    });
+    
+    let started = std::time::Instant::now();
+    let result = client.run_cancellable(&token, |client| client.get_tag_values_simple(&["HMI_Tag_1".to_string()]));
+    assert!(matches!(result, Err(WinCCError::Cancelled)));
+    assert!(started.elapsed() < std::time::Duration::from_secs(5));
+}
+
+#[test]
+fn test_alarm_text_for_language() {
+    use winccua_graphql_client::{ActiveAlarm, AlarmTextField, LoggedAlarm};
+    
+    let alarm: ActiveAlarm = serde_json::from_value(json!({
+        "languages": ["en-US", "de-DE"],
+        "eventText": ["Motor overload", "Motorüberlast"],
+        "alarmText3": ["Line 1", "Linie 1"],
+        "stateText": ["Raised"]
+    }))
+    .unwrap();
+    assert_eq!(alarm.text_for_language(AlarmTextField::EventText, "de-DE"), Some("Motorüberlast"));
+    assert_eq!(alarm.text_for_language(AlarmTextField::EventText, "en-us"), Some("Motor overload"));
+    assert_eq!(alarm.text_for_language(AlarmTextField::AlarmText3, "en-US"), Some("Line 1"));
+    assert_eq!(alarm.text_for_language(AlarmTextField::StateText, "de-DE"), None);
+    assert_eq!(alarm.text_for_language(AlarmTextField::InfoText, "en-US"), None);
+    assert_eq!(alarm.text_for_language(AlarmTextField::EventText, "fr-FR"), None);
+    
+    let logged: LoggedAlarm = serde_json::from_value(json!({"eventText": ["Motor overload"]})).unwrap();
+    assert_eq!(logged.text_for_language(AlarmTextField::EventText, "en-US"), None);
+}
+
+#[test]
+fn test_shared_http_client_keeps_tokens_per_client() {
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::sync::{Arc, Mutex};
+    
+    // Keep-alive server that records the number of connections and each Authorization header
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let connections = Arc::new(Mutex::new(0));
+    let authorizations = Arc::new(Mutex::new(Vec::new()));
+    let (thread_connections, thread_authorizations) = (connections.clone(), authorizations.clone());
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { return };
+            *thread_connections.lock().unwrap() += 1;
+            let authorizations = thread_authorizations.clone();
+            std::thread::spawn(move || {
+                let mut reader = BufReader::new(stream);
+                loop {
+                    let mut content_length = 0;
+                    loop {
+                        let mut line = String::new();
+                        if reader.read_line(&mut line).unwrap_or(0) == 0 {
+                            return;
+                        }
+                        if line == "\r\n" {
+                            break;
+                        }
+                        let lower = line.to_ascii_lowercase();
+                        if let Some(value) = lower.strip_prefix("content-length:") {
+                            content_length = value.trim().parse().unwrap_or(0);
+                        }
+                        if lower.starts_with("authorization:") {
+                            authorizations.lock().unwrap().push(line["authorization:".len()..].trim().to_string());
+                        }
+                    }
+                    let mut body = vec![0u8; content_length];
+                    let _ = reader.read_exact(&mut body);
+                    let response = r#"{"data":{"tagValues":[]}}"#;
+                    let _ = write!(
+                        reader.get_mut(),
+                        "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\n\r\n{}",
+                        response.len(),
+                        response
+                    );
+                }
+            });
+        }
+    });
+    
+    let shared = reqwest::blocking::Client::new();
+    let tenant_a = WinCCUnifiedClient::builder().http_url(&url).http_client(shared.clone()).token("token-a").build().ok().unwrap();
+    let tenant_b = WinCCUnifiedClient::builder().http_url(&url).http_client(shared).token("token-b").build().ok().unwrap();
+    tenant_a.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    tenant_b.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    tenant_a.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    
+    assert_eq!(*authorizations.lock().unwrap(), vec!["Bearer token-a", "Bearer token-b", "Bearer token-a"]);
+    assert_eq!(*connections.lock().unwrap(), 1);
+    
+    assert!(WinCCUnifiedClient::builder().http_url(&url).pool_max_idle_per_host(2).build().is_ok());
+}
+
+#[test]
+fn test_acknowledge_matching() {
+    use winccua_graphql_client::MockTransport;
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"activeAlarms": [
+            {"name": "System1::Motor_1:Overload", "instanceID": 1},
+            {"name": "System1::Motor_2:Overload", "instanceID": 4}
+        ]}}))
+        .with_response(json!({"data": {"acknowledgeAlarms": [
+            {"alarmName": "System1::Motor_1:Overload", "alarmInstanceID": 1, "error": {"code": "0"}},
+            {"alarmName": "System1::Motor_2:Overload", "alarmInstanceID": 4, "error": {"code": "305", "description": "Alarm cannot be acknowledged in current state"}}
+        ]}}))
+        .with_response(json!({"data": {"activeAlarms": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    let results = client.acknowledge_matching("area = 'Plant1'").unwrap();
+    assert_eq!(results.len(), 2);
+    assert_eq!(results[1].error.as_ref().and_then(|e| e.code.as_deref()), Some("305"));
+    
+    let requests = mock.requests();
+    assert_eq!(requests[0].1["filterString"], "area = 'Plant1'");
+    assert_eq!(requests[1].1["input"], json!([
+        {"name": "System1::Motor_1:Overload", "instanceID": 1},
+        {"name": "System1::Motor_2:Overload", "instanceID": 4}
+    ]));
+    
+    assert!(client.acknowledge_matching("area = 'Empty'").unwrap().is_empty());
+    assert_eq!(mock.requests().len(), 3);
+}
+
+#[test]
+fn test_server_error_details() {
+    use winccua_graphql_client::{RetryPolicy, WinCCError};
+    
+    let long_body: &'static str = Box::leak("x".repeat(10_000).into_boxed_str());
+    let (url, _) = spawn_http_server_with_headers(vec![
+        (500, "X-Request-Id: req-42\r\n", "gateway error"),
+        (404, "", long_body),
+    ]);
+    let client = WinCCUnifiedClient::builder()
+        .http_url(&url)
+        .retry_policy(RetryPolicy::new(1))
+        .build()
+        .unwrap();
+    
+    let error = client.get_nonce().err().unwrap();
+    assert_eq!(error.status(), Some(500));
+    match &error {
+        WinCCError::ServerError { status, request_id, body } => {
+            assert_eq!(*status, 500);
+            assert_eq!(request_id.as_deref(), Some("req-42"));
+            assert_eq!(body, "gateway error");
+        }
+        other => panic!("unexpected error: {:?}", other),
+    }
+    assert_eq!(error.to_string(), "Server returned HTTP 500 (request id req-42): gateway error");
+    
+    // Large bodies are truncated
+    match client.get_nonce() {
+        Err(WinCCError::ServerError { status: 404, request_id: None, body }) => {
+            assert!(body.len() < 4200);
+            assert!(body.ends_with("(truncated)"));
+        }
+        other => panic!("unexpected result: {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn test_browse_object_type() {
+    use winccua_graphql_client::{BrowseTagsResult, MockTransport, ObjectType};
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"browse": [
+        {"name": "HMI_Tag_1", "objectType": "SIMPLETAG"},
+        {"name": "HMI_Struct", "objectType": "STRUCTURETAG"},
+        {"name": "HMI_Tag_1:Log", "objectType": "LOGGINGTAG"},
+        {"name": "Alarm_1", "objectType": "ALARM"},
+        {"name": "Future_1", "objectType": "FUTURETYPE"},
+        {"name": "Untyped"}
+    ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    let results = client.browse_simple().unwrap();
+    
+    let tags: Vec<_> = results.iter().filter(|r| r.is_tag()).filter_map(|r| r.name.as_deref()).collect();
+    assert_eq!(tags, vec!["HMI_Tag_1", "HMI_Struct"]);
+    let alarms: Vec<_> = results.iter().filter(|r| r.is_alarm()).filter_map(|r| r.name.as_deref()).collect();
+    assert_eq!(alarms, vec!["Alarm_1"]);
+    assert_eq!(results[2].object_type, Some(ObjectType::LoggingTag));
+    assert_eq!(results[4].object_type, Some(ObjectType::Other("FUTURETYPE".to_string())));
+    assert_eq!(results[5].object_type, None);
+    assert!(!results[5].is_tag());
+    
+    // Values round-trip unchanged
+    let json = serde_json::to_value(&results[4]).unwrap();
+    assert_eq!(json["objectType"], "FUTURETYPE");
+    let parsed: BrowseTagsResult = serde_json::from_value(json!({"objectType": "ALARMCLASS"})).unwrap();
+    assert_eq!(parsed.object_type.unwrap().as_str(), "ALARMCLASS");
+}
+
+#[tokio::test]
+async fn test_ws_subscribe_with_timeout() {
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, GraphQLWSClient, SubscriptionCallbacks, WinCCError};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let answered = next_json(&mut ws).await;
+        ws.send(Message::Text(json!({"type": "next", "id": answered["id"], "payload": {"data": 1}}).to_string())).await.unwrap();
+        let rejected = next_json(&mut ws).await;
+        ws.send(Message::Text(json!({
+            "type": "error", "id": rejected["id"], "payload": [{"message": "Cannot resolve provided name"}]
+        }).to_string())).await.unwrap();
+        
+        // The silent subscription is unsubscribed after the timeout
+        let silent = next_json(&mut ws).await;
+        let complete = next_json(&mut ws).await;
+        assert_eq!(complete["type"], "complete");
+        assert_eq!(complete["id"], silent["id"]);
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, "token".to_string());
+    ws_client.connect_and_wait(Duration::from_secs(5)).await.unwrap();
+    let variables = || {
+        let mut variables = std::collections::HashMap::new();
+        variables.insert("names".to_string(), json!(["HMI_Tag_1"]));
+        variables
+    };
+    let timeout = Duration::from_secs(5);
+    
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let subscription = ws_client
+        .subscribe_with_timeout(subscriptions::TAG_VALUES.to_string(), variables(), SubscriptionCallbacks::new(move |payload| {
+            let _ = tx.send(payload["data"].clone());
+        }), timeout)
+        .await
+        .unwrap();
+    assert!(subscription.is_active());
+    // The first notification still reaches the callback
+    assert_eq!(tokio::time::timeout(timeout, rx.recv()).await.unwrap(), Some(json!(1)));
+    
+    let result = ws_client
+        .subscribe_with_timeout(subscriptions::TAG_VALUES.to_string(), variables(), SubscriptionCallbacks::new(|_| {}), timeout)
+        .await;
+    match result {
+        Err(WinCCError::WebSocketError(message)) => assert!(message.contains("Cannot resolve provided name"), "{}", message),
+        other => panic!("unexpected result: {:?}", other.map(|s| s.id().to_string())),
+    }
+    
+    let result = ws_client
+        .subscribe_with_timeout(
+            subscriptions::TAG_VALUES.to_string(),
+            variables(),
+            SubscriptionCallbacks::new(|_| {}),
+            Duration::from_millis(200),
+        )
+        .await;
+    match result {
+        Err(WinCCError::WebSocketError(message)) => assert!(message.contains("No response"), "{}", message),
+        other => panic!("unexpected result: {:?}", other.map(|s| s.id().to_string())),
+    }
+    
+    tokio::time::timeout(timeout, server).await.unwrap().unwrap();
+    ws_client.disconnect().await;
+}
+
+#[test]
+fn test_shelve_alarms_for_timespan() {
+    use std::time::Duration;
+    use winccua_graphql_client::MockTransport;
+    
+    let response = json!({"data": {"shelveAlarms": [{"alarmName": "System1::Alarm_1", "error": null}]}});
+    let mock = MockTransport::new()
+        .with_response(response.clone())
+        .with_response(response.clone())
+        .with_response(response.clone())
+        .with_response(response);
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let names = vec!["System1::Alarm_1".to_string()];
+    
+    let results = client.shelve_alarms_for(&names, Duration::from_secs(300)).unwrap();
+    assert_eq!(results[0].alarm_name.as_deref(), Some("System1::Alarm_1"));
+    client.shelve_alarms_for(&names, Duration::from_micros(1_500_900)).unwrap();
+    client.shelve_alarms(&names, Some("PT5M")).unwrap();
+    client.shelve_alarms_simple(&names).unwrap();
+    
+    // A Duration is sent as number of milliseconds, a string unchanged; without a timeout
+    // the variable is omitted
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[0].1["shelveTimeout"], json!(300_000));
+    assert_eq!(requests[1].1["shelveTimeout"], json!(1_500));
+    assert_eq!(requests[2].1["shelveTimeout"], json!("PT5M"));
+    assert_eq!(requests[3].1.get("shelveTimeout"), None);
+}
+
+#[test]
+fn test_introspection() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let schema = json!({
+        "queryType": {"name": "Query"},
+        "mutationType": {"name": "Mutation"},
+        "subscriptionType": {"name": "Subscription"},
+        "types": [
+            {"kind": "OBJECT", "name": "Mutation", "fields": [{"name": "login"}]},
+            {"kind": "OBJECT", "name": "Query", "fields": [{"name": "session"}, {"name": "tagValues"}, {"name": "browse"}]}
+        ],
+        "directives": []
+    });
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"__schema": schema.clone()}}))
+        .with_response(json!({"data": {"__schema": schema.clone()}}))
+        .with_response(json!({"data": {}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    assert_eq!(client.introspect().unwrap(), schema);
+    assert!(mock.requests()[0].0.contains("__schema"));
+    assert_eq!(client.list_query_fields().unwrap(), vec!["session", "tagValues", "browse"]);
+    assert!(matches!(client.introspect(), Err(WinCCError::OperationFailed(_))));
+}
+
+#[tokio::test]
+async fn test_ws_reconnect_replays_subscriptions() {
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, ConnectionState, GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let subscribe = next_json(&mut ws).await;
+        let id = subscribe["id"].clone();
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 1}}).to_string())).await.unwrap();
+        
+        // reconnect() opens a new connection with a new connection_init and replays the subscription
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        let init = next_json(&mut ws).await;
+        assert_eq!(init["type"], "connection_init");
+        assert_eq!(init["payload"]["Authorization"], "Bearer token");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let resubscribe = next_json(&mut ws).await;
+        assert_eq!(resubscribe["id"], id);
+        assert_eq!(resubscribe["payload"], subscribe["payload"]);
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 2}}).to_string())).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, "token".to_string());
+    ws_client.connect_and_wait(Duration::from_secs(5)).await.unwrap();
+    
+    let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+    let mut variables = std::collections::HashMap::new();
+    variables.insert("names".to_string(), json!(["HMI_Tag_1"]));
+    let subscription = ws_client
+        .subscribe(subscriptions::TAG_VALUES.to_string(), variables, SubscriptionCallbacks::new(move |payload| {
+            let _ = tx.send(payload["data"].clone());
+        }))
+        .await
+        .unwrap();
+    
+    let timeout = Duration::from_secs(5);
+    assert_eq!(tokio::time::timeout(timeout, rx.recv()).await.unwrap(), Some(json!(1)));
+    ws_client.reconnect().await.unwrap();
+    assert_eq!(ws_client.connection_state(), ConnectionState::Connected);
+    assert_eq!(tokio::time::timeout(timeout, rx.recv()).await.unwrap(), Some(json!(2)));
+    assert!(subscription.is_active());
+    
+    ws_client.disconnect().await;
+    server.abort();
+}
+
+#[test]
+fn test_gzip_responses_are_decompressed() {
+    use flate2::{write::GzEncoder, Compression};
+    use std::io::{BufRead, BufReader, Read, Write};
+    use std::sync::{Arc, Mutex};
+    
+    // Answers with a gzip encoded body when the request accepts gzip, records Accept-Encoding
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let accept_encodings = Arc::new(Mutex::new(Vec::new()));
+    let thread_accept_encodings = accept_encodings.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming() {
+            let Ok(stream) = stream else { return };
+            let mut reader = BufReader::new(stream);
+            let mut content_length = 0;
+            let mut accept_encoding = String::new();
+            loop {
+                let mut line = String::new();
+                if reader.read_line(&mut line).unwrap_or(0) == 0 || line == "\r\n" {
+                    break;
+                }
+                let lower = line.to_ascii_lowercase();
+                if let Some(value) = lower.strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap_or(0);
+                }
+                if let Some(value) = lower.strip_prefix("accept-encoding:") {
+                    accept_encoding = value.trim().to_string();
+                }
+            }
+            let mut body = vec![0u8; content_length];
+            let _ = reader.read_exact(&mut body);
+            
+            let response = br#"{"data":{"nonce":{"value":"compressed","validFor":60}}}"#;
+            let (encoding, body) = if accept_encoding.contains("gzip") {
+                let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+                encoder.write_all(response).unwrap();
+                ("Content-Encoding: gzip\r\n", encoder.finish().unwrap())
+            } else {
+                ("", response.to_vec())
+            };
+            thread_accept_encodings.lock().unwrap().push(accept_encoding);
+            let stream = reader.get_mut();
+            let _ = write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\n{}Content-Length: {}\r\nConnection: close\r\n\r\n",
+                encoding,
+                body.len()
+            );
+            let _ = stream.write_all(&body);
+        }
+    });
+    
+    let client = WinCCUnifiedClient::new(&url);
+    assert_eq!(client.get_nonce().unwrap().value.as_deref(), Some("compressed"));
+    let client = WinCCUnifiedClient::builder().http_url(&url).compression(false).build().ok().unwrap();
+    assert_eq!(client.get_nonce().unwrap().value.as_deref(), Some("compressed"));
+    
+    let accept_encodings = accept_encodings.lock().unwrap();
+    assert!(accept_encodings[0].contains("gzip") && accept_encodings[0].contains("br"), "{:?}", accept_encodings);
+    assert_eq!(accept_encodings[1], "");
+}
+
+#[test]
+fn test_identity_provider_url_validation() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"identityProviderURL": "https://idp.example.com/umc/login"}}))
+        .with_response(json!({"data": {"identityProviderURL": "/umc/login"}}))
+        .with_response(json!({"data": {"identityProviderURL": "mailto:admin@example.com"}}))
+        .with_response(json!({"data": {"identityProviderURL": null}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    
+    let mut url = client.get_identity_provider_url().unwrap();
+    assert_eq!(url.host_str(), Some("idp.example.com"));
+    url.query_pairs_mut().append_pair("nonce", "a b");
+    assert_eq!(url.as_str(), "https://idp.example.com/umc/login?nonce=a+b");
+    
+    for _ in 0..3 {
+        assert!(matches!(client.get_identity_provider_url(), Err(WinCCError::OperationFailed(_))));
+    }
+}
+
+#[test]
+fn test_swac_login_flow_retries_expired_nonce() {
+    use winccua_graphql_client::{MockTransport, SwacClaim, WinCCError};
+    
+    let swac_session = |code: &str, token: Option<&str>| {
+        json!({"data": {"loginSWAC": {"user": null, "token": token, "expires": null, "error": {"code": code, "description": "Nonce expired"}}}})
+    };
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"identityProviderURL": "https://idp.example.com/umc"}}))
+        .with_response(json!({"data": {"nonce": {"value": "nonce-1", "validFor": 60}}}))
+        .with_response(swac_session("103", None))
+        .with_response(json!({"data": {"nonce": {"value": "nonce-2", "validFor": 60}}}))
+        .with_response(swac_session("0", Some("swac-token")));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    let mut nonces = Vec::new();
+    let session = client
+        .swac_login_flow(|challenge| {
+            assert_eq!(challenge.identity_provider_url.as_str(), "https://idp.example.com/umc");
+            nonces.push(challenge.nonce.clone());
+            Ok(SwacClaim { claim: format!("claim-{}", challenge.nonce), signed_claim: "signed".to_string() })
+        })
+        .unwrap();
+    assert_eq!(session.token.as_deref(), Some("swac-token"));
+    assert_eq!(nonces, vec!["nonce-1", "nonce-2"]);
+    assert_eq!(mock.requests()[4].1["claim"], "claim-nonce-2");
+    
+    // Only one retry; errors of the callback are passed through
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"identityProviderURL": "https://idp.example.com/umc"}}))
+        .with_response(json!({"data": {"nonce": {"value": "nonce-1"}}}))
+        .with_response(swac_session("103", None))
+        .with_response(json!({"data": {"nonce": {"value": "nonce-2"}}}))
+        .with_response(swac_session("103", None));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let result = client.swac_login_flow(|_| Ok(SwacClaim { claim: "c".to_string(), signed_claim: "s".to_string() }));
+    assert_eq!(result.err().and_then(|e| e.code()), Some(103));
+    assert_eq!(mock.pending_responses(), 0);
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"identityProviderURL": "https://idp.example.com/umc"}}))
+        .with_response(json!({"data": {"nonce": {"value": "nonce-1"}}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    let result = client.swac_login_flow(|_| Err(WinCCError::Cancelled));
+    assert!(matches!(result, Err(WinCCError::Cancelled)));
+}
+
+#[test]
+fn test_metrics_hooks() {
+    use std::sync::{Arc, Mutex};
+    use std::time::Duration;
+    use winccua_graphql_client::{Metrics, MockTransport, WinCCError};
+    
+    #[derive(Clone, Default)]
+    struct Recorder(Arc<Mutex<Vec<String>>>);
+    
+    impl Metrics for Recorder {
+        fn on_request(&self, operation: &str) {
+            self.0.lock().unwrap().push(format!("request {}", operation));
+        }
+        
+        fn on_response(&self, operation: &str, _duration: Duration, status: u16) {
+            self.0.lock().unwrap().push(format!("response {} {}", operation, status));
+        }
+        
+        fn on_error(&self, operation: &str, error: &WinCCError) {
+            self.0.lock().unwrap().push(format!("error {} {}", operation, error));
+        }
+    }
+    
+    let recorder = Recorder::default();
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"tagValues": []}}))
+        .with_response(json!({"data": null, "errors": [{"message": "boom"}]}));
+    mock.push_error(WinCCError::OperationFailed("offline".to_string()));
+    let client = WinCCUnifiedClient::builder().transport(mock).metrics(recorder.clone()).build().ok().unwrap();
+    
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    assert!(client.get_nonce().is_err());
+    assert!(client.execute_raw("{ nonce { value } }", None).is_err());
+    
+    assert_eq!(*recorder.0.lock().unwrap(), vec![
+        "request TagValues",
+        "response TagValues 200",
+        "request Nonce",
+        "error Nonce GraphQL error: boom",
+        "request anonymous",
+        "error anonymous Operation failed: offline",
+    ]);
+    
+    // Over HTTP the status of the response is reported
+    let recorder = Recorder::default();
+    let (url, _) = spawn_http_server(vec![(200, r#"{"data":{"nonce":{"value":"abc","validFor":60}}}"#), (503, "down")]);
+    let client = WinCCUnifiedClient::builder().http_url(&url).metrics(recorder.clone()).build().ok().unwrap();
+    client.get_nonce().unwrap();
+    assert!(client.get_nonce().is_err());
+    let events = recorder.0.lock().unwrap();
+    assert_eq!(events[1], "response Nonce 200");
+    assert!(events[3].starts_with("error Nonce Server returned HTTP 503"), "{}", events[3]);
+}
+
+#[test]
+fn test_write_structure() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let browse_struct = json!({"data": {"browse": [
+        {"name": "System1::HMI_Struct.Setpoint", "objectType": "SIMPLETAG"},
+        {"name": "System1::HMI_Struct.Mode", "objectType": "SIMPLETAG"},
+        {"name": "System1::HMI_Struct.Motor", "objectType": "STRUCTURETAG"}
+    ]}});
+    let browse_motor = json!({"data": {"browse": [
+        {"name": "System1::HMI_Struct.Motor.Speed", "objectType": "SIMPLETAG"}
+    ]}});
+    let mock = MockTransport::new()
+        .with_response(browse_struct.clone())
+        .with_response(browse_motor.clone())
+        .with_response(json!({"data": {"writeTagValues": [
+            {"name": "System1::HMI_Struct.Setpoint", "error": null},
+            {"name": "System1::HMI_Struct.Motor.Speed", "error": null}
+        ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    let results = client.write_structure("HMI_Struct", &json!({"Setpoint": 5, "Motor": {"Speed": 1500}})).unwrap();
+    assert_eq!(results.len(), 2);
+    let requests = mock.requests();
+    assert_eq!(requests[2].1["input"], json!([
+        {"name": "System1::HMI_Struct.Setpoint", "value": 5},
+        {"name": "System1::HMI_Struct.Motor.Speed", "value": 1500}
+    ]));
+    
+    // Unknown fields are rejected before anything is written
+    let mock = MockTransport::new().with_response(browse_struct).with_response(browse_motor);
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    match client.write_structure("HMI_Struct", &json!({"Mode": 2, "Motor": {"Torque": 3}})) {
+        Err(WinCCError::InvalidParameter(message)) => assert!(message.contains("Motor.Torque"), "{}", message),
+        other => panic!("unexpected result: {:?}", other),
+    }
+    assert_eq!(mock.requests().len(), 2);
+    
+    assert!(matches!(client.write_structure("HMI_Struct", &json!(5)), Err(WinCCError::InvalidParameter(_))));
+    assert!(client.write_structure("HMI_Struct", &json!({})).unwrap().is_empty());
+}
+
+#[test]
+fn test_direct_read_limits() {
+    use winccua_graphql_client::{MockTransport, WinCCError};
+    
+    let names: Vec<String> = (1..=5).map(|i| format!("HMI_Tag_{}", i)).collect();
+    let values = |names: &[String]| {
+        json!({"data": {"tagValues": names.iter().map(|name| json!({"name": name})).collect::<Vec<_>>()}})
+    };
+    let mock = MockTransport::new()
+        .with_response(values(&names[0..2]))
+        .with_response(values(&names[2..4]))
+        .with_response(values(&names[4..5]))
+        .with_response(values(&names));
+    let client = WinCCUnifiedClient::builder()
+        .transport(mock.clone())
+        .direct_read_chunk_size(2)
+        .max_direct_read_batch(5)
+        .build()
+        .ok()
+        .unwrap();
+    
+    // Direct reads are split into chunks, results keep the order of the names
+    let results = client.get_tag_values(&names, true).unwrap();
+    let result_names: Vec<_> = results.iter().filter_map(|r| r.name.clone()).collect();
+    assert_eq!(result_names, names);
+    // Cached reads stay in one request
+    assert_eq!(client.get_tag_values(&names, false).unwrap().len(), 5);
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 4);
+    assert_eq!(requests[0].1["names"], json!(["HMI_Tag_1", "HMI_Tag_2"]));
+    assert_eq!(requests[2].1["names"], json!(["HMI_Tag_5"]));
+    assert_eq!(requests[2].1["directRead"], true);
+    assert_eq!(requests[3].1["names"].as_array().unwrap().len(), 5);
+    
+    let mut too_many = names.clone();
+    too_many.push("HMI_Tag_6".to_string());
+    assert!(matches!(client.get_tag_values(&too_many, true), Err(WinCCError::InvalidParameter(_))));
+    assert!(matches!(client.get_tag_values_partial(&too_many, true), Err(WinCCError::InvalidParameter(_))));
+    assert_eq!(mock.requests().len(), 4);
+}
+
+#[test]
+fn test_with_token_constructors() {
+    use std::io::{BufRead, BufReader, Write};
+    
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let authorizations = std::sync::Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = authorizations.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(2) {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(": ") {
+                    match name.to_ascii_lowercase().as_str() {
+                        "authorization" => recorded.lock().unwrap().push(value.to_string()),
+                        "content-length" => content_length = value.parse().unwrap(),
+                        _ => {}
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+            let response = r#"{"data":{"tagValues":[]}}"#;
+            let _ = write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+        }
+    });
+    
+    let client = WinCCUnifiedClient::with_token(&url, "token-a");
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    let client = WinCCUnifiedClient::with_token_and_ws(&url, "ws://127.0.0.1:1/graphql", "token-b");
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    
+    assert_eq!(*authorizations.lock().unwrap(), vec!["Bearer token-a", "Bearer token-b"]);
+}
+
+#[test]
+fn test_auto_logoff_deadline() {
+    use std::time::Duration;
+    use winccua_graphql_client::MockTransport;
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {
+            "user": {"name": "operator", "autoLogoffSec": 120},
+            "token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}
+        }}}))
+        .with_response(json!({"data": {"tagValues": []}}))
+        .with_response(json!({"data": {"logout": true}}))
+        .with_response(json!({"data": {"login": {
+            "user": {"name": "engineer", "autoLogoffSec": 0},
+            "token": "def", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}
+        }}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    assert!(client.auto_logoff_at().is_none());
+    
+    client.login("operator", "secret").unwrap();
+    let deadline = client.auto_logoff_at().unwrap();
+    let remaining = client.auto_logoff_remaining().unwrap();
+    assert!(remaining <= Duration::from_secs(120) && remaining > Duration::from_secs(110));
+    
+    std::thread::sleep(Duration::from_millis(20));
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    assert!(client.auto_logoff_at().unwrap() > deadline);
+    
+    client.logout_simple().unwrap();
+    assert!(client.auto_logoff_at().is_none());
+    
+    client.login("engineer", "secret").unwrap();
+    assert!(client.auto_logoff_at().is_none());
+    assert!(client.auto_logoff_remaining().is_none());
+    
+    assert!(WinCCUnifiedClient::builder().transport(MockTransport::new()).prevent_auto_logoff(true).build().is_ok());
+}
+
+#[test]
+fn test_prevent_auto_logoff_extends_before_deadline() {
+    use std::time::{Duration, Instant};
+    use winccua_graphql_client::MockTransport;
+    
+    // The session itself is valid for long, only the auto-logoff deadline requires an extension
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {
+            "user": {"name": "operator", "autoLogoffSec": 2},
+            "token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}
+        }}}))
+        .with_response(json!({"data": {"extendSession": {
+            "user": {"name": "operator", "autoLogoffSec": 2},
+            "token": "def", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}
+        }}}));
+    let client = WinCCUnifiedClient::builder()
+        .transport(mock.clone())
+        .prevent_auto_logoff(true)
+        .build()
+        .unwrap();
+    client.login("operator", "secret").unwrap();
+    let deadline = client.auto_logoff_at().unwrap();
+    client.enable_auto_extend().unwrap();
+    
+    let started = Instant::now();
+    while mock.requests().len() < 2 && started.elapsed() < Duration::from_secs(5) {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+    assert!(chrono::Utc::now() < deadline, "extension sent after the auto-logoff deadline");
+    let requests = mock.requests();
+    assert_eq!(requests.len(), 2);
+    assert!(requests[1].0.contains("extendSession"));
+    assert!(client.auto_logoff_at().unwrap() > deadline);
+    client.disable_auto_extend();
+}
+
+#[test]
+fn test_subscription_reason_filter() {
+    use winccua_graphql_client::{NotificationReason, SubscriptionCallbacks, TagValueNotification, TypedSubscriptionCallbacks};
+    
+    let notification = |name: &str, reason: &str| json!({"data": {"tagValues": {"name": name, "notificationReason": reason}}});
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let callbacks = SubscriptionCallbacks::new(move |payload| tx.send(payload["data"]["tagValues"]["name"].clone()).unwrap())
+        .with_reason_filter([NotificationReason::Modified, NotificationReason::Removed]);
+    (callbacks.on_data)(notification("HMI_Tag_1", "Added"));
+    (callbacks.on_data)(notification("HMI_Tag_2", "Modified"));
+    (callbacks.on_data)(notification("HMI_Tag_3", "Removed"));
+    (callbacks.on_data)(notification("HMI_Tag_4", "Removed (Name changed)"));
+    (callbacks.on_data)(json!({"data": {"tagValues": {"name": "HMI_Tag_5"}}}));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec![json!("HMI_Tag_2"), json!("HMI_Tag_3"), json!("HMI_Tag_5")]);
+    
+    let (tx, rx) = std::sync::mpsc::channel();
+    let callbacks = TypedSubscriptionCallbacks::<TagValueNotification>::new(move |n| tx.send(n.name.unwrap_or_default()).unwrap())
+        .with_reason_filter([NotificationReason::Modified])
+        .into_callbacks("tagValues");
+    (callbacks.on_data)(notification("HMI_Tag_1", "Added"));
+    (callbacks.on_data)(notification("HMI_Tag_2", "Modified"));
+    assert_eq!(rx.try_iter().collect::<Vec<_>>(), vec!["HMI_Tag_2"]);
+}
+
+#[test]
+fn test_ws_channels() {
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{subscriptions, SubscriptionCallbacks};
+    
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = runtime.spawn(async move {
+        let mut alarms = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut alarms).await["type"], "connection_init");
+        alarms.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let mut tags = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut tags).await["type"], "connection_init");
+        tags.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        let subscribe = next_json(&mut tags).await;
+        assert!(subscribe["payload"]["query"].as_str().unwrap().contains("tagValues"));
+        let id = subscribe["id"].clone();
+        tags.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": 1}}).to_string())).await.unwrap();
+        
+        // A token change reaches both connections, each reconnects with the new token
+        for _ in 0..2 {
+            let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+            let init = next_json(&mut ws).await;
+            assert_eq!(init["payload"]["Authorization"], "Bearer new-token");
+        }
+    });
+    
+    let mut client = WinCCUnifiedClient::new_with_ws("http://localhost:1/graphql", &url);
+    runtime.block_on(async {
+        client.connect_ws_and_wait(Duration::from_secs(5)).await.unwrap();
+        
+        let (tx, mut rx) = tokio::sync::mpsc::unbounded_channel();
+        let mut variables = std::collections::HashMap::new();
+        variables.insert("names".to_string(), json!(["HMI_Tag_1"]));
+        let channel = client.connect_ws_channel("tags").await.unwrap();
+        channel
+            .subscribe(subscriptions::TAG_VALUES.to_string(), variables, SubscriptionCallbacks::new(move |payload| {
+                let _ = tx.send(payload["data"].clone());
+            }))
+            .await
+            .unwrap();
+        assert_eq!(tokio::time::timeout(Duration::from_secs(5), rx.recv()).await.unwrap(), Some(json!(1)));
+        assert_eq!(client.ws_channel_names(), vec!["tags"]);
+        assert!(client.ws_channel("other").is_none());
+        
+        client.set_token("new-token");
+        tokio::time::timeout(Duration::from_secs(5), server).await.unwrap().unwrap();
+        
+        client.disconnect_ws_channel("tags").await;
+        assert!(client.ws_channel("tags").is_none());
+        client.disconnect_ws().await;
+    });
+}
+
+#[test]
+fn test_logged_tag_values_by_tag() {
+    use winccua_graphql_client::{BoundingMode, MockTransport, SortingMode, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"browse": [
+            {"name": "System1::HMI_Tag_1:LoggingTag_1", "objectType": "LOGGINGTAG"},
+            {"name": "System1::HMI_Tag_1:LoggingTag_2", "objectType": "LOGGINGTAG"}
+        ]}}))
+        .with_response(json!({"data": {"browse": [
+            {"name": "System1::HMI_Tag_2:LoggingTag_1", "objectType": "LOGGINGTAG"}
+        ]}}))
+        .with_response(json!({"data": {"loggedTagValues": [
+            {"loggingTagName": "System1::HMI_Tag_1:LoggingTag_1", "values": [{"value": {"value": 1}}]},
+            {"loggingTagName": "System1::HMI_Tag_1:LoggingTag_2", "values": [{"value": {"value": 2}}]},
+            {"values": []}
+        ]}}))
+        .with_response(json!({"data": {"browse": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    
+    let names = vec!["HMI_Tag_1".to_string(), "HMI_Tag_2".to_string()];
+    let results = client
+        .get_logged_tag_values_by_tag(&names, None, None, 100, SortingMode::TimeAsc, BoundingMode::None)
+        .unwrap();
+    assert_eq!(results.len(), 3);
+    assert_eq!(results["System1::HMI_Tag_1:LoggingTag_2"].values.as_ref().unwrap().len(), 1);
+    assert!(results["System1::HMI_Tag_2:LoggingTag_1"].values.as_ref().unwrap().is_empty());
+    
+    let requests = mock.requests();
+    assert_eq!(requests[2].1["names"], json!([
+        "System1::HMI_Tag_1:LoggingTag_1",
+        "System1::HMI_Tag_1:LoggingTag_2",
+        "System1::HMI_Tag_2:LoggingTag_1"
+    ]));
+    
+    let err = client
+        .get_logged_tag_values_by_tag(&["HMI_Tag_3".to_string()], None, None, 100, SortingMode::TimeAsc, BoundingMode::None)
+        .unwrap_err();
+    assert!(matches!(err, WinCCError::TagError(msg) if msg.contains("HMI_Tag_3")));
+    assert_eq!(mock.pending_responses(), 0);
+}
+
+#[test]
+fn test_types_compare_and_hash() {
+    use std::collections::HashSet;
+    use winccua_graphql_client::{AlarmIdentifierInput, TagValueInput, TagValueResult};
+    
+    assert_eq!(TagValueInput::new("HMI_Tag_1", 42), TagValueInput::new("HMI_Tag_1", json!(42)));
+    assert_ne!(TagValueInput::new("HMI_Tag_1", 42), TagValueInput::new("HMI_Tag_1", 43));
+    
+    let identifiers: HashSet<AlarmIdentifierInput> = [
+        AlarmIdentifierInput::new("Alarm_1"),
+        AlarmIdentifierInput::with_instance("Alarm_1", 2),
+        AlarmIdentifierInput::from("Alarm_1"),
+        AlarmIdentifierInput::from(("Alarm_1", 2)),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(identifiers.len(), 2);
+    
+    let parse = || serde_json::from_value::<TagValueResult>(json!({"name": "HMI_Tag_1", "value": {"value": 1.5}})).unwrap();
+    assert_eq!(parse(), parse());
+}
+
+#[test]
+#[allow(clippy::result_large_err)]
+fn test_user_agent() {
+    use std::io::{BufRead, BufReader, Write};
+    use std::sync::{Arc, Mutex};
+    use tokio_tungstenite::tungstenite::handshake::server::{Request, Response};
+    use winccua_graphql_client::DEFAULT_USER_AGENT;
+    
+    // HTTP server recording the User-Agent of each request
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let http_url = format!("http://{}/graphql", listener.local_addr().unwrap());
+    let user_agents = Arc::new(Mutex::new(Vec::new()));
+    let recorded = user_agents.clone();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().take(2) {
+            let mut reader = BufReader::new(stream.unwrap());
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some((name, value)) = line.split_once(": ") {
+                    match name.to_ascii_lowercase().as_str() {
+                        "user-agent" => recorded.lock().unwrap().push(value.to_string()),
+                        "content-length" => content_length = value.parse().unwrap(),
+                        _ => {}
+                    }
+                }
+            }
+            let mut body = vec![0; content_length];
+            std::io::Read::read_exact(&mut reader, &mut body).unwrap();
+            let response = r#"{"data":{"tagValues":[]}}"#;
+            let _ = write!(
+                reader.get_mut(),
+                "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+                response.len(),
+                response
+            );
+        }
+    });
+    
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let ws_listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let ws_url = format!("ws://{}/graphql", ws_listener.local_addr().unwrap());
+    let server = runtime.spawn(async move {
+        let (stream, _) = ws_listener.accept().await.unwrap();
+        let mut user_agent = None;
+        let _ws = tokio_tungstenite::accept_hdr_async(stream, |request: &Request, mut response: Response| {
+            user_agent = request.headers().get("user-agent").map(|value| value.to_str().unwrap().to_string());
+            response.headers_mut().insert("Sec-WebSocket-Protocol", "graphql-transport-ws".parse().unwrap());
+            Ok(response)
+        })
+        .await
+        .unwrap();
+        user_agent
+    });
+    
+    let client = WinCCUnifiedClient::new(&http_url);
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    let mut client = WinCCUnifiedClient::builder()
+        .http_url(&http_url)
+        .ws_url(&ws_url)
+        .user_agent("my-dashboard/1.2.0")
+        .build()
+        .ok()
+        .unwrap();
+    client.get_tag_values_simple(&["HMI_Tag_1".to_string()]).unwrap();
+    assert_eq!(*user_agents.lock().unwrap(), vec![DEFAULT_USER_AGENT.to_string(), "my-dashboard/1.2.0".to_string()]);
+    assert!(DEFAULT_USER_AGENT.starts_with("winccua-graphql-client/"));
+    
+    runtime.block_on(async {
+        client.connect_ws().await.unwrap();
+        assert_eq!(server.await.unwrap().as_deref(), Some("my-dashboard/1.2.0"));
+        client.disconnect_ws().await;
+    });
+}
+
+#[test]
+fn test_write_tag_values_checked() {
+    use winccua_graphql_client::{MockTransport, TagValueInput, WinCCError};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"writeTagValues": [
+            {"name": "HMI_Tag_1", "error": {"code": "0", "description": ""}},
+            {"name": "HMI_Tag_2", "error": null}
+        ]}}))
+        .with_response(json!({"data": {"writeTagValues": [
+            {"name": "HMI_Tag_1", "error": null},
+            {"name": "HMI_Tag_2", "error": {"code": "2", "description": "Cannot resolve provided name"}},
+            {"name": "HMI_Tag_3", "error": {"code": "201", "description": "Cannot convert provided value to data type"}}
+        ]}}))
+        .with_response(json!({"data": {"writeTagValues": []}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    
+    let inputs = vec![TagValueInput::new("HMI_Tag_1", 1), TagValueInput::new("HMI_Tag_2", 2)];
+    client.write_tag_values_checked(&inputs, None, None).unwrap();
+    
+    let inputs = vec![TagValueInput::new("HMI_Tag_1", 1), TagValueInput::new("HMI_Tag_2", 2), TagValueInput::new("HMI_Tag_3", "x")];
+    let err = client.write_tag_values_checked(&inputs, None, None).unwrap_err();
+    assert!(matches!(&err, WinCCError::TagError(msg) if msg == "2 of 3 tag writes failed: \
+        HMI_Tag_2: Cannot resolve provided name (code 2); HMI_Tag_3: Cannot convert provided value to data type (code 201)"));
+    
+    let err = client.write_tag_values_checked(&inputs, None, None).unwrap_err();
+    assert!(matches!(&err, WinCCError::TagError(msg) if msg.contains("Expected 3 write results, got 0")));
+}
+
+#[test]
+fn test_get_alarm_classes() {
+    use winccua_graphql_client::{AlarmClass, MockTransport};
+    
+    let mock = MockTransport::new().with_response(json!({"data": {"browse": [
+        {"name": "System1::Alarm", "displayName": "Alarm", "objectType": "ALARMCLASS"},
+        {"name": "System1::Warning", "displayName": "Warnung", "objectType": "ALARMCLASS"},
+        {"displayName": "Unnamed", "objectType": "ALARMCLASS"}
+    ]}}));
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    client.set_browse_language("de-DE");
+    
+    let classes = client.get_alarm_classes().unwrap();
+    assert_eq!(classes, vec![
+        AlarmClass { name: "System1::Alarm".to_string(), display_name: Some("Alarm".to_string()) },
+        AlarmClass { name: "System1::Warning".to_string(), display_name: Some("Warnung".to_string()) },
+    ]);
+    assert_eq!(classes[1].short_name(), "Warning");
+    
+    let variables = &mock.requests()[0].1;
+    assert_eq!(variables["objectTypeFilters"], json!(["ALARMCLASS"]));
+    assert_eq!(variables["language"], "de-DE");
+}
+
+#[tokio::test]
+async fn test_ws_max_message_size() {
+    use futures_util::{SinkExt, StreamExt};
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, WinCCError};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let id = next_json(&mut ws).await["id"].clone();
+        let large = "x".repeat(4096);
+        ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": {"tagValues": {"value": large}}}}).to_string())).await.unwrap();
+        tokio::time::sleep(std::time::Duration::from_secs(30)).await;
+    });
+    
+    let mut ws_client = GraphQLWSClient::new(url, String::new())
+        .with_max_message_size(1024);
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    
+    let mut stream = ws_client.subscribe_stream("subscription { tagValues }".to_string(), Default::default()).await.unwrap();
+    let first = tokio::time::timeout(std::time::Duration::from_secs(5), stream.next()).await.unwrap();
+    assert!(matches!(first, Some(Err(WinCCError::WebSocketError(message))) if message.contains("exceeds the limit of 1024 bytes")));
+    
+    ws_client.disconnect().await;
+}
+
+#[tokio::test]
+async fn test_ws_unsubscribe_all() {
+    use futures_util::SinkExt;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::Arc;
+    use tokio_tungstenite::tungstenite::Message;
+    use winccua_graphql_client::{GraphQLWSClient, SubscriptionCallbacks};
+    
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let server = tokio::spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        assert_eq!(next_json(&mut ws).await["type"], "connection_init");
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        
+        let mut ids = Vec::new();
+        for _ in 0..2 {
+            let id = next_json(&mut ws).await["id"].clone();
+            ws.send(Message::Text(json!({"type": "next", "id": id, "payload": {"data": {"tagValues": {}}}}).to_string())).await.unwrap();
+            ids.push(id);
+        }
+        
+        let mut completed = Vec::new();
+        for _ in 0..2 {
+            let complete = next_json(&mut ws).await;
+            assert_eq!(complete["type"], "complete");
+            completed.push(complete["id"].clone());
+        }
+        completed.sort_by_key(|id| id.to_string());
+        assert_eq!(completed, ids);
+    });
+    
+    let data = Arc::new(AtomicUsize::new(0));
+    let completed = Arc::new(AtomicUsize::new(0));
+    let mut ws_client = GraphQLWSClient::new(url, "token".to_string());
+    ws_client.connect_and_wait(std::time::Duration::from_secs(5)).await.unwrap();
+    let mut subscriptions = Vec::new();
+    for _ in 0..2 {
+        let (data_cb, completed_cb) = (data.clone(), completed.clone());
+        let callbacks = SubscriptionCallbacks::new(move |_| {
+            data_cb.fetch_add(1, Ordering::SeqCst);
+        })
+        .with_complete(move || {
+            completed_cb.fetch_add(1, Ordering::SeqCst);
+        });
+        subscriptions.push(ws_client
+            .subscribe("subscription { tagValues }".to_string(), Default::default(), callbacks)
+            .await
+            .unwrap());
+    }
+    
+    while data.load(Ordering::SeqCst) < 2 {
+        tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+    }
+    ws_client.unsubscribe_all().await.unwrap();
+    assert_eq!(completed.load(Ordering::SeqCst), 2);
+    assert!(subscriptions.iter().all(|subscription| !subscription.is_active()));
+    
+    server.await.unwrap();
+    ws_client.disconnect().await;
+}
+
+#[test]
+fn test_auth_method() {
+    use winccua_graphql_client::{AuthMethod, MockTransport};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {"token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}}))
+        .with_response(json!({"data": {"extendSession": {"token": "def", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}}))
+        .with_response(json!({"data": {"logout": true}}))
+        .with_response(json!({"data": {"loginSWAC": {"token": "ghi", "error": null}}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    assert_eq!(client.auth_method(), None);
+    
+    client.login("username", "password").unwrap();
+    assert_eq!(client.auth_method(), Some(AuthMethod::Password));
+    client.extend_session().unwrap();
+    assert_eq!(client.auth_method(), Some(AuthMethod::Password));
+    client.logout_simple().unwrap();
+    assert_eq!(client.auth_method(), None);
+    
+    client.login_swac("claim", "signed").unwrap();
+    assert_eq!(client.auth_method(), Some(AuthMethod::Swac));
+    client.set_token("jkl");
+    assert_eq!(client.auth_method(), Some(AuthMethod::Preset));
+    client.clear_token();
+    assert_eq!(client.auth_method(), None);
+    
+    let client = WinCCUnifiedClient::with_token("http://localhost/graphql", "token");
+    assert_eq!(client.auth_method(), Some(AuthMethod::Preset));
+}
+
+#[test]
+fn test_ws_init_params() {
+    use futures_util::SinkExt;
+    use std::time::Duration;
+    use tokio_tungstenite::tungstenite::Message;
+    
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    let listener = runtime.block_on(tokio::net::TcpListener::bind("127.0.0.1:0")).unwrap();
+    let url = format!("ws://{}/graphql", listener.local_addr().unwrap());
+    let _server = runtime.spawn(async move {
+        let mut ws = accept_ws(&listener, "graphql-transport-ws").await;
+        let init = next_json(&mut ws).await;
+        assert_eq!(init["payload"], json!({
+            "Authorization": "Bearer token",
+            "x-api-key": "secret",
+            "tenantId": "tenant-1",
+            "locale": "de-DE"
+        }));
+        ws.send(Message::Text(json!({"type": "connection_ack"}).to_string())).await.unwrap();
+        tokio::time::sleep(Duration::from_secs(30)).await;
+    });
+    
+    let mut client = WinCCUnifiedClient::with_token_and_ws("http://localhost:1/graphql", &url, "token");
+    client.set_header("X-Api-Key", "secret").unwrap();
+    // Entries set on a clone are shared
+    client.clone().set_ws_init_param("tenantId", "tenant-1");
+    client.set_ws_init_param("locale", "en-US");
+    client.set_ws_init_param("locale", "de-DE");
+    client.set_ws_init_param("obsolete", "value");
+    client.remove_ws_init_param("obsolete");
+    runtime.block_on(async {
+        // The server only acknowledges a matching payload
+        client.connect_ws_and_wait(Duration::from_secs(5)).await.unwrap();
+        client.disconnect_ws().await;
+    });
+}
+
+#[test]
+fn test_alarm_thresholds() {
+    use winccua_graphql_client::{ActiveAlarm, LoggedAlarm};
+    
+    let alarm: ActiveAlarm = serde_json::from_value(json!({"name": "Alarm_1", "valueLimit": 80, "deadBand": " 0.5 "})).unwrap();
+    assert_eq!(alarm.value_limit_f64(), Some(80.0));
+    assert_eq!(alarm.dead_band_f64(), Some(0.5));
+    
+    let alarm: ActiveAlarm = serde_json::from_value(json!({"name": "Alarm_2", "valueLimit": "high", "deadBand": null})).unwrap();
+    assert_eq!(alarm.value_limit_f64(), None);
+    assert_eq!(alarm.value_limit, Some(json!("high")));
+    assert_eq!(alarm.dead_band_f64(), None);
+    
+    let alarm: LoggedAlarm = serde_json::from_value(json!({"name": "Alarm_1", "valueLimit": 12.5, "deadband": "2"})).unwrap();
+    assert_eq!(alarm.value_limit_f64(), Some(12.5));
+    assert_eq!(alarm.dead_band_f64(), Some(2.0));
+    
+    let alarm: LoggedAlarm = serde_json::from_value(json!({"name": "Alarm_2", "deadband": "none"})).unwrap();
+    assert_eq!(alarm.dead_band_f64(), None);
+}
+
+#[test]
+fn test_login_scoped() {
+    use winccua_graphql_client::{AuthMethod, MockTransport};
+    
+    let login = json!({"data": {"login": {"token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}});
+    let logout = json!({"data": {"logout": true}});
+    let mock = MockTransport::new()
+        .with_response(login.clone())
+        .with_response(logout.clone())
+        .with_response(login.clone())
+        .with_response(logout.clone())
+        .with_response(login.clone())
+        .with_response(logout.clone())
+        .with_response(login.clone())
+        .with_response(logout.clone());
+    let client = WinCCUnifiedClient::with_transport(mock.clone());
+    let logouts = || mock.requests().iter().filter(|(query, _)| query.contains("logout")).count();
+    
+    // Dropping the guard logs out
+    {
+        let guard = client.login_scoped("username", "password").unwrap();
+        assert_eq!(guard.session().token.as_deref(), Some("abc"));
+        assert_eq!(client.auth_method(), Some(AuthMethod::Password));
+    }
+    assert_eq!(logouts(), 1);
+    assert_eq!(client.auth_method(), None);
+    
+    assert!(client.login_scoped("username", "password").unwrap().close().unwrap());
+    assert_eq!(logouts(), 2);
+    
+    // Nothing is sent if the client was logged out already
+    let guard = client.login_scoped("username", "password").unwrap();
+    client.logout_simple().unwrap();
+    assert!(!guard.close().unwrap());
+    assert_eq!(logouts(), 3);
+    
+    // Inside a runtime the logout runs on the blocking pool
+    let runtime = tokio::runtime::Runtime::new().unwrap();
+    runtime.block_on(async {
+        drop(client.login_scoped("username", "password").unwrap());
+        for _ in 0..500 {
+            if logouts() == 4 {
+                break;
+            }
+            tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+        }
+    });
+    assert_eq!(logouts(), 4);
+    assert_eq!(mock.pending_responses(), 0);
+}
+
+#[test]
+fn test_int64_precision() {
+    use winccua_graphql_client::TagValue;
+    
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"writeTagValues":[{"name":"HMI_Counter","error":{"code":"0"}}]}}"#),
+        (200, r#"{"data":{"tagValues":[{"name":"HMI_Counter","value":{"value":9007199254740993}}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    
+    // 2^53 + 1 is not representable as f64
+    client.write_tag("HMI_Counter", 9007199254740993u64).unwrap();
+    let written = requests.lock().unwrap()[0]["variables"]["input"][0]["value"].clone();
+    assert_eq!(written.to_string(), "9007199254740993");
+    
+    let values = client.get_tag_values_simple(&["HMI_Counter".to_string()]).unwrap();
+    let value = values[0].value.as_ref().unwrap();
+    assert_eq!(value.as_u64(), Some(9007199254740993));
+    assert_eq!(value.as_i64(), Some(9007199254740993));
+    assert_eq!(value.as_i128(), Some(9007199254740993));
+    
+    let value = |value| TagValue { value: Some(value), timestamp: None, quality: None };
+    assert_eq!(value(json!(u64::MAX)).as_i128(), Some(i128::from(u64::MAX)));
+    assert_eq!(value(json!(u64::MAX)).as_i64(), None);
+    assert_eq!(value(json!(i64::MIN)).as_i128(), Some(i128::from(i64::MIN)));
+    assert_eq!(value(json!(i64::MIN)).as_u64(), None);
+    assert_eq!(value(json!("18446744073709551615")).as_i128(), Some(i128::from(u64::MAX)));
+    assert_eq!(value(json!(1.5)).as_i128(), None);
+}
+
+#[test]
+fn test_custom_queries() {
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"activeAlarms":[{"name":"System1::Alarm_1","priority":10}]}}"#),
+        (200, r#"{"data":{"tagValues":[{"name":"HMI_Tag_1","value":{"value":21.5}}]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    let languages = vec!["en-US".to_string()];
+    
+    let query = "query ActiveAlarms($systemNames: [String], $filterString: String, $filterLanguage: String, $languages: [String]) { activeAlarms(systemNames: $systemNames, filterString: $filterString, filterLanguage: $filterLanguage, languages: $languages) { name priority } }";
+    let alarms = client.get_active_alarms_with_query(query, &[], "priority > 5", "en-US", &languages).unwrap();
+    assert_eq!(alarms[0].name.as_deref(), Some("System1::Alarm_1"));
+    assert_eq!(alarms[0].priority, Some(10));
+    assert_eq!(alarms[0].raise_time, None);
+    
+    let query = "query TagValues($names: [String!]!, $directRead: Boolean) { tagValues(names: $names, directRead: $directRead) { name value { value } } }";
+    let values = client.get_tag_values_with_query(query, &["HMI_Tag_1".to_string()], false).unwrap();
+    assert_eq!(values[0].value.as_ref().unwrap().as_f64(), Some(21.5));
+    
+    let requests = requests.lock().unwrap();
+    assert!(requests[0]["query"].as_str().unwrap().contains("{ name priority }"));
+    assert_eq!(requests[0]["variables"]["filterString"], "priority > 5");
+    assert!(requests[1]["query"].as_str().unwrap().contains("value { value }"));
+    assert_eq!(requests[1]["variables"]["names"], json!(["HMI_Tag_1"]));
+}
+
+#[test]
+fn test_logged_tag_values_bounding_and_sorting() {
+    use winccua_graphql_client::{BoundingMode, SortingMode, WinCCError};
+    
+    let (url, requests) = spawn_http_server(vec![
+        (200, r#"{"data":{"loggedTagValues":[]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[]}}"#),
+        (200, r#"{"data":{"loggedTagValues":[]}}"#),
+    ]);
+    let client = WinCCUnifiedClient::new(&url);
+    let names = vec!["Tag:Log".to_string()];
+    let (start, end) = (Some("2024-01-01T00:00:00.000Z"), Some("2024-01-02T00:00:00.000Z"));
+    let invalid = |result| matches!(result, Err(WinCCError::InvalidParameter(_)));
+    
+    // A one-sided window defines the search direction and needs a limit
+    assert!(invalid(client.get_logged_tag_values(&names, start, None, 10, SortingMode::TimeDesc, BoundingMode::None)));
+    assert!(invalid(client.get_logged_tag_values(&names, None, end, 10, SortingMode::TimeAsc, BoundingMode::None)));
+    assert!(invalid(client.get_logged_tag_values(&names, start, None, 0, SortingMode::TimeAsc, BoundingMode::Left)));
+    assert!(invalid(client.get_logged_tag_values(&names, None, end, 0, SortingMode::TimeDesc, BoundingMode::Right)));
+    assert!(requests.lock().unwrap().is_empty());
+    
+    // Bounding values work with any sorting mode and limit
+    client.get_logged_tag_values(&names, start, end, 100, SortingMode::TimeAsc, BoundingMode::LeftRight).unwrap();
+    client.get_logged_tag_values(&names, start, end, 100, SortingMode::TimeDesc, BoundingMode::LeftRight).unwrap();
+    client.get_logged_tag_values(&names, None, end, 10, SortingMode::TimeDesc, BoundingMode::Left).unwrap();
+    
+    let requests = requests.lock().unwrap();
+    assert_eq!(requests[0]["variables"]["boundingValuesMode"], "LEFTRIGHT_BOUNDING_VALUES");
+    assert_eq!(requests[1]["variables"]["sortingMode"], "TIME_DESC");
+    assert_eq!(requests[2]["variables"]["maxNumberOfValues"], 10);
+    assert!(requests[2]["variables"].get("startTime").is_none());
+}
+
+#[test]
+fn test_failed_logout_keeps_auto_extend() {
+    use winccua_graphql_client::{AuthMethod, MockTransport};
+    
+    let mock = MockTransport::new()
+        .with_response(json!({"data": {"login": {"token": "abc", "expires": "2099-01-01T00:00:00.000Z", "error": {"code": "0"}}}}))
+        .with_response(json!({"errors": [{"message": "Service unavailable"}]}))
+        .with_response(json!({"data": {"logout": true}}));
+    let client = WinCCUnifiedClient::with_transport(mock);
+    client.login("username", "password").unwrap();
+    client.enable_auto_extend().unwrap();
+    
+    // The session is still valid after a failed logout, so it keeps being extended
+    assert!(client.logout_simple().is_err());
+    assert!(client.is_auto_extend_enabled());
+    assert_eq!(client.auth_method(), Some(AuthMethod::Password));
+    
+    assert!(client.logout_simple().unwrap());
+    assert!(!client.is_auto_extend_enabled());
+    assert_eq!(client.auth_method(), None);
+}